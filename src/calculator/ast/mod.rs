@@ -0,0 +1,204 @@
+//! AST submodule.
+//!
+//! The syntax tree of an expression, built by the `parser` submodule. Every node keeps the byte range
+//! of its source, so that the evaluation errors can point at the offending part of the input.
+
+use std::{fmt, ops::Range};
+
+/// A unary prefix operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Prefix {
+    Plus,
+    Minus,
+    /// `~x`, the bitwise complement of the programmer mode.
+    Not,
+}
+
+/// A binary infix operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Infix {
+    Add,
+    Subtract,
+    Multiply,
+    /// The multiplication without operator, e.g. `2pi` or `3(x+1)`.
+    ImplicitMultiply,
+    Divide,
+    Remainder,
+    Power,
+    BitAnd,
+    BitOr,
+    /// `x ^ y` in the programmer mode, where `**` is the power.
+    BitXor,
+    ShiftLeft,
+    ShiftRight,
+}
+
+impl Infix {
+    /// Whether the operator is one of the bitwise operators of the programmer mode.
+    pub fn is_bitwise(&self) -> bool {
+        matches!(
+            self,
+            Infix::BitAnd | Infix::BitOr | Infix::BitXor | Infix::ShiftLeft | Infix::ShiftRight
+        )
+    }
+}
+
+/// A unary postfix operator.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Postfix {
+    /// `x!`, defined for the non-negative integers.
+    Factorial,
+    /// `x%`, i.e. `x / 100`.
+    Percent,
+}
+
+/// The kind of a syntax tree node.
+#[derive(Clone, Debug, PartialEq)]
+pub enum NodeKind {
+    /// A decimal literal, evaluated in the arithmetic mode of the session.
+    Number(String),
+    Variable(String),
+    Prefix(Prefix, Box<Node>),
+    Infix(Infix, Box<Node>, Box<Node>),
+    Postfix(Postfix, Box<Node>),
+    Call(String, Vec<Node>),
+    /// A string, only as an argument of `load`, e.g. `"latency.csv"`.
+    Text(String),
+    /// A matrix literal, by rows of the same length, e.g. `[1, 2; 3, 4]`.
+    Matrix(Vec<Vec<Node>>),
+    /// The conversion of a quantity to a unit, e.g. `3 km in mi`.
+    Convert(Box<Node>, Box<Node>),
+    /// An equation, only as the first argument of `solve`, e.g. `x^2 = 2`;
+    /// it evaluates to the difference of its sides.
+    Equation(Box<Node>, Box<Node>),
+}
+
+/// A syntax tree node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Node {
+    pub kind: NodeKind,
+    /// The byte range of the node in the expression.
+    pub span: Range<usize>,
+}
+
+impl Node {
+    /// Node constructor.
+    pub fn new(kind: NodeKind, span: Range<usize>) -> Node {
+        Node { kind, span }
+    }
+
+    /// The child nodes.
+    pub fn children(&self) -> Vec<&Node> {
+        match &self.kind {
+            NodeKind::Number(_) | NodeKind::Variable(_) | NodeKind::Text(_) => vec![],
+            NodeKind::Prefix(_, operand) | NodeKind::Postfix(_, operand) => vec![operand],
+            NodeKind::Infix(_, left, right)
+            | NodeKind::Convert(left, right)
+            | NodeKind::Equation(left, right) => vec![left, right],
+            NodeKind::Call(_, args) => args.iter().collect(),
+            NodeKind::Matrix(rows) => rows.iter().flatten().collect(),
+        }
+    }
+
+    /// The names of the functions called by the node and its descendants.
+    pub fn calls(&self) -> Vec<&str> {
+        let mut calls = vec![];
+        if let NodeKind::Call(name, _) = &self.kind {
+            calls.push(name.as_str());
+        }
+        for child in self.children() {
+            calls.extend(child.calls());
+        }
+        calls
+    }
+
+    /// The tree drawing of the node, one node per line, e.g. for `2+3*4`:
+    ///
+    /// ```text
+    /// +
+    /// ├── 2
+    /// └── *
+    ///     ├── 3
+    ///     └── 4
+    /// ```
+    pub fn tree(&self) -> String {
+        let mut tree = String::new();
+        self.draw(&mut tree, "", "");
+        tree
+    }
+
+    /// Draws the node with the prefix of its line, and the prefix of the lines of its children.
+    fn draw(&self, tree: &mut String, prefix: &str, children_prefix: &str) {
+        tree.push_str(prefix);
+        tree.push_str(&self.label());
+        tree.push('\n');
+
+        let children = self.children();
+        for (index, child) in children.iter().enumerate() {
+            let (branch, indent) = if index + 1 == children.len() {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            child.draw(
+                tree,
+                &format!("{}{}", children_prefix, branch),
+                &format!("{}{}", children_prefix, indent),
+            );
+        }
+    }
+
+    /// The label of the node in the tree drawing.
+    fn label(&self) -> String {
+        match &self.kind {
+            NodeKind::Number(literal) => literal.clone(),
+            NodeKind::Variable(name) => name.clone(),
+            NodeKind::Prefix(operator, _) => format!("{} (prefix)", operator),
+            NodeKind::Infix(Infix::ImplicitMultiply, _, _) => String::from("* (implicit)"),
+            NodeKind::Infix(operator, _, _) => operator.to_string(),
+            NodeKind::Postfix(operator, _) => format!("{} (postfix)", operator),
+            NodeKind::Call(name, _) => format!("{}()", name),
+            NodeKind::Text(text) => format!("{:?}", text),
+            NodeKind::Matrix(rows) => format!("matrix {}x{}", rows.len(), rows[0].len()),
+            NodeKind::Convert(_, _) => String::from("in"),
+            NodeKind::Equation(_, _) => String::from("="),
+        }
+    }
+}
+
+impl fmt::Display for Prefix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Prefix::Plus => write!(f, "+"),
+            Prefix::Minus => write!(f, "-"),
+            Prefix::Not => write!(f, "~"),
+        }
+    }
+}
+
+impl fmt::Display for Infix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Infix::Add => write!(f, "+"),
+            Infix::Subtract => write!(f, "-"),
+            Infix::Multiply | Infix::ImplicitMultiply => write!(f, "*"),
+            Infix::Divide => write!(f, "/"),
+            Infix::Remainder => write!(f, "%"),
+            Infix::Power => write!(f, "^"),
+            Infix::BitAnd => write!(f, "&"),
+            Infix::BitOr => write!(f, "|"),
+            Infix::BitXor => write!(f, "xor"),
+            Infix::ShiftLeft => write!(f, "<<"),
+            Infix::ShiftRight => write!(f, ">>"),
+        }
+    }
+}
+
+impl fmt::Display for Postfix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Postfix::Factorial => write!(f, "!"),
+            Postfix::Percent => write!(f, "%"),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Batch submodule.
+//!
+//! Evaluates the lines of a file in order in one session, so that a line sees the variables and
+//! the functions of the lines before it, e.g. to recompute a capacity spreadsheet in CI.
+//! A failing line doesn't stop the batch: it's reported in its row, and the later lines go on.
+//! The empty lines and the comments are skipped, and `:quit` ends the batch.
+//! The listings of `:vars`, `:funcs` and `:ast` are printed as in the REPL, a row per line.
+
+use clap::ValueEnum;
+
+use super::{
+    number::{Mode, Number},
+    session::{CalculatorError, Outcome, Session},
+};
+
+/// The format of the batch results.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum BatchFormat {
+    /// An aligned table with the line numbers, the inputs and the results.
+    #[default]
+    Table,
+    /// CSV with the `line`, `input`, `variable`, `result`, `unit` and `error` columns.
+    Csv,
+}
+
+/// The columns of the CSV format.
+pub const CSV_HEADER: [&str; 6] = ["line", "input", "variable", "result", "unit", "error"];
+
+/// An evaluated line of a batch.
+#[derive(Debug)]
+pub struct Line {
+    /// The line number, from 1.
+    pub number: usize,
+    pub input: String,
+    /// The arithmetic mode of the line, which a `:mode` line changes.
+    pub mode: Mode,
+    pub result: Result<Outcome, CalculatorError>,
+}
+
+impl Line {
+    /// The assigned variable, if the line is an assignment.
+    pub fn variable(&self) -> Option<&str> {
+        match &self.result {
+            Ok(Outcome::Value {
+                name: Some(name), ..
+            }) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The value of an expression or an assignment.
+    pub fn value(&self) -> Option<&Number> {
+        match &self.result {
+            Ok(Outcome::Value { value, .. }) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// The error message, if the line fails.
+    pub fn error(&self) -> Option<String> {
+        self.result.as_ref().err().map(CalculatorError::describe)
+    }
+
+    /// The result as printed by the calculator, e.g. `5 km`, a function definition, a plot file,
+    /// a listing, or the error; empty for the other commands.
+    pub fn text(&self) -> String {
+        match &self.result {
+            Ok(Outcome::Value { value, .. }) => value.format_result(self.mode),
+            Ok(Outcome::Defined(function)) => function.to_string(),
+            Ok(Outcome::Mode(mode)) => mode.to_string(),
+            Ok(Outcome::Plotted { path, .. }) => path.display().to_string(),
+            Ok(_) => self.listing().unwrap_or_default(),
+            Err(error) => format!("error: {}", error.describe()),
+        }
+    }
+
+    /// The listing of the variables, of the functions, or of the syntax tree, a line per item,
+    /// as printed by the calculator.
+    fn listing(&self) -> Option<String> {
+        match &self.result {
+            Ok(Outcome::Variables(variables)) if variables.is_empty() => {
+                Some(String::from("No variables."))
+            }
+            Ok(Outcome::Variables(variables)) => Some(
+                variables
+                    .iter()
+                    .map(|(name, value)| format!("{} = {}", name, value.format_result(self.mode)))
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Ok(Outcome::Functions(functions)) if functions.is_empty() => {
+                Some(String::from("No functions."))
+            }
+            Ok(Outcome::Functions(functions)) => Some(
+                functions
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join("\n"),
+            ),
+            Ok(Outcome::Ast(tree)) => Some(tree.trim_end().to_owned()),
+            _ => None,
+        }
+    }
+
+    /// The value without its unit, e.g. `5` for `5 km`, or the integer without its bits in the programmer mode.
+    fn number(&self) -> Option<String> {
+        self.value().map(|value| match value {
+            Number::Quantity(quantity) => {
+                Number::Float(quantity.value_in_label()).format(self.mode)
+            }
+            Number::Integer(integer) => integer.to_string(),
+            value => value.format(self.mode),
+        })
+    }
+
+    /// The unit of a quantity, e.g. `km`.
+    fn unit(&self) -> Option<String> {
+        match self.value() {
+            Some(Number::Quantity(quantity)) => Some(quantity.unit()),
+            _ => None,
+        }
+    }
+
+    /// The CSV record of the line, see `CSV_HEADER`; the result of a listing is its lines.
+    pub fn csv_record(&self) -> [String; 6] {
+        [
+            self.number.to_string(),
+            self.input.clone(),
+            self.variable().unwrap_or_default().to_owned(),
+            self.number().or_else(|| self.listing()).unwrap_or_default(),
+            self.unit().unwrap_or_default(),
+            self.error().unwrap_or_default(),
+        ]
+    }
+}
+
+/// Evaluates the lines in order in the session.
+pub fn run(session: &mut Session, lines: impl IntoIterator<Item = String>) -> Vec<Line> {
+    let mut evaluated = vec![];
+    for (index, input) in lines.into_iter().enumerate() {
+        let result = session.execute(&input);
+        match result {
+            Ok(Outcome::Empty) => continue,
+            Ok(Outcome::Quit) => break,
+            _ => {}
+        }
+        evaluated.push(Line {
+            number: index + 1,
+            input: input.trim().to_owned(),
+            mode: session.mode(),
+            result,
+        });
+    }
+    evaluated
+}
+
+/// The rows of the aligned table of the lines, the header first.
+/// A result of several lines continues on the next rows, in the result column.
+pub fn table(lines: &[Line]) -> Vec<String> {
+    let rows: Vec<[String; 3]> = std::iter::once(["Line", "Input", "Result"].map(str::to_owned))
+        .chain(lines.iter().flat_map(|line| {
+            let text = line.text();
+            let mut rows = vec![];
+            for (index, part) in text.split('\n').enumerate() {
+                rows.push(match index {
+                    0 => [line.number.to_string(), line.input.clone(), part.to_owned()],
+                    _ => [String::new(), String::new(), part.to_owned()],
+                });
+            }
+            rows
+        }))
+        .collect();
+    let width = |column: usize| {
+        rows.iter()
+            .map(|row| row[column].chars().count())
+            .max()
+            .unwrap_or_default()
+    };
+    let (number_width, input_width) = (width(0), width(1));
+    rows.iter()
+        .map(|[number, input, text]| {
+            let row = format!(
+                "{:>number_width$}  {:<input_width$}  {}",
+                number, input, text
+            );
+            row.trim_end().to_owned()
+        })
+        .collect()
+}
+
+/// The lines in the CSV format, with the header.
+pub fn csv(lines: &[Line]) -> String {
+    let mut writer = csv::Writer::from_writer(vec![]);
+    let records =
+        std::iter::once(CSV_HEADER.map(str::to_owned)).chain(lines.iter().map(Line::csv_record));
+    for record in records {
+        writer
+            .write_record(&record)
+            .expect("Can not write a CSV record to memory");
+    }
+    let bytes = writer
+        .into_inner()
+        .expect("Can not write the CSV records to memory");
+    String::from_utf8(bytes).expect("The CSV records are UTF-8")
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+mod batch {
+    use crate::calculator::{
+        batch::{csv, run, table, Line},
+        session::Session,
+    };
+
+    /// Evaluates the lines in a new session.
+    fn batch(lines: &[&str]) -> Vec<Line> {
+        run(
+            &mut Session::new(),
+            lines.iter().map(|line| line.to_string()),
+        )
+    }
+
+    #[test]
+    fn shared_state_and_errors() {
+        let lines = batch(&[
+            "# capacity",
+            "rps = 1200",
+            "",
+            "nodes = rps / 300",
+            "nodes * 2",
+            "disk = 20 GiB * nodes in GB",
+            "y + 1",
+            "ans",
+            ":quit",
+            "1 / 0",
+        ]);
+        let numbers: Vec<usize> = lines.iter().map(|line| line.number).collect();
+        assert_eq!(numbers, [2, 4, 5, 6, 7, 8]);
+        assert_eq!(lines[1].variable(), Some("nodes"));
+        assert_eq!(lines[2].text(), "8");
+        assert!(lines[4].error().unwrap().contains("unknown variable"));
+        assert_eq!(lines[5].text(), lines[3].text());
+
+        let rows = table(&lines);
+        assert_eq!(rows[0], "Line  Input                        Result");
+        assert_eq!(rows[1], "   2  rps = 1200                   1200");
+        assert!(rows[5].starts_with("   7  y + 1                        error: "));
+
+        let csv = csv(&lines);
+        let mut records = csv.lines();
+        assert_eq!(
+            records.next(),
+            Some("line,input,variable,result,unit,error")
+        );
+        assert_eq!(records.next(), Some("2,rps = 1200,rps,1200,,"));
+        assert_eq!(
+            records.nth(2),
+            Some("6,disk = 20 GiB * nodes in GB,disk,85.89934592,GB,")
+        );
+        assert!(records.next().unwrap().starts_with("7,y + 1,,,,"));
+    }
+
+    #[test]
+    fn listings() {
+        let lines = batch(&[
+            ":vars",
+            "x = 2",
+            "f(a) = a + x",
+            ":vars",
+            ":funcs",
+            ":ast 1+2",
+        ]);
+        assert_eq!(lines[0].text(), "No variables.");
+        assert_eq!(lines[3].text(), "x = 2\nans = 2");
+        assert_eq!(lines[4].text(), "f(a) = a + x");
+        assert_eq!(lines[5].text(), "+\n├── 1\n└── 2");
+
+        let rows = table(&lines);
+        assert_eq!(rows[1], "   1  :vars         No variables.");
+        assert_eq!(rows[4], "   4  :vars         x = 2");
+        assert_eq!(rows[5], "                    ans = 2");
+        assert_eq!(rows[6], "   5  :funcs        f(a) = a + x");
+        assert_eq!(
+            &rows[7..],
+            [
+                "   6  :ast 1+2      +",
+                "                    ├── 1",
+                "                    └── 2"
+            ]
+        );
+
+        let csv = csv(&lines);
+        assert!(csv.contains("4,:vars,,\"x = 2\nans = 2\",,\n"));
+        assert!(csv.contains("5,:funcs,,f(a) = a + x,,\n"));
+    }
+}
@@ -0,0 +1,101 @@
+//! Editor submodule.
+//!
+//! Reads the calculator input lines with line editing and a persistent history if the console is a terminal,
+//! and with the `prompt` module otherwise, e.g. in scripted sessions and with the JSON output formats.
+
+use colored::Colorize;
+use rustyline::{error::ReadlineError, DefaultEditor};
+use std::{
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+};
+
+use crate::{config, console, output, prompt};
+
+/// The line reader of the calculator.
+pub enum Editor {
+    /// Line editing with the history file.
+    Terminal {
+        editor: Box<DefaultEditor>,
+        history_file: PathBuf,
+    },
+    /// The prompt module.
+    Prompt,
+}
+
+impl Editor {
+    /// Editor constructor.
+    pub fn new() -> Editor {
+        let terminal = prompt::is_interactive()
+            && !console::is_scripted()
+            && output::is_text()
+            && io::stdin().is_terminal()
+            && io::stdout().is_terminal();
+        if !terminal {
+            return Editor::Prompt;
+        }
+
+        match DefaultEditor::new() {
+            Ok(mut editor) => {
+                let history_file = config::get().calculator.history_file.clone();
+                // The history file doesn't exist in the first session.
+                let _ = editor.load_history(&history_file);
+                Editor::Terminal {
+                    editor: Box::new(editor),
+                    history_file,
+                }
+            }
+            Err(error) => {
+                log::warn!("line editing is not available: {}", error);
+                Editor::Prompt
+            }
+        }
+    }
+
+    /// Reads an input line; `None` if the user ends the session, e.g. with `Ctrl-D`.
+    pub fn read_line(&mut self) -> Option<String> {
+        match self {
+            Editor::Terminal {
+                editor,
+                history_file,
+            } => loop {
+                match editor.readline("calc> ") {
+                    Ok(line) => {
+                        if !line.trim().is_empty() {
+                            let _ = editor.add_history_entry(line.as_str());
+                            save_history(editor, history_file);
+                        }
+                        return Some(line);
+                    }
+                    Err(ReadlineError::Interrupted) => continue,
+                    Err(ReadlineError::Eof) => return None,
+                    Err(error) => {
+                        log::error!("can't read the input line: {}", error);
+                        return None;
+                    }
+                }
+            },
+            Editor::Prompt => {
+                human_println!(
+                    "\n{}",
+                    "Please input an expression, an assignment, or a command (:help):"
+                        .yellow()
+                        .bold()
+                );
+
+                prompt::read_line_until_eof("An expression (EXPRESSION argument)")
+            }
+        }
+    }
+}
+
+/// Saves the history, so that it survives the process exiting on an error.
+fn save_history(editor: &mut DefaultEditor, history_file: &Path) {
+    if let Some(directory) = history_file.parent() {
+        let _ = fs::create_dir_all(directory);
+    }
+    if let Err(error) = editor.save_history(history_file) {
+        log::warn!("can't save the history file {:?}: {}", history_file, error);
+    }
+}
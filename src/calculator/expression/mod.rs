@@ -0,0 +1,678 @@
+//! Expression submodule.
+//!
+//! Evaluates the syntax tree of an expression, see the `parser` submodule, and checks each operation,
+//! so that a failure is reported with its position in the input instead of a silent `inf` or `NaN`:
+//! syntax errors, unknown names with the closest known name as a hint, wrong numbers of arguments,
+//! division by zero, results which are not a number, overflows, incompatible matrix shapes, singular
+//! matrices, and the operations which are not available in the arithmetic mode, e.g. `&` outside
+//! the programmer mode.
+//! The user-defined functions are evaluated with their parameters bound to the arguments, and so are
+//! the expressions of the numerical functions, e.g. `x` in `integrate(exp(-x^2), x, 0, 3)`,
+//! see the `numeric` submodule.
+
+use num_complex::Complex64;
+use std::{collections::BTreeMap, fmt, ops::Range};
+use thiserror::Error;
+
+use super::{
+    ast::{Infix, Node, NodeKind, Postfix, Prefix},
+    matrix::{Matrix, Shape},
+    number::{Mode, Number},
+    numeric::{self, Failure},
+    parser, statistics,
+    unit::{self, Dimension, Label, Quantity, Unit},
+};
+
+/// The names of the built-in constants.
+pub const CONSTANTS: &[&str] = &["pi", "e", "i"];
+
+/// The built-in functions which have a complex result for some real arguments, e.g. `sqrt(-4)`.
+const COMPLEX_FUNCTIONS: &[&str] = &["sqrt", "ln", "asin", "acos", "acosh", "atanh"];
+
+/// The names of the built-in functions.
+pub const FUNCTIONS: &[&str] = &[
+    "sqrt",
+    "exp",
+    "ln",
+    "abs",
+    "sin",
+    "cos",
+    "tan",
+    "asin",
+    "acos",
+    "atan",
+    "sinh",
+    "cosh",
+    "tanh",
+    "asinh",
+    "acosh",
+    "atanh",
+    "floor",
+    "ceil",
+    "round",
+    "signum",
+    "atan2",
+    "max",
+    "min",
+    "solve",
+    "deriv",
+    "integrate",
+    "minimum",
+    "maximum",
+    "arg",
+    "conj",
+    "re",
+    "im",
+    "transpose",
+    "det",
+    "inverse",
+    "linsolve",
+    "sum",
+    "mean",
+    "median",
+    "variance",
+    "stddev",
+    "percentile",
+    "histogram",
+    "load",
+];
+
+/// The kind of an expression failure.
+#[derive(Debug, Error, PartialEq)]
+pub enum ErrorKind {
+    #[error("unexpected `{0}`")]
+    UnexpectedToken(String),
+    #[error("unexpected end of the expression, an operand is missing")]
+    MissingOperand,
+    #[error("{0} unclosed parenthesis, `)` is missing")]
+    MissingParenthesis(i32),
+    #[error("unclosed bracket, `]` is missing")]
+    MissingBracket,
+    #[error("unclosed string, `\"` is missing")]
+    MissingQuote,
+    #[error("the rows of a matrix must have the same length: row {row} has {columns}, row 1 has {expected}")]
+    RaggedMatrix {
+        row: usize,
+        columns: usize,
+        expected: usize,
+    },
+    #[error("unknown variable `{0}`")]
+    UnknownVariable(String),
+    #[error("unknown function `{0}`")]
+    UnknownFunction(String),
+    #[error("the function `{name}` expects {expected}")]
+    Arguments { name: String, expected: String },
+    #[error("in the function `{name}`: {error}")]
+    Function {
+        name: String,
+        error: Box<ExpressionError>,
+    },
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("the factorial is defined for the non-negative integers, not {0}")]
+    Factorial(String),
+    #[error("the result of `{0}` is not a number")]
+    NotANumber(String),
+    #[error("the result of `{0}` is infinite (overflow)")]
+    Overflow(String),
+    #[error("the result of `{0}` is too large to be computed exactly")]
+    TooLarge(String),
+    #[error("`{0}` is not an integer")]
+    NotAnInteger(String),
+    #[error("the shift amount of `{0}` is negative")]
+    NegativeShift(String),
+    #[error("`{0}` is only available in the programmer mode")]
+    Programmer(String),
+    #[error("`{0}` is not available in the programmer mode")]
+    Unavailable(String),
+    #[error("incompatible units in `{0}`: {1} and {2}")]
+    Units(String, Dimension, Dimension),
+    #[error("`{0}` is not defined for quantities with units")]
+    Dimensionless(String),
+    #[error("`{0}` is not a unit")]
+    NotAUnit(String),
+    #[error(
+        "the temperature unit `{0}` must follow a number, e.g. `20 {0}`, or be the target of `in`"
+    )]
+    AffineUnit(String),
+    #[error("`{name}` did not converge: {reason}")]
+    Convergence { name: &'static str, reason: String },
+    #[error("`{0}` is not defined for complex numbers")]
+    Complex(String),
+    #[error("`{0}` is not defined for matrices")]
+    Matrix(String),
+    #[error("the elements of a matrix must be real numbers without units, not `{0}`")]
+    Element(String),
+    #[error("incompatible shapes in `{0}`: {1} and {2}")]
+    Shape(String, Shape, Shape),
+    #[error("`{0}` requires a square matrix, not {1}")]
+    NotSquare(String, Shape),
+    #[error("the matrix of `{0}` is singular")]
+    Singular(String),
+    #[error("the string {0:?} is only allowed as an argument of `load`")]
+    Text(String),
+    #[error("can not load the CSV column of {0}")]
+    Load(String),
+    #[error("`{0}` reads files, which is not allowed in a sandboxed session")]
+    Sandboxed(String),
+    #[error("the evaluation exceeds the budget of {0} steps")]
+    Budget(u64),
+}
+
+/// An expression failure, with the offending part of the expression if it is known.
+#[derive(Debug, Error, PartialEq)]
+#[error("{kind}")]
+pub struct ExpressionError {
+    pub kind: ErrorKind,
+    /// The byte range of the offending part of the expression.
+    pub span: Option<Range<usize>>,
+    /// A suggestion to fix the expression.
+    pub hint: Option<String>,
+}
+
+impl ExpressionError {
+    /// The expression failure without position.
+    pub fn new(kind: ErrorKind) -> ExpressionError {
+        ExpressionError {
+            kind,
+            span: None,
+            hint: None,
+        }
+    }
+
+    /// Sets the offending part of the expression.
+    pub fn at(mut self, span: Option<Range<usize>>) -> ExpressionError {
+        self.span = span;
+        self
+    }
+
+    /// Sets the suggestion.
+    pub fn hint(mut self, hint: Option<String>) -> ExpressionError {
+        self.hint = hint;
+        self
+    }
+
+    /// The caret line marking the offending part of the expression, to print under the expression.
+    pub fn underline(&self, expression: &str) -> Option<String> {
+        let span = self.span.as_ref()?;
+        let start = expression.get(..span.start)?.chars().count();
+        let width = expression
+            .get(span.clone())
+            .map_or(0, |part| part.chars().count())
+            .max(1);
+        Some(format!("{}{}", " ".repeat(start), "^".repeat(width)))
+    }
+}
+
+/// A user-defined function, e.g. `f(x, y) = x^2 + y`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Function {
+    pub name: String,
+    pub parameters: Vec<String>,
+    /// The source of the body.
+    pub body: String,
+    pub node: Node,
+    /// The mode of the definition, whose syntax the body is parsed with.
+    pub mode: Mode,
+}
+
+impl fmt::Display for Function {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}({}) = {}",
+            self.name,
+            self.parameters.join(", "),
+            self.body
+        )
+    }
+}
+
+/// The variables, the user-defined functions and the arithmetic of an expression.
+pub trait Scope {
+    /// The value of the variable.
+    fn variable(&self, name: &str) -> Option<Number>;
+
+    /// The names of the variables, for the hints.
+    fn names(&self) -> Vec<&str>;
+
+    /// The user-defined function.
+    fn function(&self, _name: &str) -> Option<&Function> {
+        None
+    }
+
+    /// The names of the user-defined functions, for the hints.
+    fn function_names(&self) -> Vec<&str> {
+        vec![]
+    }
+
+    /// The arithmetic mode.
+    fn mode(&self) -> Mode {
+        Mode::Float
+    }
+
+    /// Whether the functions reading files, e.g. `load`, are rejected.
+    fn sandboxed(&self) -> bool {
+        false
+    }
+
+    /// Counts an evaluation step, and fails if the budget of the evaluation is exhausted.
+    fn step(&self) -> Result<(), ErrorKind> {
+        Ok(())
+    }
+}
+
+impl Scope for BTreeMap<String, f64> {
+    fn variable(&self, name: &str) -> Option<Number> {
+        self.get(name).copied().map(Number::Float)
+    }
+
+    fn names(&self) -> Vec<&str> {
+        self.keys().map(String::as_str).collect()
+    }
+}
+
+/// The parameters of a user-defined function bound to the arguments of a call.
+struct Frame<'a> {
+    parameters: &'a [String],
+    arguments: &'a [Number],
+    outer: &'a dyn Scope,
+}
+
+impl Scope for Frame<'_> {
+    fn variable(&self, name: &str) -> Option<Number> {
+        match self
+            .parameters
+            .iter()
+            .position(|parameter| parameter == name)
+        {
+            Some(index) => Some(self.arguments[index].clone()),
+            None => self.outer.variable(name),
+        }
+    }
+
+    fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.parameters.iter().map(String::as_str).collect();
+        names.extend(self.outer.names());
+        names
+    }
+
+    fn function(&self, name: &str) -> Option<&Function> {
+        self.outer.function(name)
+    }
+
+    fn function_names(&self) -> Vec<&str> {
+        self.outer.function_names()
+    }
+
+    fn mode(&self) -> Mode {
+        self.outer.mode()
+    }
+
+    fn sandboxed(&self) -> bool {
+        self.outer.sandboxed()
+    }
+
+    fn step(&self) -> Result<(), ErrorKind> {
+        self.outer.step()
+    }
+}
+
+/// Parses and evaluates the expression.
+pub fn evaluate(expression: &str, scope: &dyn Scope) -> Result<Number, ExpressionError> {
+    let node = parser::parse(expression, scope.mode())?;
+    Evaluator { expression, scope }.evaluate(&node)
+}
+
+/// The evaluation of a syntax tree.
+struct Evaluator<'a> {
+    /// The source of the syntax tree, for the names of the conversion units.
+    expression: &'a str,
+    scope: &'a dyn Scope,
+}
+
+impl Evaluator<'_> {
+    /// Evaluates the node.
+    fn evaluate(&self, node: &Node) -> Result<Number, ExpressionError> {
+        let mode = self.scope.mode();
+        let span = || Some(node.span.clone());
+        let failed = |kind| ExpressionError::new(kind).at(span());
+        self.scope.step().map_err(failed)?;
+        if let NodeKind::Infix(Infix::Multiply | Infix::ImplicitMultiply, left, right) = &node.kind
+        {
+            if let Some(unit) = self.unit(right).filter(|unit| unit.is_affine()) {
+                return match self.evaluate(left)? {
+                    Number::Quantity(_) => Err(failed(ErrorKind::AffineUnit(unit.name.to_owned()))),
+                    value => Ok(Number::Quantity(Quantity::of(value.to_f64(), unit))),
+                };
+            }
+        }
+        match &node.kind {
+            NodeKind::Number(literal) => Number::literal(literal, mode).map_err(failed),
+            NodeKind::Variable(name) => {
+                let value = match constant(name) {
+                    Some(_) if matches!(mode, Mode::Programmer(_)) => {
+                        return Err(failed(ErrorKind::Unavailable(name.clone())))
+                    }
+                    Some(value) => value,
+                    None => match (self.scope.variable(name), unit::find(name)) {
+                        (Some(value), _) => value,
+                        (None, Some(_)) if matches!(mode, Mode::Programmer(_)) => {
+                            return Err(failed(ErrorKind::Unavailable(name.clone())))
+                        }
+                        (None, Some(unit)) if unit.is_affine() => {
+                            return Err(failed(ErrorKind::AffineUnit(name.clone())))
+                        }
+                        (None, Some(unit)) => Number::Quantity(Quantity::of(1.0, unit)),
+                        (None, None) => {
+                            return Err(ExpressionError::new(ErrorKind::UnknownVariable(
+                                name.clone(),
+                            ))
+                            .at(span())
+                            .hint(variable_hint(name, &self.scope.names())))
+                        }
+                    },
+                };
+                value.in_mode(mode).map_err(failed)
+            }
+            NodeKind::Prefix(operator, operand) => {
+                let operand = self.evaluate(operand)?;
+                match operator {
+                    Prefix::Plus => Ok(operand),
+                    Prefix::Minus => Ok(operand.negate()),
+                    Prefix::Not => operand
+                        .not()
+                        .map_err(|kind| failed(kind).hint(programmer_hint())),
+                }
+            }
+            NodeKind::Infix(operator, left, right) => {
+                let (left_value, right_value) = (self.evaluate(left)?, self.evaluate(right)?);
+                if matches!(operator, Infix::Divide | Infix::Remainder) && right_value.is_zero() {
+                    return Err(ExpressionError::new(ErrorKind::DivisionByZero)
+                        .at(Some(right.span.clone())));
+                }
+                Number::infix(*operator, &left_value, &right_value).map_err(|kind| {
+                    let hint = match kind {
+                        ErrorKind::Programmer(_) => programmer_hint(),
+                        _ => None,
+                    };
+                    failed(kind).hint(hint)
+                })
+            }
+            NodeKind::Postfix(Postfix::Percent, operand) => Ok(self.evaluate(operand)?.percent()),
+            NodeKind::Postfix(Postfix::Factorial, operand) => {
+                self.evaluate(operand)?.factorial().map_err(failed)
+            }
+            NodeKind::Call(name, args) => {
+                if let Some(function) = numeric::find(name) {
+                    return self.numeric(function, args, node);
+                }
+                if name == statistics::LOAD {
+                    return self.load(args).map_err(failed);
+                }
+                let args = args
+                    .iter()
+                    .map(|arg| self.evaluate(arg))
+                    .collect::<Result<Vec<Number>, ExpressionError>>()?;
+                if let Some(function) = self.scope.function(name) {
+                    return self.call(function, &args).map_err(|error| error.at(span()));
+                }
+                Number::call(name, &args).map_err(|kind| {
+                    let hint = match kind {
+                        ErrorKind::UnknownFunction(_) => function_hint(name, self.scope),
+                        ErrorKind::NotANumber(_) if COMPLEX_FUNCTIONS.contains(&name.as_str()) => {
+                            Some(format!(
+                                "for a complex result, pass a complex argument, e.g. `{}(-4 + 0i)`",
+                                name
+                            ))
+                        }
+                        _ => None,
+                    };
+                    failed(kind).hint(hint)
+                })
+            }
+            NodeKind::Text(text) => Err(failed(ErrorKind::Text(text.clone()))),
+            NodeKind::Matrix(rows) => {
+                if matches!(mode, Mode::Programmer(_)) {
+                    let literal = self.expression[node.span.clone()].to_owned();
+                    return Err(failed(ErrorKind::Unavailable(literal)));
+                }
+                let rows = rows
+                    .iter()
+                    .map(|row| row.iter().map(|element| self.element(element)).collect())
+                    .collect::<Result<Vec<Vec<f64>>, ExpressionError>>()?;
+                Ok(Number::Matrix(Matrix::new(&rows)))
+            }
+            NodeKind::Equation(left, right) => {
+                let (left, right) = (self.evaluate(left)?, self.evaluate(right)?);
+                Number::infix(Infix::Subtract, &left, &right).map_err(failed)
+            }
+            NodeKind::Convert(value, target) => {
+                let value = self.evaluate(value)?;
+                let name = self.expression[target.span.clone()].trim().to_owned();
+                let (label, dimension) = match self.unit(target) {
+                    Some(unit) => (unit.label(), unit.dimension),
+                    None => match self.evaluate(target)? {
+                        Number::Quantity(unit) => (
+                            Label {
+                                name,
+                                factor: unit.value(),
+                                offset: 0.0,
+                            },
+                            unit.dimension(),
+                        ),
+                        _ => {
+                            return Err(ExpressionError::new(ErrorKind::NotAUnit(name))
+                                .at(Some(target.span.clone())))
+                        }
+                    },
+                };
+                value
+                    .convert(label, dimension, || {
+                        self.expression[node.span.clone()].to_owned()
+                    })
+                    .map_err(failed)
+            }
+        }
+    }
+
+    /// The built-in unit of the node, if it's a name which is neither a constant nor a variable.
+    fn unit(&self, node: &Node) -> Option<&'static Unit> {
+        match &node.kind {
+            NodeKind::Variable(name)
+                if constant(name).is_none()
+                    && self.scope.variable(name).is_none()
+                    && !matches!(self.scope.mode(), Mode::Programmer(_)) =>
+            {
+                unit::find(name)
+            }
+            _ => None,
+        }
+    }
+
+    /// Loads the CSV column of `load(FILE, COLUMN)`, whose arguments are strings.
+    fn load(&self, args: &[Node]) -> Result<Number, ErrorKind> {
+        if self.scope.sandboxed() {
+            return Err(ErrorKind::Sandboxed(statistics::LOAD.to_owned()));
+        }
+        if matches!(self.scope.mode(), Mode::Programmer(_)) {
+            return Err(ErrorKind::Unavailable(statistics::LOAD.to_owned()));
+        }
+        match args {
+            [Node {
+                kind: NodeKind::Text(path),
+                ..
+            }, Node {
+                kind: NodeKind::Text(column),
+                ..
+            }] => statistics::load(path, column),
+            _ => Err(ErrorKind::Arguments {
+                name: statistics::LOAD.to_owned(),
+                expected: statistics::LOAD_ARGUMENTS.to_owned(),
+            }),
+        }
+    }
+
+    /// Evaluates the element of a matrix literal, a real number without unit.
+    fn element(&self, node: &Node) -> Result<f64, ExpressionError> {
+        let value = self.evaluate(node)?;
+        value.real().ok_or_else(|| {
+            ExpressionError::new(ErrorKind::Element(value.to_string())).at(Some(node.span.clone()))
+        })
+    }
+
+    /// Evaluates the numerical function of the node, e.g. `integrate(exp(-x^2), x, 0, 3)`,
+    /// with the variable bound to the points chosen by the numerical method.
+    fn numeric(
+        &self,
+        (name, expected): (&'static str, &str),
+        args: &[Node],
+        node: &Node,
+    ) -> Result<Number, ExpressionError> {
+        let failed = |kind| ExpressionError::new(kind).at(Some(node.span.clone()));
+        if matches!(self.scope.mode(), Mode::Programmer(_)) {
+            return Err(failed(ErrorKind::Unavailable(name.to_owned())));
+        }
+        let arguments = || {
+            failed(ErrorKind::Arguments {
+                name: name.to_owned(),
+                expected: expected.to_owned(),
+            })
+        };
+        let [body, variable, points @ ..] = args else {
+            return Err(arguments());
+        };
+        let NodeKind::Variable(variable) = &variable.kind else {
+            return Err(arguments());
+        };
+
+        let points = points
+            .iter()
+            .map(|point| self.evaluate(point).map(|value| value.to_f64()))
+            .collect::<Result<Vec<f64>, ExpressionError>>()?;
+        let parameters = std::slice::from_ref(variable);
+        let f = |x: f64| {
+            let arguments = [Number::Float(x)];
+            let frame = Frame {
+                parameters,
+                arguments: &arguments,
+                outer: self.scope,
+            };
+            let evaluator = Evaluator {
+                expression: self.expression,
+                scope: &frame,
+            };
+            evaluator.evaluate(body).map(|value| value.to_f64())
+        };
+        match numeric::apply(name, &f, &points) {
+            None => Err(arguments()),
+            Some(Ok(value)) if value.is_finite() => Ok(Number::Float(value)),
+            Some(Ok(_)) => Err(failed(ErrorKind::Overflow(
+                self.expression[node.span.clone()].to_owned(),
+            ))),
+            Some(Err(Failure::Evaluation(error))) => Err(error),
+            Some(Err(Failure::Convergence(reason))) => {
+                Err(failed(ErrorKind::Convergence { name, reason }))
+            }
+        }
+    }
+
+    /// Evaluates the body of the user-defined function with the arguments.
+    fn call(&self, function: &Function, arguments: &[Number]) -> Result<Number, ExpressionError> {
+        if arguments.len() != function.parameters.len() {
+            let expected = match function.parameters.len() {
+                1 => String::from("1 argument"),
+                count => format!("{} arguments", count),
+            };
+            return Err(ExpressionError::new(ErrorKind::Arguments {
+                name: function.name.clone(),
+                expected,
+            }));
+        }
+
+        let frame = Frame {
+            parameters: &function.parameters,
+            arguments,
+            outer: self.scope,
+        };
+        Evaluator {
+            expression: &function.body,
+            scope: &frame,
+        }
+        .evaluate(&function.node)
+        .map_err(|error| {
+            ExpressionError::new(ErrorKind::Function {
+                name: function.name.clone(),
+                error: Box::new(error),
+            })
+        })
+    }
+}
+
+/// The value of the built-in constant.
+fn constant(name: &str) -> Option<Number> {
+    match name {
+        "pi" => Some(Number::Float(std::f64::consts::PI)),
+        "e" => Some(Number::Float(std::f64::consts::E)),
+        "i" => Some(Number::Complex(Complex64::i())),
+        _ => None,
+    }
+}
+
+/// The hint for an operation of the programmer mode.
+fn programmer_hint() -> Option<String> {
+    Some(String::from(
+        "switch to the programmer mode, e.g. `:mode u32`, where `^` is the bitwise xor and `**` the power",
+    ))
+}
+
+/// The hint for an unknown variable: a function used without arguments, or the closest name.
+fn variable_hint(name: &str, names: &[&str]) -> Option<String> {
+    if FUNCTIONS.contains(&name) {
+        return Some(format!("`{}` is a function, e.g. `{}(1)`", name, name));
+    }
+    let candidates = CONSTANTS.iter().chain(names);
+    closest(name, candidates.copied()).map(|closest| format!("did you mean `{}`?", closest))
+}
+
+/// The hint for an unknown function: a variable used as a function, or the closest function name.
+fn function_hint(name: &str, scope: &dyn Scope) -> Option<String> {
+    if scope.names().contains(&name) || CONSTANTS.contains(&name) {
+        return Some(format!(
+            "`{}` is a variable, not a function; write `{}*(...)` to multiply",
+            name, name
+        ));
+    }
+    let function_names = scope.function_names();
+    let candidates = FUNCTIONS.iter().copied().chain(function_names);
+    closest(name, candidates).map(|closest| format!("did you mean `{}`?", closest))
+}
+
+/// The candidate closest to the name, if it is close enough to be a typo.
+pub fn closest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let length = name.chars().count();
+    let threshold = length.div_ceil(3);
+    candidates
+        .map(|candidate| (distance(name, candidate), candidate))
+        .filter(|(distance, _)| *distance <= threshold && *distance < length)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// The Levenshtein distance between the strings.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, b) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a != *b);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,82 @@
+mod expression {
+    use std::collections::BTreeMap;
+
+    use crate::calculator::expression::{closest, evaluate, ErrorKind, ExpressionError};
+
+    /// Evaluates the expression with the variable `x = 2`.
+    fn eval(expression: &str) -> Result<f64, ExpressionError> {
+        let variables = BTreeMap::from([(String::from("x"), 2.0)]);
+        evaluate(expression, &variables).map(|value| value.to_f64())
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let error = eval("2+*3").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnexpectedToken(String::from("*")));
+        assert_eq!(error.underline("2+*3").as_deref(), Some("  ^"));
+
+        let error = eval("(1+2").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::MissingParenthesis(1));
+        assert_eq!(error.underline("(1+2").as_deref(), Some("    ^"));
+
+        assert_eq!(eval("1+").unwrap_err().kind, ErrorKind::MissingOperand);
+        assert_eq!(eval("1+2)").unwrap_err().span, Some(3..4));
+        assert_eq!(eval("2 3").unwrap_err().span, Some(2..3));
+        assert_eq!(eval("1 # 2").unwrap_err().span, Some(2..3));
+    }
+
+    #[test]
+    fn unknown_names() {
+        let error = eval("1 + sinn(x)").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnknownFunction(String::from("sinn")));
+        assert_eq!(
+            error.underline("1 + sinn(x)").as_deref(),
+            Some("    ^^^^^^^")
+        );
+        assert_eq!(error.hint.as_deref(), Some("did you mean `sin`?"));
+
+        let error = eval("2 * pj").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::UnknownVariable(String::from("pj")));
+        assert_eq!(error.span, Some(4..6));
+        assert_eq!(error.hint.as_deref(), Some("did you mean `pi`?"));
+
+        assert!(eval("sqrt").unwrap_err().hint.unwrap().contains("function"));
+        assert!(eval("x(1)").unwrap_err().hint.unwrap().contains("variable"));
+        assert_eq!(eval("y").unwrap_err().hint, None);
+        assert!(matches!(
+            eval("atan2(1)").unwrap_err().kind,
+            ErrorKind::Arguments { .. }
+        ));
+        assert_eq!(closest("sqtr", ["sqrt", "exp"].into_iter()), Some("sqrt"));
+    }
+
+    #[test]
+    fn numeric_failures() {
+        let error = eval("1 / (x - 2)").unwrap_err();
+        assert_eq!(error.kind, ErrorKind::DivisionByZero);
+        assert_eq!(error.span, Some(4..11));
+        assert_eq!(eval("5 % 0").unwrap_err().kind, ErrorKind::DivisionByZero);
+
+        assert_eq!(
+            eval("sqrt(-1)").unwrap_err().kind,
+            ErrorKind::NotANumber(String::from("sqrt(-1)"))
+        );
+        assert_eq!(
+            eval("2^2000").unwrap_err().kind,
+            ErrorKind::Overflow(String::from("2 ^ 2000"))
+        );
+        assert!(matches!(
+            eval("1e999").unwrap_err().kind,
+            ErrorKind::Overflow(_)
+        ));
+        assert!(matches!(
+            eval("2.5!").unwrap_err().kind,
+            ErrorKind::Factorial(_)
+        ));
+        assert!(matches!(
+            eval("171!").unwrap_err().kind,
+            ErrorKind::Overflow(_)
+        ));
+        assert_eq!(eval("-x^2 + 10 % 4").unwrap(), -2.0);
+    }
+}
@@ -0,0 +1,233 @@
+//! Integer submodule.
+//!
+//! The fixed-width integers of the programmer mode, e.g. `u8` or `i64`: every operation wraps around
+//! like the machine integers, e.g. `255 + 1` is `0` in `u8`, and the signed integers are two's complement.
+//! The values are stored as `i128`, which holds every value of every type.
+
+use num_bigint::BigInt;
+use num_traits::ToPrimitive;
+use std::{fmt, str::FromStr};
+
+use super::{ast::Infix, expression::ErrorKind};
+
+/// The names of the integer types.
+pub const TYPES: &[&str] = &["u8", "u16", "u32", "u64", "i8", "i16", "i32", "i64"];
+
+/// A fixed-width integer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntegerType {
+    pub signed: bool,
+    /// The width: 8, 16, 32 or 64.
+    pub bits: u32,
+}
+
+impl IntegerType {
+    /// The value wrapped around into the range of the type.
+    fn wrap(&self, value: i128) -> i128 {
+        let modulus = 1i128 << self.bits;
+        let value = value & (modulus - 1);
+        if self.signed && value >= modulus / 2 {
+            value - modulus
+        } else {
+            value
+        }
+    }
+}
+
+impl FromStr for IntegerType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<IntegerType, String> {
+        let invalid = || format!("expected one of {}", TYPES.join(", "));
+        let (signed, bits) = match name.split_at_checked(1) {
+            Some(("u", bits)) => (false, bits),
+            Some(("i", bits)) => (true, bits),
+            _ => return Err(invalid()),
+        };
+        match bits.parse::<u32>() {
+            Ok(bits @ (8 | 16 | 32 | 64)) => Ok(IntegerType { signed, bits }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for IntegerType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", if self.signed { "i" } else { "u" }, self.bits)
+    }
+}
+
+/// A value of a fixed-width integer type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Integer {
+    value: i128,
+    integer_type: IntegerType,
+}
+
+impl Integer {
+    /// The value wrapped around into the range of the type.
+    pub fn new(value: i128, integer_type: IntegerType) -> Integer {
+        Integer {
+            value: integer_type.wrap(value),
+            integer_type,
+        }
+    }
+
+    /// The big integer wrapped around into the range of the type.
+    pub fn from_big(value: &BigInt, integer_type: IntegerType) -> Integer {
+        let low = value & BigInt::from(u64::MAX);
+        Integer::new(low.to_i128().unwrap_or_default(), integer_type)
+    }
+
+    pub fn value(&self) -> i128 {
+        self.value
+    }
+
+    pub fn integer_type(&self) -> IntegerType {
+        self.integer_type
+    }
+
+    /// The same value converted to the type, wrapping around if it's out of range.
+    pub fn cast(&self, integer_type: IntegerType) -> Integer {
+        Integer::new(self.value, integer_type)
+    }
+
+    /// The bits of the value, e.g. `0xff` for `-1` in `i8`.
+    fn bits(&self) -> u128 {
+        (self.value as u128) & ((1u128 << self.integer_type.bits) - 1)
+    }
+
+    /// The hexadecimal representation of the bits, e.g. `0xff`.
+    pub fn hex(&self) -> String {
+        format!("{:#x}", self.bits())
+    }
+
+    /// The binary representation of the bits in groups of 4 digits, e.g. `0b1_0000_0000`.
+    pub fn bin(&self) -> String {
+        let digits = format!("{:b}", self.bits());
+        let mut grouped = String::from("0b");
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 4 == 0 {
+                grouped.push('_');
+            }
+            grouped.push(digit);
+        }
+        grouped
+    }
+
+    pub fn negate(&self) -> Integer {
+        self.with(self.value.wrapping_neg())
+    }
+
+    /// The bitwise complement, `~x`.
+    pub fn not(&self) -> Integer {
+        self.with(!self.value)
+    }
+
+    pub fn abs(&self) -> Integer {
+        self.with(self.value.abs())
+    }
+
+    pub fn signum(&self) -> Integer {
+        self.with(self.value.signum())
+    }
+
+    /// The percent, i.e. the value divided by 100, rounded toward zero.
+    pub fn percent(&self) -> Integer {
+        self.with(self.value / 100)
+    }
+
+    /// The binary operation, in the type of the left operand; the divisor of `/` and `%` isn't zero.
+    pub fn infix(&self, operator: Infix, other: &Integer) -> Result<Integer, ErrorKind> {
+        let (left, right) = (self.value, other.cast(self.integer_type).value);
+        let value = match operator {
+            Infix::Add => left.wrapping_add(right),
+            Infix::Subtract => left.wrapping_sub(right),
+            Infix::Multiply | Infix::ImplicitMultiply => left.wrapping_mul(right),
+            Infix::Divide => left / right,
+            Infix::Remainder => left % right,
+            Infix::Power => {
+                let exponent = u64::try_from(right)
+                    .map_err(|_| ErrorKind::NotAnInteger(format!("{} ** {}", left, right)))?;
+                self.pow(exponent)
+            }
+            Infix::BitAnd => left & right,
+            Infix::BitOr => left | right,
+            Infix::BitXor => left ^ right,
+            Infix::ShiftLeft | Infix::ShiftRight => {
+                let amount = u32::try_from(right).map_err(|_| {
+                    ErrorKind::NegativeShift(format!("{} {} {}", left, operator, right))
+                })?;
+                match operator {
+                    _ if amount >= self.integer_type.bits => {
+                        if operator == Infix::ShiftRight && left < 0 {
+                            -1
+                        } else {
+                            0
+                        }
+                    }
+                    Infix::ShiftLeft => left << amount,
+                    _ => left >> amount,
+                }
+            }
+        };
+        Ok(self.with(value))
+    }
+
+    /// The power, by squaring.
+    fn pow(&self, mut exponent: u64) -> i128 {
+        let (mut base, mut power) = (self.value, 1i128);
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                power = self.integer_type.wrap(power.wrapping_mul(base));
+            }
+            base = self.integer_type.wrap(base.wrapping_mul(base));
+            exponent >>= 1;
+        }
+        power
+    }
+
+    /// The factorial of the non-negative value.
+    pub fn factorial(&self) -> Result<Integer, ErrorKind> {
+        if self.value < 0 {
+            return Err(ErrorKind::Factorial(self.to_string()));
+        }
+        // Beyond twice the width, the factorial has more factors 2 than bits, so it wraps to 0.
+        if self.value > 2 * self.integer_type.bits as i128 {
+            return Ok(self.with(0));
+        }
+        Ok((2..=self.value).fold(self.with(1), |product, factor| {
+            product.with(product.value.wrapping_mul(factor))
+        }))
+    }
+
+    /// The integer of the same type with the value.
+    fn with(&self, value: i128) -> Integer {
+        Integer::new(value, self.integer_type)
+    }
+}
+
+impl fmt::Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+/// Parses a hexadecimal, octal or binary literal, e.g. `0xff`, `0o17` or `0b1010_0101`;
+/// `None` if the literal has no such prefix, or an invalid digit.
+pub fn parse_radix(literal: &str) -> Option<BigInt> {
+    let radix = match literal.get(..2)?.to_ascii_lowercase().as_str() {
+        "0x" => 16,
+        "0o" => 8,
+        "0b" => 2,
+        _ => return None,
+    };
+    let digits: String = literal[2..].chars().filter(|char| *char != '_').collect();
+    if digits.is_empty() || !digits.chars().all(|char| char.is_digit(radix)) {
+        return None;
+    }
+    BigInt::parse_bytes(digits.as_bytes(), radix)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,131 @@
+mod integer {
+    use num_bigint::BigInt;
+
+    use crate::calculator::{
+        ast::Infix,
+        expression::{evaluate, ErrorKind},
+        integer::{parse_radix, Integer, IntegerType},
+        number::Mode,
+        session::Session,
+    };
+
+    /// Evaluates the expression in the programmer mode of the type, and formats the result.
+    fn eval(integer_type: &str, expression: &str) -> Result<String, ErrorKind> {
+        let mode = Mode::Programmer(integer_type.parse().unwrap());
+        let mut session = Session::new();
+        session.set_mode(mode);
+        evaluate(expression, &session)
+            .map(|value| value.format(mode))
+            .map_err(|error| error.kind)
+    }
+
+    #[test]
+    fn types_and_literals() {
+        let i8: IntegerType = "i8".parse().unwrap();
+        assert_eq!(
+            i8,
+            IntegerType {
+                signed: true,
+                bits: 8
+            }
+        );
+        assert_eq!(i8.to_string(), "i8");
+        assert!("u128".parse::<IntegerType>().is_err());
+        assert!("x8".parse::<IntegerType>().is_err());
+
+        assert_eq!(parse_radix("0xFF"), Some(BigInt::from(255)));
+        assert_eq!(parse_radix("0o17"), Some(BigInt::from(15)));
+        assert_eq!(parse_radix("0b1010_0101"), Some(BigInt::from(165)));
+        assert_eq!(parse_radix("0b102"), None);
+        assert_eq!(parse_radix("255"), None);
+
+        let minus_one = Integer::new(255, i8);
+        assert_eq!(minus_one.value(), -1);
+        assert_eq!(minus_one.hex(), "0xff");
+        assert_eq!(minus_one.bin(), "0b1111_1111");
+        assert_eq!(Integer::from_big(&BigInt::from(-1), i8).value(), -1);
+        assert_eq!(
+            Integer::new(256, "u16".parse().unwrap()).bin(),
+            "0b1_0000_0000"
+        );
+    }
+
+    #[test]
+    fn wraparound() {
+        let u8: IntegerType = "u8".parse().unwrap();
+        let max = Integer::new(255, u8);
+        let one = Integer::new(1, u8);
+        assert_eq!(max.infix(Infix::Add, &one).unwrap().value(), 0);
+        assert_eq!(one.infix(Infix::Subtract, &max).unwrap().value(), 2);
+        assert_eq!(max.infix(Infix::Multiply, &max).unwrap().value(), 1);
+        assert_eq!(one.infix(Infix::ShiftLeft, &max).unwrap().value(), 0);
+        assert_eq!(Integer::new(6, u8).factorial().unwrap().value(), 208);
+        assert_eq!(Integer::new(200, u8).factorial().unwrap().value(), 0);
+
+        let i64: IntegerType = "i64".parse().unwrap();
+        let min = Integer::new(i64::MIN as i128, i64);
+        assert_eq!(min.negate(), min);
+        assert_eq!(
+            min.infix(Infix::Divide, &Integer::new(-1, i64)).unwrap(),
+            min
+        );
+        assert_eq!(
+            Integer::new(3, i64)
+                .infix(Infix::Power, &Integer::new(41, i64))
+                .unwrap()
+                .value(),
+            3i64.wrapping_pow(41) as i128
+        );
+    }
+
+    #[test]
+    fn programmer_mode() {
+        assert_eq!(
+            eval("u8", "0xf0 | 0b1010").unwrap(),
+            "250 (hex 0xfa, bin 0b1111_1010)"
+        );
+        assert_eq!(
+            eval("u32", "0xff ^ 0x0f").unwrap().split(' ').next(),
+            Some("240")
+        );
+        assert_eq!(
+            eval("u32", "2 ** 10 & 0x7ff").unwrap().split(' ').next(),
+            Some("1024")
+        );
+        assert_eq!(
+            eval("u32", "1 << 4 + 1").unwrap().split(' ').next(),
+            Some("32")
+        );
+        assert_eq!(eval("i8", "~0").unwrap(), "-1 (hex 0xff, bin 0b1111_1111)");
+        assert_eq!(
+            eval("i8", "-128 >> 7").unwrap().split(' ').next(),
+            Some("-1")
+        );
+        assert_eq!(
+            eval("u16", "7 / 2 + max(3, 9)").unwrap().split(' ').next(),
+            Some("12")
+        );
+
+        assert_eq!(
+            eval("u8", "1.5"),
+            Err(ErrorKind::NotAnInteger(String::from("1.5")))
+        );
+        assert_eq!(
+            eval("u8", "sqrt(4)"),
+            Err(ErrorKind::Unavailable(String::from("sqrt")))
+        );
+        assert_eq!(
+            eval("u8", "2pi"),
+            Err(ErrorKind::Unavailable(String::from("pi")))
+        );
+        assert!(matches!(
+            eval("i8", "1 << -1"),
+            Err(ErrorKind::NegativeShift(_))
+        ));
+        assert_eq!(eval("u8", "1 / (1 - 1)"), Err(ErrorKind::DivisionByZero));
+
+        let session = Session::new();
+        assert_eq!(session.evaluate("0x10 + 2^3").unwrap().to_f64(), 24.0);
+        assert!(session.evaluate("6 & 3").is_err());
+    }
+}
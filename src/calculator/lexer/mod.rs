@@ -0,0 +1,173 @@
+//! Lexer submodule.
+//!
+//! Splits an expression into tokens with their byte ranges; whitespace only separates the tokens.
+
+use std::ops::Range;
+
+use super::{
+    expression::{ErrorKind, ExpressionError},
+    integer,
+};
+
+/// The kind of a token.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TokenKind {
+    /// A decimal number, optionally with a fraction and an exponent, e.g. `1.5e-3`,
+    /// or a hexadecimal, octal or binary integer, e.g. `0xff`, `0o17` or `0b1010`.
+    Number(String),
+    /// A name: a letter or `_` followed by letters, digits or `_`.
+    Identifier(String),
+    /// A string between double quotes, without escapes, e.g. `"latency.csv"`.
+    Text(String),
+    /// One of `+ - * / % ^ ! ** & | ~ << >> =`.
+    Operator(&'static str),
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    /// The separator of the rows of a matrix.
+    Semicolon,
+}
+
+/// A token of an expression.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub kind: TokenKind,
+    /// The byte range of the token in the expression.
+    pub span: Range<usize>,
+}
+
+/// The operators, the longest first.
+const OPERATORS: &[&str] = &[
+    "**", "<<", ">>", "+", "-", "*", "/", "%", "^", "!", "&", "|", "~", "=",
+];
+
+/// Splits the expression into tokens.
+pub fn tokenize(expression: &str) -> Result<Vec<Token>, ExpressionError> {
+    let mut tokens = vec![];
+    let mut chars = expression.char_indices().peekable();
+
+    while let Some(&(start, char)) = chars.peek() {
+        let kind = if char.is_whitespace() {
+            chars.next();
+            continue;
+        } else if char.is_ascii_digit() || char == '.' {
+            let end = number_end(expression, start);
+            let literal = &expression[start..end];
+            while chars.next_if(|(index, _)| *index < end).is_some() {}
+            let valid = match integer::parse_radix(literal) {
+                Some(_) => true,
+                None => literal.parse::<f64>().is_ok(),
+            };
+            if !valid {
+                return Err(
+                    ExpressionError::new(ErrorKind::UnexpectedToken(literal.to_owned()))
+                        .at(Some(start..end)),
+                );
+            }
+            tokens.push(Token {
+                kind: TokenKind::Number(literal.to_owned()),
+                span: start..end,
+            });
+            continue;
+        } else if char.is_ascii_alphabetic() || char == '_' {
+            let mut end = start;
+            while let Some((index, char)) =
+                chars.next_if(|(_, char)| char.is_ascii_alphanumeric() || *char == '_')
+            {
+                end = index + char.len_utf8();
+            }
+            tokens.push(Token {
+                kind: TokenKind::Identifier(expression[start..end].to_owned()),
+                span: start..end,
+            });
+            continue;
+        } else if char == '"' {
+            let Some(length) = expression[start + 1..].find('"') else {
+                let end = expression.len();
+                return Err(ExpressionError::new(ErrorKind::MissingQuote).at(Some(start..end)));
+            };
+            let end = start + length + 2;
+            while chars.next_if(|(index, _)| *index < end).is_some() {}
+            tokens.push(Token {
+                kind: TokenKind::Text(expression[start + 1..end - 1].to_owned()),
+                span: start..end,
+            });
+            continue;
+        } else if let Some(operator) = OPERATORS
+            .iter()
+            .find(|operator| expression[start..].starts_with(**operator))
+        {
+            let end = start + operator.len();
+            while chars.next_if(|(index, _)| *index < end).is_some() {}
+            tokens.push(Token {
+                kind: TokenKind::Operator(operator),
+                span: start..end,
+            });
+            continue;
+        } else {
+            match char {
+                '(' => TokenKind::LeftParen,
+                ')' => TokenKind::RightParen,
+                '[' => TokenKind::LeftBracket,
+                ']' => TokenKind::RightBracket,
+                ',' => TokenKind::Comma,
+                ';' => TokenKind::Semicolon,
+                _ => {
+                    return Err(
+                        ExpressionError::new(ErrorKind::UnexpectedToken(char.to_string()))
+                            .at(Some(start..start + char.len_utf8())),
+                    )
+                }
+            }
+        };
+        chars.next();
+        tokens.push(Token {
+            kind,
+            span: start..start + char.len_utf8(),
+        });
+    }
+
+    Ok(tokens)
+}
+
+/// The end of the number starting at `start`: digits and `.`, then an optional exponent.
+/// The `e` of `2e` isn't an exponent, so that it reads as `2*e`.
+/// A `0x`, `0o` or `0b` prefix followed by a letter or a digit starts an integer, which ends
+/// with the letters, digits and `_` separators.
+fn number_end(expression: &str, start: usize) -> usize {
+    let bytes = expression.as_bytes();
+    let word = |byte: &u8| byte.is_ascii_alphanumeric() || *byte == b'_';
+    if bytes[start] == b'0'
+        && bytes
+            .get(start + 1)
+            .is_some_and(|byte| matches!(byte, b'x' | b'X' | b'o' | b'O' | b'b' | b'B'))
+        && bytes.get(start + 2).is_some_and(u8::is_ascii_alphanumeric)
+    {
+        return start
+            + 2
+            + bytes[start + 2..]
+                .iter()
+                .take_while(|byte| word(byte))
+                .count();
+    }
+
+    let mut end = start;
+    while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'.') {
+        end += 1;
+    }
+    if end < bytes.len() && matches!(bytes[end], b'e' | b'E') {
+        let mut exponent = end + 1;
+        if exponent < bytes.len() && matches!(bytes[exponent], b'+' | b'-') {
+            exponent += 1;
+        }
+        if exponent < bytes.len() && bytes[exponent].is_ascii_digit() {
+            end = exponent;
+            while end < bytes.len() && bytes[end].is_ascii_digit() {
+                end += 1;
+            }
+        }
+    }
+    end
+}
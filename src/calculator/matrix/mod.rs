@@ -0,0 +1,357 @@
+//! Matrix submodule.
+//!
+//! The matrices of the calculator, written by rows, e.g. `[1, 2; 3, 4]`; a vector is a matrix with
+//! one row, e.g. `[1, 2, 3]`, or one column, e.g. `[1; 2; 3]`. The elements are `f64` in every mode
+//! but the programmer mode, where the matrices are not available.
+//!
+//! `A * B` is the matrix product, which requires as many columns in `A` as rows in `B`, `A + B`
+//! requires the same shapes, and a number multiplies or divides every element.
+//! The determinant, the inverse and the solution of a linear system are computed by Gaussian
+//! elimination with partial pivoting, and a pivot within the rounding errors of the elimination
+//! makes the matrix singular.
+
+use ndarray::Array2;
+use std::fmt;
+
+use super::expression::ErrorKind;
+
+/// The largest number of rows or columns shown of a matrix, the middle ones of a larger one are elided.
+const MAX_SHOWN: usize = 10;
+
+/// The names of the matrix functions, with the description of their arguments.
+pub const FUNCTIONS: &[(&str, &str)] = &[
+    ("transpose", "a matrix"),
+    ("det", "a square matrix"),
+    ("inverse", "a square matrix"),
+    (
+        "linsolve",
+        "a square matrix and a vector, e.g. `linsolve([2, 1; 1, 3], [3; 5])`",
+    ),
+];
+
+/// The description of the arguments of the matrix function.
+pub fn expected(name: &str) -> Option<&'static str> {
+    FUNCTIONS
+        .iter()
+        .find(|(function, _)| *function == name)
+        .map(|(_, expected)| *expected)
+}
+
+/// The numbers of rows and columns of a matrix, e.g. `2x3`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Shape {
+    pub rows: u32,
+    pub columns: u32,
+}
+
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}x{}", self.rows, self.columns)
+    }
+}
+
+/// A matrix of `f64`, with at least one row and one column.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix {
+    values: Array2<f64>,
+}
+
+impl Matrix {
+    /// The matrix of the rows, which are not empty and have the same length.
+    pub fn new(rows: &[Vec<f64>]) -> Matrix {
+        Matrix {
+            values: Array2::from_shape_fn((rows.len(), rows[0].len()), |(row, column)| {
+                rows[row][column]
+            }),
+        }
+    }
+
+    pub fn shape(&self) -> Shape {
+        let (rows, columns) = self.values.dim();
+        Shape {
+            rows: rows as u32,
+            columns: columns as u32,
+        }
+    }
+
+    /// The elements, row by row.
+    pub fn rows(&self) -> Vec<Vec<f64>> {
+        self.values.outer_iter().map(|row| row.to_vec()).collect()
+    }
+
+    /// Whether every element is finite.
+    pub fn is_finite(&self) -> bool {
+        self.values.iter().all(|value| value.is_finite())
+    }
+
+    /// The matrix with the function applied to every element.
+    pub fn map(&self, f: impl Fn(f64) -> f64) -> Matrix {
+        Matrix {
+            values: self.values.mapv(f),
+        }
+    }
+
+    /// The sum with the other matrix multiplied by `sign`, i.e. 1 or -1; `operation` describes the sum.
+    pub fn add(
+        &self,
+        other: &Matrix,
+        sign: f64,
+        operation: impl Fn() -> String,
+    ) -> Result<Matrix, ErrorKind> {
+        if self.values.dim() != other.values.dim() {
+            return Err(ErrorKind::Shape(operation(), self.shape(), other.shape()));
+        }
+        Ok(Matrix {
+            values: &self.values + &(&other.values * sign),
+        })
+    }
+
+    /// The matrix product; `operation` describes the product.
+    pub fn multiply(
+        &self,
+        other: &Matrix,
+        operation: impl Fn() -> String,
+    ) -> Result<Matrix, ErrorKind> {
+        if self.values.ncols() != other.values.nrows() {
+            return Err(ErrorKind::Shape(operation(), self.shape(), other.shape()));
+        }
+        Ok(Matrix {
+            values: self.values.dot(&other.values),
+        })
+    }
+
+    /// The integer power of the square matrix, with the inverse for the negative exponents.
+    pub fn pow(&self, exponent: i64, operation: impl Fn() -> String) -> Result<Matrix, ErrorKind> {
+        let size = self.size(&operation)?;
+        let mut base = match exponent < 0 {
+            true => self.inverse(&operation)?,
+            false => self.clone(),
+        };
+        let mut result = Matrix {
+            values: Array2::eye(size),
+        };
+        let mut exponent = exponent.unsigned_abs();
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result.values = result.values.dot(&base.values);
+            }
+            base.values = base.values.dot(&base.values);
+            exponent >>= 1;
+        }
+        Ok(result)
+    }
+
+    pub fn transpose(&self) -> Matrix {
+        Matrix {
+            values: self.values.t().to_owned(),
+        }
+    }
+
+    /// The determinant of the square matrix, zero if it's singular.
+    pub fn determinant(&self, operation: impl Fn() -> String) -> Result<f64, ErrorKind> {
+        self.size(&operation)?;
+        let mut values = self.values.clone();
+        let tolerance = tolerance(&values);
+        let mut determinant = 1.0;
+        for pivot in 0..values.nrows() {
+            let row = pivot_row(&values, pivot);
+            if values[[row, pivot]].abs() <= tolerance {
+                return Ok(0.0);
+            }
+            if row != pivot {
+                swap_rows(&mut values, row, pivot);
+                determinant = -determinant;
+            }
+            determinant *= values[[pivot, pivot]];
+            for row in pivot + 1..values.nrows() {
+                let factor = values[[row, pivot]] / values[[pivot, pivot]];
+                for column in pivot..values.ncols() {
+                    values[[row, column]] -= factor * values[[pivot, column]];
+                }
+            }
+        }
+        Ok(determinant)
+    }
+
+    /// The inverse of the square matrix.
+    pub fn inverse(&self, operation: impl Fn() -> String) -> Result<Matrix, ErrorKind> {
+        let size = self.size(&operation)?;
+        self.solve(
+            &Matrix {
+                values: Array2::eye(size),
+            },
+            operation,
+        )
+    }
+
+    /// The solution `X` of `A X = B`, where `A` is this square matrix, and `B` has as many rows;
+    /// a row vector `B` is solved as a column, and the solution is a row as well.
+    pub fn solve(
+        &self,
+        right: &Matrix,
+        operation: impl Fn() -> String,
+    ) -> Result<Matrix, ErrorKind> {
+        let size = self.size(&operation)?;
+        if right.values.nrows() == 1 && right.values.ncols() == size && size > 1 {
+            return self
+                .solve(&right.transpose(), operation)
+                .map(|solution| solution.transpose());
+        }
+        if right.values.nrows() != size {
+            return Err(ErrorKind::Shape(operation(), self.shape(), right.shape()));
+        }
+
+        let (mut values, mut solution) = (self.values.clone(), right.values.clone());
+        let tolerance = tolerance(&values);
+        for pivot in 0..size {
+            let row = pivot_row(&values, pivot);
+            if values[[row, pivot]].abs() <= tolerance {
+                return Err(ErrorKind::Singular(operation()));
+            }
+            swap_rows(&mut values, row, pivot);
+            swap_rows(&mut solution, row, pivot);
+
+            let divisor = values[[pivot, pivot]];
+            values.row_mut(pivot).mapv_inplace(|value| value / divisor);
+            solution
+                .row_mut(pivot)
+                .mapv_inplace(|value| value / divisor);
+            for row in (0..size).filter(|row| *row != pivot) {
+                let factor = values[[row, pivot]];
+                if factor == 0.0 {
+                    continue;
+                }
+                let (pivot_values, pivot_solution) =
+                    (values.row(pivot).to_owned(), solution.row(pivot).to_owned());
+                values
+                    .row_mut(row)
+                    .scaled_add(-factor, &pivot_values.view());
+                solution
+                    .row_mut(row)
+                    .scaled_add(-factor, &pivot_solution.view());
+            }
+        }
+        Ok(Matrix { values: solution })
+    }
+
+    /// The matrix with the formatted elements, e.g. `[1, 2; 3, 4]`; the middle rows and columns
+    /// of a large matrix are elided, and its shape follows, e.g. `[1, 2, 3, 4, 5, 6, 7, 8, ..., 50] (1x50)`.
+    pub fn format(&self, element: impl Fn(f64) -> String) -> String {
+        let rows: Vec<String> = self
+            .cells(element)
+            .iter()
+            .map(|row| row.join(", "))
+            .collect();
+        let shape = self.shape();
+        match shape.rows as usize > MAX_SHOWN || shape.columns as usize > MAX_SHOWN {
+            true => format!("[{}] ({})", rows.join("; "), shape),
+            false => format!("[{}]", rows.join("; ")),
+        }
+    }
+
+    /// The literal of the matrix with every element, e.g. `[1, 2; 3, 4]`.
+    pub fn to_expression(&self) -> String {
+        let rows: Vec<String> = self
+            .rows()
+            .iter()
+            .map(|row| {
+                let elements: Vec<String> = row.iter().map(f64::to_string).collect();
+                elements.join(", ")
+            })
+            .collect();
+        format!("[{}]", rows.join("; "))
+    }
+
+    /// The lines of the matrix with the formatted elements aligned in columns, the middle rows
+    /// and columns of a large matrix elided.
+    pub fn table(&self, element: impl Fn(f64) -> String) -> Vec<String> {
+        let cells = self.cells(element);
+        let widths: Vec<usize> = (0..cells[0].len())
+            .map(|column| {
+                cells
+                    .iter()
+                    .map(|row| row[column].chars().count())
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+        cells
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| format!("{:>width$}", cell))
+                    .collect();
+                cells.join("  ")
+            })
+            .collect()
+    }
+
+    /// The formatted elements, row by row, with `...` for the elided rows and columns.
+    fn cells(&self, element: impl Fn(f64) -> String) -> Vec<Vec<String>> {
+        let columns = shown(self.values.ncols());
+        shown(self.values.nrows())
+            .iter()
+            .map(|row| {
+                columns
+                    .iter()
+                    .map(|column| match (row, column) {
+                        (Some(row), Some(column)) => element(self.values[[*row, *column]]),
+                        _ => String::from("..."),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The size of the square matrix.
+    fn size(&self, operation: impl Fn() -> String) -> Result<usize, ErrorKind> {
+        match self.values.nrows() == self.values.ncols() {
+            true => Ok(self.values.nrows()),
+            false => Err(ErrorKind::NotSquare(operation(), self.shape())),
+        }
+    }
+}
+
+/// The indices of the rows or the columns shown of a matrix, `None` for the elided ones.
+fn shown(count: usize) -> Vec<Option<usize>> {
+    match count > MAX_SHOWN {
+        true => (0..MAX_SHOWN - 2)
+            .map(Some)
+            .chain([None, Some(count - 1)])
+            .collect(),
+        false => (0..count).map(Some).collect(),
+    }
+}
+
+/// The rounding errors of the elimination of the values: the pivots within it are zero.
+fn tolerance(values: &Array2<f64>) -> f64 {
+    let largest = values
+        .iter()
+        .fold(0.0, |largest: f64, value| largest.max(value.abs()));
+    largest * values.nrows() as f64 * f64::EPSILON
+}
+
+/// The row of the largest pivot of the column, from the row of the column's index.
+fn pivot_row(values: &Array2<f64>, column: usize) -> usize {
+    (column..values.nrows())
+        .max_by(|a, b| {
+            values[[*a, column]]
+                .abs()
+                .total_cmp(&values[[*b, column]].abs())
+        })
+        .unwrap_or(column)
+}
+
+fn swap_rows(values: &mut Array2<f64>, a: usize, b: usize) {
+    if a == b {
+        return;
+    }
+    for column in 0..values.ncols() {
+        values.swap([a, column], [b, column]);
+    }
+}
+
+#[cfg(test)]
+mod tests;
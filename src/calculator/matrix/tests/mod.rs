@@ -0,0 +1,83 @@
+mod matrix {
+    use crate::calculator::{
+        expression::{evaluate, ErrorKind},
+        matrix::Shape,
+        number::Mode,
+        session::Session,
+    };
+
+    /// Evaluates the expression in a new session, and formats the result with 6 decimal places.
+    fn eval(expression: &str) -> Result<String, ErrorKind> {
+        evaluate(expression, &Session::new())
+            .map(|value| value.format(Mode::Decimal(6)))
+            .map_err(|error| error.kind)
+    }
+
+    #[test]
+    fn linear_algebra() {
+        assert_eq!(eval("[1, 2; 3, 4] * [5; 6]").unwrap(), "[17; 39]");
+        assert_eq!(eval("2[1, 2] - [1, 1] / 2").unwrap(), "[1.5, 3.5]");
+        assert_eq!(eval("transpose([1, 2, 3])").unwrap(), "[1; 2; 3]");
+        assert_eq!(eval("det([1, 2; 3, 4])").unwrap(), "-2");
+        assert_eq!(eval("det([1, 2, 3; 4, 5, 6; 7, 8, 9])").unwrap(), "0");
+        assert_eq!(eval("inverse([1, 2; 3, 4])").unwrap(), "[-2, 1; 1.5, -0.5]");
+        assert_eq!(eval("[1, 1; 1, 0]^10").unwrap(), "[89, 55; 55, 34]");
+        assert_eq!(
+            eval("linsolve([2, 1, -1; -3, -1, 2; -2, 1, 2], [8; -11; -3])").unwrap(),
+            "[2; 3; -1]"
+        );
+        assert_eq!(
+            eval("linsolve([2, 1; 1, 3], [3, 5])").unwrap(),
+            "[0.8, 1.4]"
+        );
+    }
+
+    #[test]
+    fn shape_errors() {
+        let shape = |rows, columns| Shape { rows, columns };
+        assert_eq!(
+            eval("[1, 2; 3, 4] * [1, 2]"),
+            Err(ErrorKind::Shape(
+                String::from("[1, 2; 3, 4] * [1, 2]"),
+                shape(2, 2),
+                shape(1, 2)
+            ))
+        );
+        assert_eq!(
+            eval("[1, 2] + [1; 2]"),
+            Err(ErrorKind::Shape(
+                String::from("[1, 2] + [1; 2]"),
+                shape(1, 2),
+                shape(2, 1)
+            ))
+        );
+        assert_eq!(
+            eval("det([1, 2, 3])"),
+            Err(ErrorKind::NotSquare(
+                String::from("det([1, 2, 3])"),
+                shape(1, 3)
+            ))
+        );
+        assert_eq!(
+            eval("inverse([1, 2; 2, 4])"),
+            Err(ErrorKind::Singular(String::from("inverse([1, 2; 2, 4])")))
+        );
+        assert_eq!(
+            eval("[1, 2; 3]"),
+            Err(ErrorKind::RaggedMatrix {
+                row: 2,
+                columns: 1,
+                expected: 2
+            })
+        );
+        assert_eq!(eval("[1, 2"), Err(ErrorKind::MissingBracket));
+        assert_eq!(
+            eval("[1, 2 km]"),
+            Err(ErrorKind::Element(String::from("2000 m")))
+        );
+        assert_eq!(
+            eval("sqrt([4])"),
+            Err(ErrorKind::Matrix(String::from("sqrt([4])")))
+        );
+    }
+}
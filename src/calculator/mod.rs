@@ -1,89 +1,460 @@
+//! Calculator module.
+//!
+//! The calculator is a REPL: expressions, assignments like `x = 3.5`, the previous result `ans`,
+//! functions like `f(x, y) = x^2 + y`, and commands like `:vars`, see the `session` submodule.
+//! An invalid input is reported with a caret under the offending part, and the REPL goes on.
+//! The arithmetic is `f64` by default, exact with `--exact` and `--precision N`, or with fixed-width
+//! integers and bitwise operators with `--programmer TYPE`, see the `number` submodule.
+//! Numbers may have units, e.g. `3 km + 200 m in mi`, see the `unit` submodule.
+//! `:plot` draws functions of `x` to a file or to the terminal, see the `plot` submodule.
+//! With `--batch FILE`, the calculator evaluates the lines of the file instead, see the `batch` submodule.
+
+use clap::Args;
 use colored::Colorize;
-use meval::eval_str;
-use std::{env::args, io};
+use serde::Serialize;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{console, output, prompt};
+
+pub mod ast;
+pub mod batch;
+mod editor;
+pub mod expression;
+pub mod integer;
+pub mod lexer;
+pub mod matrix;
+pub mod number;
+pub mod numeric;
+pub mod parser;
+pub mod plot;
+pub mod session;
+pub mod statistics;
+pub mod unit;
+
+pub use self::{
+    number::{Mode, Number},
+    session::{CalculatorError, Outcome, Session},
+};
+
+/// The exit code used when the expression argument is invalid in the non-interactive mode, or a batch line fails.
+pub const EXIT_CODE: i32 = 10;
 
 /// The entry point of the program.
-pub fn main() {
-    Calculator::new();
+pub fn main(args: InuputArguments) {
+    Calculator::new(args);
+}
+
+/// The budget of the evaluation steps of `evaluate`.
+pub const EVALUATION_STEPS: u64 = 100_000;
+
+/// Evaluates the expression, without variables, in a sandboxed session: `load` is rejected,
+/// and so is an expression of more than `EVALUATION_STEPS` steps.
+pub fn evaluate(expression: &str) -> Result<Number, CalculatorError> {
+    Session::new()
+        .sandbox()
+        .budget(EVALUATION_STEPS)
+        .evaluate(expression)
 }
 
 /// Input arguments of the program.
-struct InuputArguments {
+#[derive(Args, Default)]
+pub struct InuputArguments {
+    /// The expression or assignment to evaluate first; the calculator prompts for further input.
+    #[arg(allow_hyphen_values = true)]
     expression: Option<String>,
+    /// Computes exactly with fractions and big integers, e.g. `1/3 + 1/6` is `1/2`.
+    #[arg(long, conflicts_with_all = ["precision", "programmer"])]
+    exact: bool,
+    /// Computes exactly, and prints the results rounded to N decimal places.
+    #[arg(long, value_name = "N", conflicts_with = "programmer", value_parser = clap::value_parser!(u64).range(..=number::MAX_PLACES as u64))]
+    precision: Option<u64>,
+    /// Computes with the integer type, u8 to u64 or i8 to i64, with wraparound and bitwise operators.
+    #[arg(long, value_name = "TYPE")]
+    programmer: Option<integer::IntegerType>,
+    /// Evaluates the lines of the file in order, or of the standard input if FILE is `-` or omitted,
+    /// and prints a row per line; exits with an error if any line fails.
+    #[arg(long, value_name = "FILE", num_args = 0..=1, default_missing_value = "-", conflicts_with = "expression")]
+    batch: Option<PathBuf>,
+    /// The format of the batch results, `table` by default.
+    #[arg(long, value_enum, requires = "batch", conflicts_with = "expression")]
+    format: Option<batch::BatchFormat>,
 }
 
-struct Calculator;
+impl InuputArguments {
+    /// The arithmetic mode of the arguments.
+    fn mode(&self) -> Mode {
+        match (self.exact, self.precision, self.programmer) {
+            (_, _, Some(integer_type)) => Mode::Programmer(integer_type),
+            (_, Some(places), None) => Mode::Decimal(places as usize),
+            (true, None, None) => Mode::Exact,
+            (false, None, None) => Mode::Float,
+        }
+    }
+}
 
-impl Calculator {
-    /// Program constructor.
-    fn new() -> Calculator {
-        let mut program = Calculator;
-        program.init();
-        program
+/// The `calculator.result` output record, and the response of the server calculator.
+#[derive(Serialize)]
+pub struct ResultRecord<'a> {
+    pub expression: &'a str,
+    #[serde(flatten)]
+    pub value: ValueFields<'a>,
+}
+
+/// The `calculator.line` output record of a batch line.
+#[derive(Serialize)]
+struct LineRecord<'a> {
+    line: usize,
+    expression: &'a str,
+    /// The fields of the `calculator.result` record, if the line has a value.
+    #[serde(flatten)]
+    value: Option<ValueFields<'a>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// The `calculator.error` output record of an invalid input line.
+#[derive(Serialize)]
+pub struct ErrorRecord<'a> {
+    pub expression: &'a str,
+    /// The error, with its causes.
+    pub error: String,
+    /// A suggestion to fix the expression.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<&'a str>,
+}
+
+impl<'a> ErrorRecord<'a> {
+    /// The record of the error of the input line.
+    pub fn new(expression: &'a str, error: &'a CalculatorError) -> ErrorRecord<'a> {
+        ErrorRecord {
+            expression,
+            error: error.describe(),
+            hint: error
+                .expression_error()
+                .and_then(|(_, error)| error.hint.as_deref()),
+        }
     }
+}
 
-    /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "Calculator initialized.".blue().bold());
+/// The `calculator.plot` output record.
+#[derive(Serialize)]
+struct PlotRecord<'a> {
+    path: &'a Path,
+}
 
-        let args = self.args();
+/// The fields of the records with a value.
+#[derive(Serialize)]
+pub struct ValueFields<'a> {
+    /// The nearest `f64` of an exact result, the value of a quantity in its unit, or the real part
+    /// of a complex number; `null` for a matrix.
+    result: f64,
+    /// The imaginary part of a complex number.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    imaginary: Option<f64>,
+    /// The elements of a matrix, row by row.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matrix: Option<Vec<Vec<f64>>>,
+    /// The unit of a quantity, e.g. `mi` or `m/s`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unit: Option<String>,
+    /// The exact result as a fraction, e.g. `1/3`, in the exact modes, or the integer in the programmer mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    exact: Option<String>,
+    /// The bits of the integer in hexadecimal, e.g. `0xff`, in the programmer mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hex: Option<String>,
+    /// The bits of the integer in binary, e.g. `0b1111_1111`, in the programmer mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bin: Option<String>,
+    /// Whether the result is a `f64` approximation in the exact and decimal modes, e.g. `sqrt(2)`.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    approximate: bool,
+    /// The assigned variable, if the input is an assignment.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    variable: Option<&'a str>,
+}
 
-        self.calculate(args.expression);
+impl<'a> ValueFields<'a> {
+    /// The fields of the value in the mode, and of the assigned variable if any.
+    pub fn new(value: &Number, mode: Mode, variable: Option<&'a str>) -> ValueFields<'a> {
+        ValueFields {
+            result: match value {
+                Number::Complex(value) => value.re,
+                value => value.to_f64(),
+            },
+            imaginary: match value {
+                Number::Complex(value) => Some(value.im),
+                _ => None,
+            },
+            matrix: match value {
+                Number::Matrix(value) => Some(value.rows()),
+                _ => None,
+            },
+            unit: match value {
+                Number::Quantity(value) => Some(value.unit()),
+                _ => None,
+            },
+            exact: match value {
+                Number::Exact(value) => Some(value.to_string()),
+                Number::Integer(value) => Some(value.to_string()),
+                _ => None,
+            },
+            hex: match value {
+                Number::Integer(value) => Some(value.hex()),
+                _ => None,
+            },
+            bin: match value {
+                Number::Integer(value) => Some(value.bin()),
+                _ => None,
+            },
+            approximate: value.is_approximate(mode),
+            variable,
+        }
     }
+}
 
-    /// Parses arguments passed to the program.
-    fn args(&mut self) -> InuputArguments {
-        let arguments: Vec<String> = args().collect();
+struct Calculator;
 
-        println!("\n{}:\n{:?}", "Arguments".cyan().bold(), arguments);
+impl Calculator {
+    /// Program constructor.
+    fn new(args: InuputArguments) -> Calculator {
+        let mut program = Calculator;
+        program.init(args);
+        program
+    }
 
-        InuputArguments {
-            expression: arguments.get(2).cloned(),
+    /// Initializes the program.
+    fn init(&mut self, args: InuputArguments) {
+        let mode = args.mode();
+        if let Some(path) = args.batch {
+            // The batch results may be piped, e.g. as CSV, so they are printed alone.
+            self.batch(&path, args.format.unwrap_or_default(), mode);
+            return;
         }
+
+        human_println!("\n{}", "Calculator initialized.".blue().bold());
+        self.calculate(args.expression, mode);
     }
 
-    /// The calculator program expresion parsing and evaluation logic.
-    fn calculate(&mut self, expression_arg: Option<String>) {
-        let is_some = expression_arg.is_some();
-        let mut expression_arg_input = if is_some {
-            match expression_arg.unwrap().trim().parse::<String>() {
-                Ok(value) => value,
-                Err(_) => String::new(),
-            }
+    /// Evaluates the lines of the file, or of the standard input if the path is `-`, and prints the results.
+    fn batch(&mut self, path: &Path, format: batch::BatchFormat, mode: Mode) {
+        let content = if path.as_os_str() == "-" {
+            let mut content = String::new();
+            while console::read_line(&mut content).is_ok_and(|read| read > 0) {}
+            content
         } else {
-            String::new()
+            match fs::read_to_string(path) {
+                Ok(content) => content,
+                Err(error) => {
+                    human_eprintln!(
+                        "{}: can't read the batch file {:?}: {}",
+                        "Error".red().bold(),
+                        path,
+                        error
+                    );
+                    console::exit(EXIT_CODE);
+                }
+            }
         };
 
-        loop {
-            let mut expression_input = String::new();
+        let mut session = Session::new();
+        session.set_mode(mode);
+        let lines = batch::run(&mut session, content.lines().map(str::to_owned));
 
-            if expression_arg_input.trim().is_empty() && expression_input.trim().is_empty() {
-                println!(
-                    "\n{}",
-                    "Please input an expression without spaces:".yellow().bold()
-                );
+        match format {
+            batch::BatchFormat::Table => {
+                for row in batch::table(&lines) {
+                    human_println!("{}", row);
+                }
+            }
+            batch::BatchFormat::Csv => human_println!("{}", batch::csv(&lines).trim_end()),
+        }
 
-                io::stdin()
-                    .read_line(&mut expression_input)
-                    .expect("Failed to read line");
-            } else if expression_input.trim().is_empty() {
-                expression_input = expression_arg_input.to_string();
+        let mut failures = 0;
+        for line in &lines {
+            let error = line.error();
+            if let Some(error) = &error {
+                failures += 1;
+                human_eprintln!("{}: line {}: {}", "Error".red().bold(), line.number, error);
             }
+            output::emit(
+                "calculator.line",
+                &LineRecord {
+                    line: line.number,
+                    expression: &line.input,
+                    value: line
+                        .value()
+                        .map(|value| ValueFields::new(value, line.mode, line.variable())),
+                    error,
+                },
+            );
+        }
+        if failures > 0 {
+            human_eprintln!(
+                "{}",
+                format!("{} of {} lines failed.", failures, lines.len()).red()
+            );
+            console::exit(EXIT_CODE);
+        }
+    }
+
+    /// The calculator REPL: reads, evaluates and prints the input lines until the session ends.
+    fn calculate(&mut self, expression_arg: Option<String>, mode: Mode) {
+        let mut session = Session::new();
+        session.set_mode(mode);
 
-            if expression_input.trim().is_empty() {
-                expression_arg_input = String::new();
-                continue;
+        if let Some(expression) = expression_arg.filter(|arg| !arg.trim().is_empty()) {
+            let outcome = self.execute(&mut session, &expression);
+            if !prompt::is_interactive() || outcome == Some(Outcome::Quit) {
+                return;
             }
+        }
 
-            let expression = expression_input.as_str().trim().to_string();
+        let mut editor = editor::Editor::new();
 
-            println!("\n{}: {}", "Your expression".cyan(), expression);
+        while let Some(line) = editor.read_line() {
+            if self.execute(&mut session, &line) == Some(Outcome::Quit) {
+                break;
+            }
+        }
+    }
 
-            let result = eval_str(expression.as_str()).unwrap();
+    /// Executes the input line and prints the outcome.
+    /// An invalid line ends the program with an error in the non-interactive mode.
+    fn execute(&mut self, session: &mut Session, line: &str) -> Option<Outcome> {
+        match session.execute(line) {
+            Ok(outcome) => {
+                self.print_outcome(session.mode(), line.trim(), &outcome);
+                Some(outcome)
+            }
+            Err(error) => {
+                self.print_error(line.trim(), &error);
+                if !prompt::is_interactive() {
+                    console::exit(EXIT_CODE);
+                }
+                None
+            }
+        }
+    }
 
-            println!("\n{}: {}", "Result".green().bold(), result);
+    /// Prints the error, and the offending part of the expression with a caret under it.
+    fn print_error(&mut self, line: &str, error: &CalculatorError) {
+        human_println!("{}: {}", "Error".red().bold(), error.describe());
+        output::emit("calculator.error", &ErrorRecord::new(line, error));
+        if let Some((expression, error)) = error.expression_error() {
+            if let Some(underline) = error.underline(expression) {
+                human_println!("  {}", expression);
+                human_println!("  {}", underline.red().bold());
+            }
+            if let Some(hint) = &error.hint {
+                human_println!("{}: {}", "Hint".cyan().bold(), hint);
+            }
+        }
+    }
 
-            expression_arg_input = String::new();
+    /// Prints the outcome of the input line.
+    fn print_outcome(&mut self, mode: Mode, line: &str, outcome: &Outcome) {
+        match outcome {
+            Outcome::Value { name, value } => {
+                // A matrix with several rows is printed below, a row per line.
+                let formatted = match value {
+                    Number::Matrix(matrix) if matrix.shape().rows > 1 => {
+                        format!("{} matrix", matrix.shape())
+                    }
+                    value => value.format_result(mode),
+                };
+                match name {
+                    Some(name) => human_println!("{} = {}", name.cyan(), formatted),
+                    None => human_println!("{}: {}", "Result".green().bold(), formatted),
+                }
+                if let Number::Matrix(matrix) = value {
+                    if matrix.shape().rows > 1 {
+                        for row in matrix.table(|element| Number::Float(element).format(mode)) {
+                            human_println!("  {}", row);
+                        }
+                    }
+                }
+                output::emit(
+                    "calculator.result",
+                    &ResultRecord {
+                        expression: line,
+                        value: ValueFields::new(value, mode, name.as_deref()),
+                    },
+                );
+            }
+            Outcome::Variables(variables) if variables.is_empty() => {
+                human_println!("{}", "No variables.".yellow());
+            }
+            Outcome::Variables(variables) => {
+                for (name, value) in variables {
+                    human_println!("{} = {}", name.cyan(), value.format_result(mode));
+                }
+            }
+            Outcome::Defined(function) => {
+                human_println!("{} {}", "Defined".green(), function.to_string().cyan());
+            }
+            Outcome::Functions(functions) if functions.is_empty() => {
+                human_println!("{}", "No functions.".yellow());
+            }
+            Outcome::Functions(functions) => {
+                for function in functions {
+                    human_println!("{}", function.to_string().cyan());
+                }
+            }
+            Outcome::Cleared => {
+                human_println!("{}", "Variables and functions cleared.".yellow());
+            }
+            Outcome::Saved {
+                path,
+                variables,
+                functions,
+            } => {
+                human_println!(
+                    "{} {} variables and {} functions to {:?}",
+                    "Saved".green(),
+                    variables,
+                    functions,
+                    path
+                );
+            }
+            Outcome::Loaded { path, statements } => {
+                human_println!(
+                    "{} {} statements from {:?}",
+                    "Loaded".green(),
+                    statements,
+                    path
+                );
+            }
+            Outcome::Plot(plot) => {
+                for line in plot.preview() {
+                    human_println!("{}", line);
+                }
+            }
+            Outcome::Plotted { path, functions } => {
+                human_println!(
+                    "{} {} functions to {:?}",
+                    "Plotted".green(),
+                    functions,
+                    path
+                );
+                output::emit("calculator.plot", &PlotRecord { path });
+            }
+            Outcome::Mode(mode) => human_println!("{}: {}", "Mode".green(), mode),
+            Outcome::Ast(tree) => human_println!("{}", tree.trim_end()),
+            Outcome::Help => {
+                human_println!(
+                    "{}",
+                    "Input an expression, e.g. `2*pi*r`, an assignment, e.g. `r = 3.5`, a function, e.g. `area(r) = pi r^2`, or a command:"
+                        .yellow()
+                );
+                for (command, description) in session::COMMANDS {
+                    human_println!("  {:<12} {}", command, description);
+                }
+            }
+            Outcome::Quit | Outcome::Empty => {}
         }
     }
 }
@@ -0,0 +1,1018 @@
+//! Number submodule.
+//!
+//! The numbers of the calculator, and the arithmetic of its modes:
+//! - `float`, the default: every number is a `f64`;
+//! - `exact`: the literals are rationals with big integers, e.g. `1/3 + 1/6` is `1/2`;
+//! - `decimal N`: exact as well, and the results are printed rounded to `N` decimal places;
+//! - the programmer mode, e.g. `u32`: fixed-width integers, see the `integer` submodule.
+//!
+//! A number may also be a quantity with units, e.g. `3 km`, see the `unit` submodule, in the `f64`
+//! arithmetic of every mode but the programmer mode.
+//!
+//! A number may also be complex, e.g. `3 + 4i`, or a matrix, e.g. `[1, 2; 3, 4]`, see the `matrix`
+//! submodule, in the `f64` arithmetic of every mode but the programmer mode; a complex number stays
+//! complex, e.g. `(1 + 2i) * (1 - 2i)` is `5 + 0i`, and `abs`, `arg`, `re` and `im` give a real number.
+//!
+//! In the exact modes, the operations without an exact result, e.g. `sqrt` or `pi`, give a `f64`,
+//! and so does any operation with a `f64` operand.
+//! The bitwise operators are only available in the programmer mode, and the operations with
+//! a non-integer result, e.g. `sqrt` or `pi`, are not.
+
+use num_bigint::BigInt;
+use num_complex::Complex64;
+use num_integer::Integer as _;
+use num_traits::{FromPrimitive, One, Signed, ToPrimitive, Zero};
+use std::{cmp::Ordering, fmt};
+
+use super::{
+    ast::Infix,
+    expression::{ErrorKind, FUNCTIONS},
+    integer::{self, Integer, IntegerType},
+    matrix::{self, Matrix},
+    statistics,
+    unit::{Dimension, Label, Quantity},
+};
+
+/// The largest integer with a factorial representable by a `f64`.
+const MAX_FACTORIAL: f64 = 170.0;
+
+/// The size limit of the exact results, in bits, about 79k decimal digits.
+pub const MAX_BITS: u64 = 1 << 18;
+
+/// The largest number of decimal places of the `decimal` mode.
+pub const MAX_PLACES: usize = 1000;
+
+/// The arithmetic of a calculator session.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    #[default]
+    Float,
+    Exact,
+    /// Exact, printed with the number of decimal places.
+    Decimal(usize),
+    /// Fixed-width integers of the type, printed in decimal, hexadecimal and binary.
+    Programmer(IntegerType),
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Float => write!(f, "float"),
+            Mode::Exact => write!(f, "exact"),
+            Mode::Decimal(places) => write!(f, "decimal {}", places),
+            Mode::Programmer(integer_type) => write!(f, "{}", integer_type),
+        }
+    }
+}
+
+/// An exact fraction, with a positive denominator and no common factor.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rational {
+    numerator: BigInt,
+    denominator: BigInt,
+}
+
+impl Rational {
+    /// The normalized fraction; the denominator isn't zero.
+    pub fn new(numerator: BigInt, denominator: BigInt) -> Rational {
+        let divisor = numerator.gcd(&denominator);
+        let sign = if denominator.is_negative() {
+            -BigInt::one()
+        } else {
+            BigInt::one()
+        };
+        Rational {
+            numerator: &numerator / &divisor * &sign,
+            denominator: &denominator / &divisor * &sign,
+        }
+    }
+
+    /// The integer.
+    pub fn integer(value: impl Into<BigInt>) -> Rational {
+        Rational {
+            numerator: value.into(),
+            denominator: BigInt::one(),
+        }
+    }
+
+    /// Parses a literal, e.g. `1.5e-3` or `0xff`; `None` if it's invalid, or if its exponent is too large.
+    pub fn parse(literal: &str) -> Option<Rational> {
+        if let Some(integer) = integer::parse_radix(literal) {
+            return Some(Rational::integer(integer));
+        }
+        let (mantissa, exponent) = match literal.find(['e', 'E']) {
+            Some(index) => (&literal[..index], literal[index + 1..].parse::<i64>().ok()?),
+            None => (literal, 0),
+        };
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let digits = format!("{}{}", integer, fraction);
+        let numerator = if digits.is_empty() {
+            return None;
+        } else {
+            digits.parse::<BigInt>().ok()?
+        };
+
+        let exponent = exponent - fraction.len() as i64;
+        if exponent.unsigned_abs() as f64 * 10f64.log2() > MAX_BITS as f64 {
+            return None;
+        }
+        let scale = BigInt::from(10).pow(exponent.unsigned_abs() as u32);
+        Some(if exponent < 0 {
+            Rational::new(numerator, scale)
+        } else {
+            Rational::integer(numerator * scale)
+        })
+    }
+
+    pub fn numerator(&self) -> &BigInt {
+        &self.numerator
+    }
+
+    pub fn denominator(&self) -> &BigInt {
+        &self.denominator
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denominator.is_one()
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.numerator.is_zero()
+    }
+
+    /// The nearest `f64`, scaling the big numerators and denominators down first.
+    pub fn to_f64(&self) -> f64 {
+        let excess = self
+            .numerator
+            .bits()
+            .max(self.denominator.bits())
+            .saturating_sub(1000);
+        let numerator = (&self.numerator >> excess).to_f64().unwrap_or(f64::NAN);
+        let denominator = (&self.denominator >> excess).to_f64().unwrap_or(f64::NAN);
+        numerator / denominator
+    }
+
+    pub fn add(&self, other: &Rational) -> Rational {
+        Rational::new(
+            &self.numerator * &other.denominator + &other.numerator * &self.denominator,
+            &self.denominator * &other.denominator,
+        )
+    }
+
+    pub fn subtract(&self, other: &Rational) -> Rational {
+        self.add(&other.negate())
+    }
+
+    pub fn multiply(&self, other: &Rational) -> Rational {
+        Rational::new(
+            &self.numerator * &other.numerator,
+            &self.denominator * &other.denominator,
+        )
+    }
+
+    /// The quotient; the divisor isn't zero.
+    pub fn divide(&self, other: &Rational) -> Rational {
+        Rational::new(
+            &self.numerator * &other.denominator,
+            &self.denominator * &other.numerator,
+        )
+    }
+
+    /// The remainder of the truncated division, with the sign of the dividend like `f64`'s `%`.
+    pub fn remainder(&self, other: &Rational) -> Rational {
+        let quotient = Rational::integer(self.divide(other).trunc());
+        self.subtract(&other.multiply(&quotient))
+    }
+
+    pub fn negate(&self) -> Rational {
+        Rational {
+            numerator: -&self.numerator,
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    pub fn abs(&self) -> Rational {
+        Rational {
+            numerator: self.numerator.abs(),
+            denominator: self.denominator.clone(),
+        }
+    }
+
+    /// The integer part, rounded toward zero.
+    pub fn trunc(&self) -> BigInt {
+        &self.numerator / &self.denominator
+    }
+
+    pub fn floor(&self) -> BigInt {
+        self.numerator.div_floor(&self.denominator)
+    }
+
+    pub fn ceil(&self) -> BigInt {
+        -(-&self.numerator).div_floor(&self.denominator)
+    }
+
+    /// The nearest integer, rounding half away from zero like `f64::round`.
+    pub fn round(&self) -> BigInt {
+        let rounded = Rational::new(
+            self.numerator.abs() * 2 + &self.denominator,
+            &self.denominator * 2,
+        )
+        .floor();
+        if self.numerator.is_negative() {
+            -rounded
+        } else {
+            rounded
+        }
+    }
+
+    /// The power with an integer exponent.
+    pub fn pow(&self, exponent: &BigInt) -> Result<Rational, ErrorKind> {
+        if self.numerator.abs().is_one() && self.denominator.is_one() {
+            let odd = exponent.is_odd();
+            return Ok(Rational::integer(if self.numerator.is_negative() && odd {
+                -1
+            } else {
+                1
+            }));
+        }
+        if self.is_zero() {
+            return match exponent.sign() {
+                num_bigint::Sign::Minus => Err(ErrorKind::DivisionByZero),
+                num_bigint::Sign::NoSign => Ok(Rational::integer(1)),
+                num_bigint::Sign::Plus => Ok(Rational::integer(0)),
+            };
+        }
+
+        let magnitude = exponent.abs().to_u64().filter(|magnitude| {
+            self.numerator.bits().max(self.denominator.bits()) * magnitude <= MAX_BITS
+        });
+        let Some(magnitude) = magnitude else {
+            return Err(ErrorKind::TooLarge(format!("{} ^ {}", self, exponent)));
+        };
+        let power = Rational {
+            numerator: num_traits::pow(self.numerator.clone(), magnitude as usize),
+            denominator: num_traits::pow(self.denominator.clone(), magnitude as usize),
+        };
+        Ok(if exponent.is_negative() {
+            Rational::integer(1).divide(&power)
+        } else {
+            power
+        })
+    }
+
+    /// The factorial of the non-negative integer.
+    pub fn factorial(&self) -> Result<Rational, ErrorKind> {
+        if !self.is_integer() || self.numerator.is_negative() {
+            return Err(ErrorKind::Factorial(self.to_string()));
+        }
+        // log2(n!) < n log2(n)
+        let too_large = || ErrorKind::TooLarge(format!("{}!", self));
+        let n = self.numerator.to_u64().ok_or_else(too_large)?;
+        if n as f64 * (n.max(2) as f64).log2() > MAX_BITS as f64 {
+            return Err(too_large());
+        }
+        Ok(Rational::integer(
+            (2..=n).fold(BigInt::one(), |product, factor| product * factor),
+        ))
+    }
+
+    /// The decimal representation rounded to the number of places, without trailing zeros.
+    pub fn decimal(&self, places: usize) -> String {
+        let scale = num_traits::pow(BigInt::from(10), places);
+        let scaled = Rational::new(&self.numerator * &scale, self.denominator.clone())
+            .round()
+            .abs();
+        let (integer, fraction) = scaled.div_rem(&scale);
+
+        let mut decimal = String::new();
+        if self.numerator.is_negative() && !scaled.is_zero() {
+            decimal.push('-');
+        }
+        decimal.push_str(&integer.to_string());
+        let fraction = format!("{:0>width$}", fraction.to_string(), width = places);
+        let fraction = fraction.trim_end_matches('0');
+        if !fraction.is_empty() {
+            decimal.push('.');
+            decimal.push_str(fraction);
+        }
+        decimal
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Rational) -> Ordering {
+        (&self.numerator * &other.denominator).cmp(&(&other.numerator * &self.denominator))
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Rational) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Rational {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numerator)
+        } else {
+            write!(f, "{}/{}", self.numerator, self.denominator)
+        }
+    }
+}
+
+/// A number of the calculator.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Number {
+    Float(f64),
+    Exact(Rational),
+    Integer(Integer),
+    Quantity(Quantity),
+    Complex(Complex64),
+    Matrix(Matrix),
+}
+
+impl Number {
+    /// The number of the literal in the mode.
+    pub fn literal(literal: &str, mode: Mode) -> Result<Number, ErrorKind> {
+        match mode {
+            Mode::Float => {
+                let value = match integer::parse_radix(literal) {
+                    Some(integer) => integer.to_f64().unwrap_or(f64::INFINITY),
+                    None => literal.parse::<f64>().unwrap_or(f64::NAN),
+                };
+                float(value, &[], || literal.to_owned())
+            }
+            Mode::Exact | Mode::Decimal(_) => Rational::parse(literal)
+                .map(Number::Exact)
+                .ok_or_else(|| ErrorKind::TooLarge(literal.to_owned())),
+            Mode::Programmer(integer_type) => {
+                let value = Rational::parse(literal)
+                    .ok_or_else(|| ErrorKind::TooLarge(literal.to_owned()))?;
+                if !value.is_integer() {
+                    return Err(ErrorKind::NotAnInteger(literal.to_owned()));
+                }
+                Ok(Number::Integer(Integer::from_big(
+                    value.numerator(),
+                    integer_type,
+                )))
+            }
+        }
+    }
+
+    /// The nearest `f64`.
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Number::Float(value) => *value,
+            Number::Exact(value) => value.to_f64(),
+            Number::Integer(value) => value.value() as f64,
+            Number::Quantity(value) => value.value_in_label(),
+            Number::Complex(value) if value.im == 0.0 => value.re,
+            Number::Complex(_) | Number::Matrix(_) => f64::NAN,
+        }
+    }
+
+    /// The `f64` of a real number without unit.
+    pub fn real(&self) -> Option<f64> {
+        match self {
+            Number::Float(_) | Number::Exact(_) | Number::Integer(_) => Some(self.to_f64()),
+            Number::Quantity(_) | Number::Complex(_) | Number::Matrix(_) => None,
+        }
+    }
+
+    /// The complex number of a number without unit.
+    fn to_complex(&self) -> Option<Complex64> {
+        match self {
+            Number::Complex(value) => Some(*value),
+            number => number.real().map(Complex64::from),
+        }
+    }
+
+    /// The quantity of the number, without unit if it's not a quantity.
+    fn to_quantity(&self) -> Quantity {
+        match self {
+            Number::Quantity(value) => value.clone(),
+            number => Quantity::new(number.to_f64(), Dimension::NONE),
+        }
+    }
+
+    /// The quantity, or the `f64` if it has no unit.
+    fn from_quantity(quantity: Quantity) -> Number {
+        match quantity.dimension().is_none() {
+            true => Number::Float(quantity.value()),
+            false => Number::Quantity(quantity),
+        }
+    }
+
+    /// The number converted to the mode, e.g. a variable assigned in another mode:
+    /// to a `f64` in the `float` mode, and to the integer type in the programmer mode.
+    pub fn in_mode(self, mode: Mode) -> Result<Number, ErrorKind> {
+        let (integer_type, number) = match (mode, self) {
+            (
+                Mode::Float | Mode::Exact | Mode::Decimal(_),
+                number @ (Number::Quantity(_) | Number::Complex(_) | Number::Matrix(_)),
+            ) => return Ok(number),
+            (Mode::Float, number) => return Ok(Number::Float(number.to_f64())),
+            (Mode::Exact | Mode::Decimal(_), Number::Integer(value)) => {
+                return Ok(Number::Exact(Rational::integer(value.value())))
+            }
+            (Mode::Exact | Mode::Decimal(_), number) => return Ok(number),
+            (Mode::Programmer(integer_type), number) => (integer_type, number),
+        };
+        match number {
+            Number::Integer(value) => Ok(Number::Integer(value.cast(integer_type))),
+            Number::Exact(value) if value.is_integer() => Ok(Number::Integer(Integer::from_big(
+                value.numerator(),
+                integer_type,
+            ))),
+            // A finite float, since the fraction of the infinities is `NaN`.
+            Number::Float(value) if value.fract() == 0.0 => {
+                let value = BigInt::from_f64(value).unwrap_or_default();
+                Ok(Number::Integer(Integer::from_big(&value, integer_type)))
+            }
+            number => Err(ErrorKind::NotAnInteger(number.to_string())),
+        }
+    }
+
+    /// Whether the number is zero.
+    pub fn is_zero(&self) -> bool {
+        match self {
+            Number::Float(value) => *value == 0.0,
+            Number::Exact(value) => value.is_zero(),
+            Number::Integer(value) => value.value() == 0,
+            Number::Quantity(value) => value.value() == 0.0,
+            Number::Complex(value) => value.is_zero(),
+            Number::Matrix(_) => false,
+        }
+    }
+
+    /// Whether the number is a `f64` approximation in an exact mode, e.g. `sqrt(2)`,
+    /// since the functions, the constants and the non-integer powers fall back to `f64`.
+    pub fn is_approximate(&self, mode: Mode) -> bool {
+        matches!(
+            (mode, self),
+            (Mode::Exact | Mode::Decimal(_), Number::Float(_))
+        )
+    }
+
+    /// The representation of the result in the mode, with `≈` before an approximation,
+    /// see `is_approximate`.
+    pub fn format_result(&self, mode: Mode) -> String {
+        match self.is_approximate(mode) {
+            true => format!("≈{}", self.format(mode)),
+            false => self.format(mode),
+        }
+    }
+
+    /// The representation of the number in the mode.
+    pub fn format(&self, mode: Mode) -> String {
+        match (mode, self) {
+            (Mode::Decimal(places), Number::Exact(value)) => value.decimal(places),
+            (Mode::Decimal(places), Number::Float(value)) if places < 17 => {
+                let decimal = format!("{:.*}", places, value);
+                match decimal.contains('.') {
+                    true => decimal
+                        .trim_end_matches('0')
+                        .trim_end_matches('.')
+                        .to_owned(),
+                    false => decimal,
+                }
+            }
+            (Mode::Programmer(_), Number::Integer(value)) => {
+                format!("{} (hex {}, bin {})", value, value.hex(), value.bin())
+            }
+            (_, Number::Float(value)) => value.to_string(),
+            (_, Number::Exact(value)) => value.to_string(),
+            (_, Number::Integer(value)) => value.to_string(),
+            (mode, Number::Quantity(value)) => format!(
+                "{} {}",
+                Number::Float(value.value_in_label()).format(mode),
+                value.unit()
+            ),
+            (mode, Number::Complex(value)) => {
+                complex_text(value, |part| Number::Float(part).format(mode))
+            }
+            (mode, Number::Matrix(value)) => {
+                value.format(|element| Number::Float(element).format(mode))
+            }
+        }
+    }
+
+    /// The expression evaluating to the number, e.g. `1/3` or `2 mi in mi`, for the session files.
+    pub fn to_expression(&self) -> String {
+        match self {
+            Number::Float(value) => value.to_string(),
+            Number::Exact(value) => value.to_string(),
+            Number::Integer(value) => value.to_string(),
+            Number::Quantity(value) if value.is_labeled() => format!(
+                "{} {} in {}",
+                value.value_in_label(),
+                value.unit(),
+                value.unit()
+            ),
+            Number::Quantity(value) => format!("{} {}", value.value_in_label(), value.unit()),
+            Number::Complex(value) => complex_text(value, |part| part.to_string()),
+            Number::Matrix(value) => value.to_expression(),
+        }
+    }
+
+    pub fn negate(&self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(-value),
+            Number::Exact(value) => Number::Exact(value.negate()),
+            Number::Integer(value) => Number::Integer(value.negate()),
+            Number::Quantity(value) => Number::Quantity(value.scale(-1.0)),
+            Number::Complex(value) => Number::Complex(-value),
+            Number::Matrix(value) => Number::Matrix(value.map(|element| -element)),
+        }
+    }
+
+    /// The quantity printed in the unit of the dimension; `operation` describes the conversion.
+    pub fn convert(
+        &self,
+        label: Label,
+        dimension: Dimension,
+        operation: impl Fn() -> String,
+    ) -> Result<Number, ErrorKind> {
+        match self {
+            Number::Complex(_) => return Err(ErrorKind::Complex(operation())),
+            Number::Matrix(_) => return Err(ErrorKind::Matrix(operation())),
+            _ => {}
+        }
+        self.to_quantity()
+            .convert(label, dimension, operation)
+            .map(Number::Quantity)
+    }
+
+    /// The bitwise complement of the integer.
+    pub fn not(&self) -> Result<Number, ErrorKind> {
+        match self {
+            Number::Integer(value) => Ok(Number::Integer(value.not())),
+            _ => Err(ErrorKind::Programmer(String::from("~"))),
+        }
+    }
+
+    /// The percent, i.e. the number divided by 100.
+    pub fn percent(&self) -> Number {
+        match self {
+            Number::Float(value) => Number::Float(value / 100.0),
+            Number::Exact(value) => Number::Exact(value.divide(&Rational::integer(100))),
+            Number::Integer(value) => Number::Integer(value.percent()),
+            Number::Quantity(value) => Number::Quantity(value.scale(0.01)),
+            Number::Complex(value) => Number::Complex(value / 100.0),
+            Number::Matrix(value) => Number::Matrix(value.map(|element| element / 100.0)),
+        }
+    }
+
+    /// The binary operation; the divisor of `/` and `%` isn't zero.
+    pub fn infix(operator: Infix, left: &Number, right: &Number) -> Result<Number, ErrorKind> {
+        if let (Number::Integer(left), Number::Integer(right)) = (left, right) {
+            return left.infix(operator, right).map(Number::Integer);
+        }
+        if operator.is_bitwise() {
+            return Err(ErrorKind::Programmer(operator.to_string()));
+        }
+        if matches!(left, Number::Matrix(_)) || matches!(right, Number::Matrix(_)) {
+            return Number::matrix_infix(operator, left, right);
+        }
+        if matches!(left, Number::Complex(_)) || matches!(right, Number::Complex(_)) {
+            let operation = || format!("{} {} {}", left, operator, right);
+            let (Some(a), Some(b)) = (left.to_complex(), right.to_complex()) else {
+                return Err(ErrorKind::Dimensionless(operation()));
+            };
+            let result = match operator {
+                Infix::Add => a + b,
+                Infix::Subtract => a - b,
+                Infix::Multiply | Infix::ImplicitMultiply => a * b,
+                Infix::Divide => a / b,
+                // The integer powers are exact, e.g. `i^2` is `-1 + 0i`.
+                Infix::Power if b.im == 0.0 && b.re.fract() == 0.0 && b.re.abs() <= 1024.0 => {
+                    a.powi(b.re as i32)
+                }
+                Infix::Power => a.powc(b),
+                _ => return Err(ErrorKind::Complex(operation())),
+            };
+            return complex(result, &[a, b], operation);
+        }
+        if matches!(left, Number::Quantity(_)) || matches!(right, Number::Quantity(_)) {
+            let operation = || format!("{} {} {}", left, operator, right);
+            let (left, right) = (left.to_quantity(), right.to_quantity());
+            let result = match operator {
+                Infix::Add => left.add(&right, 1.0, operation),
+                Infix::Subtract => left.add(&right, -1.0, operation),
+                Infix::Multiply | Infix::ImplicitMultiply => left.multiply(&right, 1, operation),
+                Infix::Divide => left.multiply(&right, -1, operation),
+                Infix::Remainder => left.remainder(&right, operation),
+                Infix::Power if right.dimension().is_none() => left.pow(right.value(), operation),
+                _ => Err(ErrorKind::Dimensionless(operation())),
+            };
+            return result.map(Number::from_quantity);
+        }
+
+        if let (Number::Exact(left), Number::Exact(right)) = (left, right) {
+            let result = match operator {
+                Infix::Add => left.add(right),
+                Infix::Subtract => left.subtract(right),
+                Infix::Multiply | Infix::ImplicitMultiply => left.multiply(right),
+                Infix::Divide => left.divide(right),
+                Infix::Remainder => left.remainder(right),
+                Infix::Power if right.is_integer() => left.pow(right.numerator())?,
+                Infix::Power => {
+                    let (base, exponent) = (left.to_f64(), right.to_f64());
+                    return float(base.powf(exponent), &[base, exponent], || {
+                        format!("{} ^ {}", left, right)
+                    });
+                }
+                _ => return Err(ErrorKind::Programmer(operator.to_string())),
+            };
+            return Ok(Number::Exact(result));
+        }
+
+        let (left, right) = (left.to_f64(), right.to_f64());
+        let result = match operator {
+            Infix::Add => left + right,
+            Infix::Subtract => left - right,
+            Infix::Multiply | Infix::ImplicitMultiply => left * right,
+            Infix::Divide => left / right,
+            Infix::Remainder => left % right,
+            Infix::Power => left.powf(right),
+            _ => return Err(ErrorKind::Programmer(operator.to_string())),
+        };
+        float(result, &[left, right], || {
+            format!(
+                "{} {} {}",
+                Number::Float(left),
+                operator,
+                Number::Float(right)
+            )
+        })
+    }
+
+    /// The binary operation with a matrix operand: the sums of matrices of the same shape, the matrix
+    /// product, the products and the quotients by a number, and the integer powers.
+    fn matrix_infix(operator: Infix, left: &Number, right: &Number) -> Result<Number, ErrorKind> {
+        let operation = || format!("{} {} {}", left, operator, right);
+        let scalar = |number: &Number| match number {
+            Number::Quantity(_) => Err(ErrorKind::Dimensionless(operation())),
+            Number::Complex(_) => Err(ErrorKind::Complex(operation())),
+            number => Ok(number.to_f64()),
+        };
+        let result = match (operator, left, right) {
+            (Infix::Add, Number::Matrix(a), Number::Matrix(b)) => a.add(b, 1.0, operation)?,
+            (Infix::Subtract, Number::Matrix(a), Number::Matrix(b)) => a.add(b, -1.0, operation)?,
+            (Infix::Multiply | Infix::ImplicitMultiply, Number::Matrix(a), Number::Matrix(b)) => {
+                a.multiply(b, operation)?
+            }
+            (Infix::Multiply | Infix::ImplicitMultiply, Number::Matrix(a), number)
+            | (Infix::Multiply | Infix::ImplicitMultiply, number, Number::Matrix(a)) => {
+                let factor = scalar(number)?;
+                a.map(|element| element * factor)
+            }
+            (Infix::Divide, Number::Matrix(a), number) if !matches!(number, Number::Matrix(_)) => {
+                let divisor = scalar(number)?;
+                a.map(|element| element / divisor)
+            }
+            (Infix::Power, Number::Matrix(a), number) if !matches!(number, Number::Matrix(_)) => {
+                let exponent = scalar(number)?;
+                if exponent.fract() != 0.0 || exponent.abs() > 1024.0 {
+                    return Err(ErrorKind::Matrix(operation()));
+                }
+                a.pow(exponent as i64, operation)?
+            }
+            _ => return Err(ErrorKind::Matrix(operation())),
+        };
+        let finite = |number: &Number| match number {
+            Number::Matrix(value) => value.is_finite(),
+            number => number.to_f64().is_finite(),
+        };
+        if !result.is_finite() && finite(left) && finite(right) {
+            return Err(ErrorKind::Overflow(operation()));
+        }
+        Ok(Number::Matrix(result))
+    }
+
+    /// The factorial of the non-negative integer.
+    pub fn factorial(&self) -> Result<Number, ErrorKind> {
+        match self {
+            Number::Exact(value) => value.factorial().map(Number::Exact),
+            Number::Integer(value) => value.factorial().map(Number::Integer),
+            Number::Quantity(_) => Err(ErrorKind::Dimensionless(format!("{}!", self))),
+            Number::Complex(_) | Number::Matrix(_) => Err(ErrorKind::Factorial(self.to_string())),
+            Number::Float(value) => {
+                if *value < 0.0 || value.fract() != 0.0 {
+                    return Err(ErrorKind::Factorial(self.to_string()));
+                }
+                if *value > MAX_FACTORIAL {
+                    return Err(ErrorKind::Overflow(format!("{}!", self)));
+                }
+                Ok(Number::Float(
+                    (2..=*value as u64).map(|factor| factor as f64).product(),
+                ))
+            }
+        }
+    }
+
+    /// Calls the built-in function; `abs`, `floor`, `ceil`, `round`, `signum`, `min` and `max`
+    /// are exact with exact arguments, and the only functions of the integers.
+    pub fn call(name: &str, args: &[Number]) -> Result<Number, ErrorKind> {
+        let integers: Option<Vec<&Integer>> = args
+            .iter()
+            .map(|arg| match arg {
+                Number::Integer(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        match (name, integers.as_deref()) {
+            (_, None | Some([])) => {}
+            ("abs", Some([x])) => return Ok(Number::Integer(x.abs())),
+            ("signum", Some([x])) => return Ok(Number::Integer(x.signum())),
+            ("floor" | "ceil" | "round", Some([x])) => return Ok(Number::Integer(**x)),
+            ("max", Some(values)) => {
+                let max = values.iter().max_by_key(|value| value.value());
+                return Ok(Number::Integer(**max.unwrap_or(&values[0])));
+            }
+            ("min", Some(values)) => {
+                let min = values.iter().min_by_key(|value| value.value());
+                return Ok(Number::Integer(**min.unwrap_or(&values[0])));
+            }
+            // The wrong numbers of arguments are reported below.
+            ("abs" | "signum" | "floor" | "ceil" | "round", _) => {}
+            (name, _) if FUNCTIONS.contains(&name) => {
+                return Err(ErrorKind::Unavailable(name.to_owned()))
+            }
+            _ => {}
+        }
+
+        let operation = || {
+            let args: Vec<String> = args.iter().map(Number::to_string).collect();
+            format!("{}({})", name, args.join(", "))
+        };
+        match (name, args) {
+            ("transpose", [Number::Matrix(a)]) => return Ok(Number::Matrix(a.transpose())),
+            ("det", [Number::Matrix(a)]) => return a.determinant(operation).map(Number::Float),
+            ("inverse", [Number::Matrix(a)]) => return a.inverse(operation).map(Number::Matrix),
+            ("linsolve", [Number::Matrix(a), Number::Matrix(b)]) => {
+                return a.solve(b, operation).map(Number::Matrix)
+            }
+            (name, _) if matrix::expected(name).is_some() => {
+                return Err(ErrorKind::Arguments {
+                    name: name.to_owned(),
+                    expected: matrix::expected(name).unwrap_or_default().to_owned(),
+                })
+            }
+            (name, _)
+                if statistics::expected(name).is_some()
+                    || (matches!(name, "min" | "max")
+                        && args.iter().any(|arg| matches!(arg, Number::Matrix(_)))) =>
+            {
+                return statistics::call(name, args, operation)
+            }
+            (name, _)
+                if FUNCTIONS.contains(&name)
+                    && args.iter().any(|arg| matches!(arg, Number::Matrix(_))) =>
+            {
+                return Err(ErrorKind::Matrix(operation()))
+            }
+            (name, _)
+                if FUNCTIONS.contains(&name)
+                    && args.iter().any(|arg| matches!(arg, Number::Complex(_))) =>
+            {
+                return complex_call(name, args, operation)
+            }
+            _ => {}
+        }
+
+        if FUNCTIONS.contains(&name) && args.iter().any(|arg| matches!(arg, Number::Quantity(_))) {
+            let quantities: Vec<Quantity> = args.iter().map(Number::to_quantity).collect();
+            return match (name, quantities.as_slice()) {
+                ("abs", [x]) => Ok(Number::Quantity(Quantity::new(
+                    x.value().abs(),
+                    x.dimension(),
+                ))),
+                ("max" | "min", [first, ..]) => {
+                    for quantity in &quantities {
+                        quantity.check(first.dimension(), operation)?;
+                    }
+                    let values = quantities.iter().map(Quantity::value);
+                    let value = match name {
+                        "max" => values.fold(f64::NEG_INFINITY, f64::max),
+                        _ => values.fold(f64::INFINITY, f64::min),
+                    };
+                    Ok(Number::Quantity(Quantity::new(value, first.dimension())))
+                }
+                _ => Err(ErrorKind::Dimensionless(operation())),
+            };
+        }
+
+        let exact: Option<Vec<&Rational>> = args
+            .iter()
+            .map(|arg| match arg {
+                Number::Exact(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        match (name, exact.as_deref()) {
+            ("abs", Some([x])) => return Ok(Number::Exact(x.abs())),
+            ("floor", Some([x])) => return Ok(Number::Exact(Rational::integer(x.floor()))),
+            ("ceil", Some([x])) => return Ok(Number::Exact(Rational::integer(x.ceil()))),
+            ("round", Some([x])) => return Ok(Number::Exact(Rational::integer(x.round()))),
+            ("re" | "conj", Some([x])) => return Ok(Number::Exact((*x).clone())),
+            ("im", Some([_])) => return Ok(Number::Exact(Rational::integer(0))),
+            ("signum", Some([x])) => {
+                return Ok(Number::Exact(Rational::integer(x.numerator().signum())))
+            }
+            ("max", Some(values)) if !values.is_empty() => {
+                return Ok(Number::Exact(
+                    (*values.iter().max().unwrap_or(&values[0])).clone(),
+                ))
+            }
+            ("min", Some(values)) if !values.is_empty() => {
+                return Ok(Number::Exact(
+                    (*values.iter().min().unwrap_or(&values[0])).clone(),
+                ))
+            }
+            _ => {}
+        }
+
+        let args: Vec<f64> = args.iter().map(Number::to_f64).collect();
+        let result = call(name, &args)?;
+        float(result, &args, || {
+            let args: Vec<String> = args
+                .iter()
+                .map(|arg| Number::Float(*arg).to_string())
+                .collect();
+            format!("{}({})", name, args.join(", "))
+        })
+    }
+}
+
+impl fmt::Display for Number {
+    /// The number with an exponent if it's a very large or very small `f64`, for the messages.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Number::Float(value) => {
+                let magnitude = value.abs();
+                if magnitude != 0.0 && !(1e-6..1e16).contains(&magnitude) {
+                    write!(f, "{:e}", value)
+                } else {
+                    write!(f, "{}", value)
+                }
+            }
+            Number::Exact(value) => write!(f, "{}", value),
+            Number::Integer(value) => write!(f, "{}", value),
+            Number::Quantity(value) => write!(
+                f,
+                "{} {}",
+                Number::Float(value.value_in_label()),
+                value.unit()
+            ),
+            // In parentheses within the operations, e.g. `2 * (3 - 4i)`.
+            Number::Complex(value) if value.re != 0.0 => write!(
+                f,
+                "({})",
+                complex_text(value, |part| Number::Float(part).to_string())
+            ),
+            Number::Complex(value) => write!(
+                f,
+                "{}",
+                complex_text(value, |part| Number::Float(part).to_string())
+            ),
+            Number::Matrix(value) => write!(
+                f,
+                "{}",
+                value.format(|element| Number::Float(element).to_string())
+            ),
+        }
+    }
+}
+
+/// The `f64` result, unless it's not a number, or infinite, while no operand already is.
+fn float(
+    result: f64,
+    operands: &[f64],
+    operation: impl Fn() -> String,
+) -> Result<Number, ErrorKind> {
+    if result.is_nan() && !operands.iter().any(|operand| operand.is_nan()) {
+        return Err(ErrorKind::NotANumber(operation()));
+    }
+    if result.is_infinite() && operands.iter().all(|operand| operand.is_finite()) {
+        return Err(ErrorKind::Overflow(operation()));
+    }
+    Ok(Number::Float(result))
+}
+
+/// The complex result, unless it's not a number, or infinite, while no operand already is.
+fn complex(
+    result: Complex64,
+    operands: &[Complex64],
+    operation: impl Fn() -> String,
+) -> Result<Number, ErrorKind> {
+    if result.is_nan() && !operands.iter().any(|operand| operand.is_nan()) {
+        return Err(ErrorKind::NotANumber(operation()));
+    }
+    if result.is_infinite() && operands.iter().all(|operand| operand.is_finite()) {
+        return Err(ErrorKind::Overflow(operation()));
+    }
+    Ok(Number::Complex(result))
+}
+
+/// The complex number with the formatted parts, e.g. `3 - 4i`, or `2i` without real part.
+fn complex_text(value: &Complex64, part: impl Fn(f64) -> String) -> String {
+    match (value.re == 0.0, value.im.is_sign_negative()) {
+        (true, _) => format!("{}i", part(value.im)),
+        (false, true) => format!("{} - {}i", part(value.re), part(-value.im)),
+        (false, false) => format!("{} + {}i", part(value.re), part(value.im)),
+    }
+}
+
+/// Calls the built-in function with a complex argument.
+fn complex_call(
+    name: &str,
+    args: &[Number],
+    operation: impl Fn() -> String,
+) -> Result<Number, ErrorKind> {
+    let values: Option<Vec<Complex64>> = args.iter().map(Number::to_complex).collect();
+    let Some(values) = values else {
+        return Err(ErrorKind::Dimensionless(operation()));
+    };
+    let [z] = values[..] else {
+        return match name {
+            "atan2" | "max" | "min" => Err(ErrorKind::Complex(operation())),
+            _ => Err(ErrorKind::Arguments {
+                name: name.to_owned(),
+                expected: String::from("1 argument"),
+            }),
+        };
+    };
+    let result = match name {
+        "abs" => return Ok(Number::Float(z.norm())),
+        "arg" => return Ok(Number::Float(z.arg())),
+        "re" => return Ok(Number::Float(z.re)),
+        "im" => return Ok(Number::Float(z.im)),
+        "conj" => z.conj(),
+        "sqrt" => z.sqrt(),
+        "exp" => z.exp(),
+        "ln" => z.ln(),
+        "sin" => z.sin(),
+        "cos" => z.cos(),
+        "tan" => z.tan(),
+        "asin" => z.asin(),
+        "acos" => z.acos(),
+        "atan" => z.atan(),
+        "sinh" => z.sinh(),
+        "cosh" => z.cosh(),
+        "tanh" => z.tanh(),
+        "asinh" => z.asinh(),
+        "acosh" => z.acosh(),
+        "atanh" => z.atanh(),
+        _ => return Err(ErrorKind::Complex(operation())),
+    };
+    complex(result, &values, operation)
+}
+
+/// Calls the built-in function with `f64` arguments.
+fn call(name: &str, args: &[f64]) -> Result<f64, ErrorKind> {
+    let arguments = |expected: &str| ErrorKind::Arguments {
+        name: name.to_owned(),
+        expected: expected.to_owned(),
+    };
+
+    match (name, args) {
+        ("atan2", [y, x]) => Ok(y.atan2(*x)),
+        ("atan2", _) => Err(arguments("2 arguments")),
+        ("max", [_, ..]) => Ok(args.iter().copied().fold(f64::NEG_INFINITY, f64::max)),
+        ("min", [_, ..]) => Ok(args.iter().copied().fold(f64::INFINITY, f64::min)),
+        ("max" | "min", []) => Err(arguments("at least 1 argument")),
+        (name, [x]) => match name {
+            "sqrt" => Ok(x.sqrt()),
+            "exp" => Ok(x.exp()),
+            "ln" => Ok(x.ln()),
+            "abs" => Ok(x.abs()),
+            "sin" => Ok(x.sin()),
+            "cos" => Ok(x.cos()),
+            "tan" => Ok(x.tan()),
+            "asin" => Ok(x.asin()),
+            "acos" => Ok(x.acos()),
+            "atan" => Ok(x.atan()),
+            "sinh" => Ok(x.sinh()),
+            "cosh" => Ok(x.cosh()),
+            "tanh" => Ok(x.tanh()),
+            "asinh" => Ok(x.asinh()),
+            "acosh" => Ok(x.acosh()),
+            "atanh" => Ok(x.atanh()),
+            "floor" => Ok(x.floor()),
+            "ceil" => Ok(x.ceil()),
+            "round" => Ok(x.round()),
+            "signum" => Ok(x.signum()),
+            "arg" => Ok(0f64.atan2(*x)),
+            "re" | "conj" => Ok(*x),
+            "im" => Ok(0.0),
+            _ => Err(ErrorKind::UnknownFunction(name.to_owned())),
+        },
+        (name, _) if FUNCTIONS.contains(&name) => Err(arguments("1 argument")),
+        (name, _) => Err(ErrorKind::UnknownFunction(name.to_owned())),
+    }
+}
+
+#[cfg(test)]
+mod tests;
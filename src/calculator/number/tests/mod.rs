@@ -0,0 +1,98 @@
+mod number {
+    use num_bigint::BigInt;
+
+    use crate::calculator::number::{Mode, Number, Rational};
+    use crate::calculator::session::Session;
+
+    /// Evaluates the expression in the mode, and formats the result.
+    fn eval(mode: Mode, expression: &str) -> String {
+        let mut session = Session::new();
+        session.set_mode(mode);
+        session.evaluate(expression).unwrap().format(mode)
+    }
+
+    #[test]
+    fn rationals() {
+        let third = Rational::parse("0.5e-1").unwrap();
+        assert_eq!(third, Rational::new(BigInt::from(-1), BigInt::from(-20)));
+        assert_eq!(third.to_string(), "1/20");
+        assert_eq!(Rational::parse("12.50").unwrap().to_string(), "25/2");
+        assert_eq!(Rational::parse("1e999999999"), None);
+
+        let value = Rational::parse("-2.5").unwrap();
+        assert_eq!(value.round(), BigInt::from(-3));
+        assert_eq!(value.floor(), BigInt::from(-3));
+        assert_eq!(value.ceil(), BigInt::from(-2));
+        assert_eq!(value.decimal(0), "-3");
+        assert_eq!(Rational::parse("2.675").unwrap().decimal(2), "2.68");
+        assert_eq!(Rational::parse("-0.001").unwrap().decimal(2), "0");
+    }
+
+    #[test]
+    fn exact_mode() {
+        assert_eq!(eval(Mode::Exact, "1/3 + 1/6"), "1/2");
+        assert_eq!(eval(Mode::Exact, "0.1 + 0.2"), "3/10");
+        assert_eq!(
+            eval(Mode::Exact, "2^100"),
+            "1267650600228229401496703205376"
+        );
+        assert_eq!(eval(Mode::Exact, "(2/3)^-2"), "9/4");
+        assert_eq!(eval(Mode::Exact, "-7.5 % 2"), "-3/2");
+        assert_eq!(eval(Mode::Exact, "round(-5/2) + max(1/3, 1/4)"), "-8/3");
+        assert_eq!(eval(Mode::Exact, "25!").len(), 26);
+        assert_eq!(eval(Mode::Exact, "2^0.5"), eval(Mode::Float, "sqrt(2)"));
+
+        let mut session = Session::new();
+        session.set_mode(Mode::Exact);
+        assert!(session.evaluate("2^10000000").is_err());
+        assert!(session.evaluate("1000000!").is_err());
+        assert!(session.evaluate("0^-1").is_err());
+    }
+
+    #[test]
+    fn decimal_mode() {
+        assert_eq!(eval(Mode::Float, "0.1 + 0.2"), "0.30000000000000004");
+        assert_eq!(eval(Mode::Decimal(10), "0.1 + 0.2"), "0.3");
+        assert_eq!(eval(Mode::Decimal(5), "2/3"), "0.66667");
+        assert_eq!(eval(Mode::Decimal(2), "19.99 * 3 * 1.2"), "71.96");
+        assert_eq!(eval(Mode::Decimal(3), "pi"), "3.142");
+        assert_eq!(
+            Number::Exact(Rational::integer(7)).format(Mode::Decimal(2)),
+            "7"
+        );
+    }
+
+    #[test]
+    fn approximate_results() {
+        let result = |mode: Mode, expression: &str| {
+            let mut session = Session::new();
+            session.set_mode(mode);
+            session.evaluate(expression).unwrap().format_result(mode)
+        };
+        assert_eq!(result(Mode::Decimal(20), "sqrt(2)"), "≈1.4142135623730951");
+        assert_eq!(result(Mode::Decimal(3), "sin(1)"), "≈0.841");
+        assert_eq!(result(Mode::Exact, "pi"), "≈3.141592653589793");
+        assert_eq!(result(Mode::Decimal(20), "2/3"), "0.66666666666666666667");
+        assert_eq!(result(Mode::Float, "sqrt(2)"), "1.4142135623730951");
+        assert_eq!(result(Mode::Decimal(3), "3 km in mi"), "1.864 mi");
+    }
+
+    #[test]
+    fn complex_numbers() {
+        assert_eq!(eval(Mode::Float, "(3 + 4i) * (1 - 2i)"), "11 - 2i");
+        assert_eq!(
+            eval(Mode::Float, "abs(3 + 4i) + arg(2i)"),
+            eval(Mode::Float, "5 + pi/2")
+        );
+        assert_eq!(eval(Mode::Float, "conj(2 + i) / 5"), "0.4 - 0.2i");
+        assert_eq!(eval(Mode::Float, "i^2"), "-1 + 0i");
+        assert_eq!(eval(Mode::Float, "sqrt(-4 + 0i)"), "2i");
+        assert_eq!(eval(Mode::Exact, "re(1/3 + 2i)"), eval(Mode::Float, "1/3"));
+        assert_eq!(eval(Mode::Decimal(3), "exp(i * pi)"), "-1 + 0i");
+
+        let session = Session::new();
+        assert!(session.evaluate("sqrt(-4)").is_err());
+        assert!(session.evaluate("(1 + i) % 2").is_err());
+        assert!(session.evaluate("2 km * i").is_err());
+    }
+}
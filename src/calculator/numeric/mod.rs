@@ -0,0 +1,415 @@
+//! Numeric submodule.
+//!
+//! The numerical methods of the functions which take an expression of a variable rather than a value,
+//! e.g. `integrate(exp(-x^2), x, 0, 3)`:
+//! - `solve`: a root of an equation, e.g. `solve(x^2 = 2, x, 1)`, by Newton's method from the guess,
+//!   or, if it doesn't converge, by a bracket of a sign change searched around the guess;
+//!   with two bounds, e.g. `solve(cos(x) = x, x, 0, 1)`, the bracket is given.
+//!   Within a bracket, the Newton steps are safeguarded by bisection, so that they stay in the bracket;
+//! - `deriv`: the derivative at a point, by Ridders' extrapolation of central differences;
+//! - `integrate`: the definite integral, by the adaptive Simpson's rule;
+//! - `minimum` and `maximum`: the extrema over an interval, by a grid search refined by a golden-section search.
+//!
+//! A method which can't reach its tolerance fails with the reason, rather than returning an inaccurate value.
+
+/// The numerical functions, and the description of their arguments.
+pub const FUNCTIONS: &[(&str, &str)] = &[
+    (
+        "solve",
+        "an equation or an expression, a variable, and a guess or two bounds",
+    ),
+    ("deriv", "an expression, a variable and a point"),
+    ("integrate", "an expression, a variable and two bounds"),
+    ("minimum", "an expression, a variable and two bounds"),
+    ("maximum", "an expression, a variable and two bounds"),
+];
+
+/// The name of the function whose first argument may be an equation.
+pub const SOLVE: &str = "solve";
+
+/// The relative tolerance of the roots and the extrema.
+const TOLERANCE: f64 = 1e-12;
+
+const MAX_ITERATIONS: usize = 200;
+
+/// The absolute tolerance of the integrals, relative to their magnitude if it's greater than 1.
+const INTEGRAL_TOLERANCE: f64 = 1e-10;
+
+/// The maximum number of bisections of the integration intervals.
+const MAX_DEPTH: u32 = 50;
+
+const MAX_EVALUATIONS: usize = 1_000_000;
+
+/// The number of points of the grid searches for extrema.
+const GRID: usize = 200;
+
+/// A failure of a numerical method.
+#[derive(Debug, PartialEq)]
+pub enum Failure<E> {
+    /// The function failed at a point.
+    Evaluation(E),
+    /// The method didn't converge, for the reason.
+    Convergence(String),
+}
+
+type Result<T, E> = std::result::Result<T, Failure<E>>;
+
+/// The numerical function of the name, and the description of its arguments.
+pub fn find(name: &str) -> Option<(&'static str, &'static str)> {
+    FUNCTIONS
+        .iter()
+        .find(|(function, _)| *function == name)
+        .copied()
+}
+
+/// Applies the numerical function to the function of the variable, with the values of the arguments
+/// after the variable; `None` if the function doesn't take that number of arguments.
+pub fn apply<E>(
+    name: &str,
+    f: &dyn Fn(f64) -> std::result::Result<f64, E>,
+    args: &[f64],
+) -> Option<Result<f64, E>> {
+    let result = match (name, args) {
+        ("solve", [guess]) => solve(f, *guess),
+        ("solve", [a, b]) => solve_between(f, *a, *b),
+        ("deriv", [x]) => derivative(f, *x),
+        ("integrate", [a, b]) => integral(f, *a, *b),
+        ("minimum", [a, b]) => extremum(f, *a, *b, false),
+        ("maximum", [a, b]) => extremum(f, *a, *b, true),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// The value of the function at the point.
+fn at<E>(f: &dyn Fn(f64) -> std::result::Result<f64, E>, x: f64) -> Result<f64, E> {
+    f(x).map_err(Failure::Evaluation)
+}
+
+/// The slope of the function at the point by a central difference; `None` if it can't be computed.
+fn slope<E>(f: &dyn Fn(f64) -> std::result::Result<f64, E>, x: f64) -> Option<f64> {
+    let step = 1e-6 * (1.0 + x.abs());
+    let slope = (f(x + step).ok()? - f(x - step).ok()?) / (2.0 * step);
+    (slope.is_finite() && slope != 0.0).then_some(slope)
+}
+
+/// A root near the guess.
+pub fn solve<E>(f: &dyn Fn(f64) -> std::result::Result<f64, E>, guess: f64) -> Result<f64, E> {
+    let value = at(f, guess)?;
+    if value == 0.0 {
+        return Ok(guess);
+    }
+    if let Some(root) = newton(f, guess, value.abs()) {
+        return Ok(root);
+    }
+    match bracket(f, guess, value) {
+        Some((a, b)) => solve_between(f, a, b),
+        None => Err(Failure::Convergence(format!(
+            "no root found near {}",
+            guess
+        ))),
+    }
+}
+
+/// The root reached by Newton's method from the guess, if the steps converge to a point where
+/// the function is negligible compared to its value at the guess.
+fn newton<E>(
+    f: &dyn Fn(f64) -> std::result::Result<f64, E>,
+    guess: f64,
+    scale: f64,
+) -> Option<f64> {
+    let mut x = guess;
+    for _ in 0..MAX_ITERATIONS {
+        let value = f(x).ok()?;
+        if value == 0.0 {
+            return Some(x);
+        }
+        let next = x - value / slope(f, x)?;
+        if !next.is_finite() {
+            return None;
+        }
+        if (next - x).abs() <= TOLERANCE * (1.0 + next.abs()) {
+            let residual = f(next).ok()?.abs();
+            return (residual <= 1e-9 * scale.max(1.0)).then_some(next);
+        }
+        x = next;
+    }
+    None
+}
+
+/// The bounds of a sign change found by stepping away from the guess on both sides, with growing steps.
+fn bracket<E>(
+    f: &dyn Fn(f64) -> std::result::Result<f64, E>,
+    guess: f64,
+    value: f64,
+) -> Option<(f64, f64)> {
+    let mut step = 0.01 * (1.0 + guess.abs());
+    let (mut left, mut right) = ((guess, value), (guess, value));
+    for _ in 0..MAX_ITERATIONS {
+        for (side, direction) in [(&mut left, -1.0), (&mut right, 1.0)] {
+            let x = guess + direction * step;
+            // The function may be undefined on one side, e.g. `sqrt(x) = 2` below 0.
+            let Some(value) = f(x).ok().filter(|value| value.is_finite()) else {
+                continue;
+            };
+            if value.signum() != side.1.signum() {
+                return Some(if direction < 0.0 {
+                    (x, side.0)
+                } else {
+                    (side.0, x)
+                });
+            }
+            *side = (x, value);
+        }
+        step *= 1.6;
+        if !step.is_finite() {
+            break;
+        }
+    }
+    None
+}
+
+/// A root between the bounds, where the function has opposite signs.
+pub fn solve_between<E>(
+    f: &dyn Fn(f64) -> std::result::Result<f64, E>,
+    a: f64,
+    b: f64,
+) -> Result<f64, E> {
+    let (value_a, value_b) = (at(f, a)?, at(f, b)?);
+    if value_a == 0.0 {
+        return Ok(a);
+    }
+    if value_b == 0.0 {
+        return Ok(b);
+    }
+    if value_a.signum() == value_b.signum() {
+        return Err(Failure::Convergence(format!(
+            "the function has the same sign at {} and {}",
+            a, b
+        )));
+    }
+
+    // The function is negative at `low`, and positive at `high`.
+    let (mut low, mut high) = if value_a < 0.0 { (a, b) } else { (b, a) };
+    let mut x = 0.5 * (a + b);
+    for _ in 0..MAX_ITERATIONS {
+        let value = at(f, x)?;
+        if value == 0.0 {
+            return Ok(x);
+        }
+        if value < 0.0 {
+            low = x;
+        } else {
+            high = x;
+        }
+        let bisection = 0.5 * (low + high);
+        let next = match slope(f, x) {
+            Some(slope) if (x - value / slope - low) * (x - value / slope - high) < 0.0 => {
+                x - value / slope
+            }
+            _ => bisection,
+        };
+        let tolerance = TOLERANCE * (1.0 + next.abs());
+        if (next - x).abs() <= tolerance || (high - low).abs() <= tolerance {
+            return Ok(next);
+        }
+        x = next;
+    }
+    Err(Failure::Convergence(format!(
+        "the root between {} and {} wasn't reached in {} iterations",
+        a, b, MAX_ITERATIONS
+    )))
+}
+
+/// The derivative at the point, by Ridders' method.
+pub fn derivative<E>(f: &dyn Fn(f64) -> std::result::Result<f64, E>, x: f64) -> Result<f64, E> {
+    const SIZE: usize = 10;
+    const SHRINK: f64 = 1.4;
+    let central = |step: f64| Ok((at(f, x + step)? - at(f, x - step)?) / (2.0 * step));
+
+    let mut step = 0.1 * (1.0 + x.abs());
+    // The extrapolations of the central differences with decreasing steps.
+    let mut table = [[0.0; SIZE]; SIZE];
+    table[0][0] = central(step)?;
+    let (mut derivative, mut error) = (table[0][0], f64::INFINITY);
+    for column in 1..SIZE {
+        step /= SHRINK;
+        table[0][column] = central(step)?;
+        let mut factor = SHRINK * SHRINK;
+        for row in 1..=column {
+            table[row][column] =
+                (table[row - 1][column] * factor - table[row - 1][column - 1]) / (factor - 1.0);
+            factor *= SHRINK * SHRINK;
+            let estimate = (table[row][column] - table[row - 1][column])
+                .abs()
+                .max((table[row][column] - table[row - 1][column - 1]).abs());
+            if estimate <= error {
+                error = estimate;
+                derivative = table[row][column];
+            }
+        }
+        // The higher orders only add rounding errors.
+        if (table[column][column] - table[column - 1][column - 1]).abs() >= 2.0 * error {
+            break;
+        }
+    }
+
+    if error.is_nan() || error > 1e-6 * derivative.abs().max(1.0) {
+        return Err(Failure::Convergence(format!(
+            "the derivative at {} is unstable, the function may not be differentiable there",
+            x
+        )));
+    }
+    Ok(derivative)
+}
+
+/// The definite integral between the bounds.
+pub fn integral<E>(
+    f: &dyn Fn(f64) -> std::result::Result<f64, E>,
+    a: f64,
+    b: f64,
+) -> Result<f64, E> {
+    if a == b {
+        return Ok(0.0);
+    }
+    // A few panels, so that a narrow peak isn't missed by the first estimates.
+    const PANELS: usize = 8;
+    let width = (b - a) / PANELS as f64;
+    let mut simpson = Simpson { f, evaluations: 0 };
+    let mut panels = vec![];
+    for panel in 0..PANELS {
+        let (start, end) = (a + width * panel as f64, a + width * (panel + 1) as f64);
+        let middle = 0.5 * (start + end);
+        let values = (simpson.at(start)?, simpson.at(middle)?, simpson.at(end)?);
+        panels.push((start, end, values, rule(start, end, values)));
+    }
+    let estimate: f64 = panels.iter().map(|panel| panel.3).sum();
+    let tolerance = INTEGRAL_TOLERANCE * estimate.abs().max(1.0) / PANELS as f64;
+
+    let mut integral = 0.0;
+    for (start, end, values, whole) in panels {
+        integral += simpson.adaptive(start, end, values, whole, tolerance, MAX_DEPTH)?;
+    }
+    Ok(integral)
+}
+
+/// Simpson's rule over the interval, with the values at its bounds and its middle.
+fn rule(start: f64, end: f64, (first, middle, last): (f64, f64, f64)) -> f64 {
+    (end - start) / 6.0 * (first + 4.0 * middle + last)
+}
+
+/// The adaptive Simpson's rule.
+struct Simpson<'a, E> {
+    f: &'a dyn Fn(f64) -> std::result::Result<f64, E>,
+    evaluations: usize,
+}
+
+impl<E> Simpson<'_, E> {
+    /// The value of the function, within the budget of evaluations.
+    fn at(&mut self, x: f64) -> Result<f64, E> {
+        self.evaluations += 1;
+        if self.evaluations > MAX_EVALUATIONS {
+            return Err(Failure::Convergence(format!(
+                "the integral didn't converge in {} evaluations",
+                MAX_EVALUATIONS
+            )));
+        }
+        at(self.f, x)
+    }
+
+    /// The integral over the interval, by bisection until the halves agree with the whole.
+    fn adaptive(
+        &mut self,
+        start: f64,
+        end: f64,
+        (first, middle, last): (f64, f64, f64),
+        whole: f64,
+        tolerance: f64,
+        depth: u32,
+    ) -> Result<f64, E> {
+        let center = 0.5 * (start + end);
+        let (left_middle, right_middle) = (
+            self.at(0.5 * (start + center))?,
+            self.at(0.5 * (center + end))?,
+        );
+        let left = rule(start, center, (first, left_middle, middle));
+        let right = rule(center, end, (middle, right_middle, last));
+        let delta = left + right - whole;
+        if delta.abs() <= 15.0 * tolerance {
+            return Ok(left + right + delta / 15.0);
+        }
+        if depth == 0 || center <= start || center >= end {
+            return Err(Failure::Convergence(format!(
+                "the integral didn't converge near {}, the function may be singular there",
+                center
+            )));
+        }
+        Ok(self.adaptive(
+            start,
+            center,
+            (first, left_middle, middle),
+            left,
+            tolerance / 2.0,
+            depth - 1,
+        )? + self.adaptive(
+            center,
+            end,
+            (middle, right_middle, last),
+            right,
+            tolerance / 2.0,
+            depth - 1,
+        )?)
+    }
+}
+
+/// The least value, or the greatest one, of the function between the bounds.
+pub fn extremum<E>(
+    f: &dyn Fn(f64) -> std::result::Result<f64, E>,
+    a: f64,
+    b: f64,
+    maximum: bool,
+) -> Result<f64, E> {
+    let sign = if maximum { -1.0 } else { 1.0 };
+    let g = |x: f64| Ok(sign * at(f, x)?);
+    let (a, b) = (a.min(b), a.max(b));
+    if a == b {
+        return Ok(sign * g(a)?);
+    }
+
+    let step = (b - a) / GRID as f64;
+    let mut best = (a, g(a)?);
+    for index in 1..=GRID {
+        let x = if index == GRID {
+            b
+        } else {
+            a + step * index as f64
+        };
+        let value = g(x)?;
+        if value < best.1 {
+            best = (x, value);
+        }
+    }
+
+    // The golden-section search around the best point of the grid.
+    let ratio = (5f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = ((best.0 - step).max(a), (best.0 + step).min(b));
+    let (mut left, mut right) = (high - ratio * (high - low), low + ratio * (high - low));
+    let (mut left_value, mut right_value) = (g(left)?, g(right)?);
+    while high - low > TOLERANCE * (1.0 + best.0.abs()) {
+        if left_value < right_value {
+            high = right;
+            (right, right_value) = (left, left_value);
+            left = high - ratio * (high - low);
+            left_value = g(left)?;
+        } else {
+            low = left;
+            (left, left_value) = (right, right_value);
+            right = low + ratio * (high - low);
+            right_value = g(right)?;
+        }
+    }
+    Ok(sign * best.1.min(left_value).min(right_value))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,99 @@
+mod numeric {
+    use crate::calculator::{
+        expression::{evaluate, ErrorKind},
+        numeric::{derivative, extremum, integral, solve, solve_between, Failure},
+        session::Session,
+        Number, Outcome,
+    };
+
+    /// Evaluates the expression in the session.
+    fn eval(session: &Session, expression: &str) -> Result<f64, ErrorKind> {
+        evaluate(expression, session)
+            .map(|value| value.to_f64())
+            .map_err(|error| error.kind)
+    }
+
+    fn close(value: f64, expected: f64) -> bool {
+        (value - expected).abs() <= 1e-9 * expected.abs().max(1.0)
+    }
+
+    #[test]
+    fn methods() {
+        let f = |x: f64| Ok::<f64, ()>(x * x - 2.0);
+        assert!(close(solve(&f, 1.0).unwrap(), 2f64.sqrt()));
+        assert!(close(solve(&f, -5.0).unwrap(), -(2f64.sqrt())));
+        assert!(close(solve_between(&f, 0.0, 2.0).unwrap(), 2f64.sqrt()));
+        assert!(matches!(
+            solve_between(&f, 2.0, 3.0),
+            Err(Failure::Convergence(_))
+        ));
+        assert!(matches!(
+            solve(&|x: f64| Ok::<f64, ()>(x * x + 1.0), 1.0),
+            Err(Failure::Convergence(_))
+        ));
+        assert!(close(derivative(&f, 3.0).unwrap(), 6.0));
+        assert!(close(integral(&f, 0.0, 3.0).unwrap(), 3.0));
+        assert!(close(extremum(&f, -1.0, 2.0, false).unwrap(), -2.0));
+        assert!(close(extremum(&f, -1.0, 2.0, true).unwrap(), 2.0));
+        assert_eq!(
+            integral(
+                &|x: f64| if x > 1.0 { Err("undefined") } else { Ok(x) },
+                0.0,
+                2.0
+            ),
+            Err(Failure::Evaluation("undefined"))
+        );
+    }
+
+    #[test]
+    fn functions() {
+        let mut session = Session::new();
+        session.execute("f(t) = t^3 - 2t - 5").unwrap();
+        session.execute("x = 10").unwrap();
+        assert!(close(
+            eval(&session, "solve(x^2 - 2 = 0, x, 1)").unwrap(),
+            2f64.sqrt()
+        ));
+        assert!(close(
+            eval(&session, "solve(f(x), x, 2)").unwrap(),
+            2.0945514815423265
+        ));
+        assert!(close(
+            eval(&session, "deriv(sin(x)*x, x, 2.0)").unwrap(),
+            2f64.sin() + 2.0 * 2f64.cos()
+        ));
+        assert!(close(
+            eval(&session, "integrate(exp(-x^2), x, 0, 3)").unwrap(),
+            0.886207348259521
+        ));
+        assert!(close(
+            eval(&session, "maximum(sin(x), x, 0, 10)").unwrap(),
+            1.0
+        ));
+        assert_eq!(eval(&session, "x"), Ok(10.0));
+
+        assert!(matches!(
+            eval(&session, "solve(x^2 + 1 = 0, x, 1)"),
+            Err(ErrorKind::Convergence { .. })
+        ));
+        assert!(matches!(
+            eval(&session, "deriv(x, 1, 2)"),
+            Err(ErrorKind::Arguments { .. })
+        ));
+        assert_eq!(
+            eval(&session, "integrate(1/x, x, -1, 1)"),
+            Err(ErrorKind::DivisionByZero)
+        );
+        assert_eq!(
+            eval(&session, "deriv(x = 1, x, 0)"),
+            Err(ErrorKind::UnexpectedToken(String::from("=")))
+        );
+        assert_eq!(
+            session.execute("y = solve(x = 4, x, 0)").unwrap(),
+            Outcome::Value {
+                name: Some(String::from("y")),
+                value: Number::Float(4.0)
+            }
+        );
+    }
+}
@@ -0,0 +1,384 @@
+//! Parser submodule.
+//!
+//! Parses the tokens of an expression into a syntax tree, by recursive descent.
+//! From the lowest to the highest precedence:
+//! - the bitwise `|`, then `^` in the programmer mode, then `&`, then the shifts `<<` and `>>`;
+//! - `+` and `-`;
+//! - `*`, `/` and `%`;
+//! - the implicit multiplication, e.g. `2pi`, `3(x+1)` or `512 MiB / 4 s`, where `4 s` is the divisor;
+//! - the prefix `+`, `-` and `~`;
+//! - `^` or `**`, right-associative, e.g. `2^3^2` is `2^9`; only `**` in the programmer mode,
+//!   where `^` is the bitwise xor;
+//! - the postfix `!` and `%`, e.g. `5!` or `15%`.
+//!
+//! `%` is the remainder if an operand follows, e.g. `7 % 3`, and the percent otherwise, e.g. `200 * 15%`.
+//! A whole expression may be followed by `in` and a unit to convert it to, e.g. `3 km + 200 m in mi`.
+//! The first argument of `solve` may be an equation, e.g. `solve(x^2 = 2, x, 1)`.
+//! A matrix is written by rows in brackets, the elements separated by `,` and the rows by `;`,
+//! e.g. `[1, 2; 3, 4]`.
+
+use std::ops::Range;
+
+use super::{
+    ast::{Infix, Node, NodeKind, Postfix, Prefix},
+    expression::{ErrorKind, ExpressionError},
+    lexer::{self, Token, TokenKind},
+    number::Mode,
+    numeric,
+};
+
+/// The keyword of the unit conversions.
+pub const IN: &str = "in";
+
+/// Parses the expression, with the syntax of the mode.
+pub fn parse(expression: &str, mode: Mode) -> Result<Node, ExpressionError> {
+    let mut parser = Parser {
+        expression,
+        tokens: lexer::tokenize(expression)?,
+        position: 0,
+        depth: 0,
+        programmer: matches!(mode, Mode::Programmer(_)),
+    };
+    let mut node = parser.bit_or()?;
+    if parser.keyword() {
+        parser.position += 1;
+        let target = parser.bit_or()?;
+        let span = node.span.start..target.span.end;
+        node = Node::new(NodeKind::Convert(Box::new(node), Box::new(target)), span);
+    }
+    match parser.peek() {
+        None => Ok(node),
+        Some(token) => Err(parser.unexpected(token)),
+    }
+}
+
+/// The state of the parsing.
+struct Parser<'a> {
+    expression: &'a str,
+    tokens: Vec<Token>,
+    position: usize,
+    /// The number of unclosed parentheses.
+    depth: i32,
+    /// Whether `^` is the bitwise xor rather than the power.
+    programmer: bool,
+}
+
+impl Parser<'_> {
+    /// The next token.
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    /// The kind of the token after the next one.
+    fn peek_second(&self) -> Option<&TokenKind> {
+        self.tokens.get(self.position + 1).map(|token| &token.kind)
+    }
+
+    /// Consumes the next token.
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    /// Whether the next token is the keyword `in`.
+    fn keyword(&self) -> bool {
+        matches!(self.peek(), Some(Token { kind: TokenKind::Identifier(name), .. }) if name == IN)
+    }
+
+    /// Consumes the next token if it's one of the operators, and returns the operator's value.
+    fn operator<T: Copy>(&mut self, operators: &[(&str, T)]) -> Option<(T, Range<usize>)> {
+        let Some(Token {
+            kind: TokenKind::Operator(operator),
+            span,
+        }) = self.peek()
+        else {
+            return None;
+        };
+        let (_, value) = operators.iter().find(|(name, _)| name == operator)?;
+        let operator = (*value, span.clone());
+        self.position += 1;
+        Some(operator)
+    }
+
+    /// `operand (operator operand)*`, left-associative.
+    fn binary(
+        &mut self,
+        operators: &[(&str, Infix)],
+        operand: fn(&mut Self) -> Result<Node, ExpressionError>,
+    ) -> Result<Node, ExpressionError> {
+        let mut left = operand(self)?;
+        while let Some((operator, _)) = self.operator(operators) {
+            let right = operand(self)?;
+            left = infix(operator, left, right);
+        }
+        Ok(left)
+    }
+
+    /// `bit_xor ('|' bit_xor)*`
+    fn bit_or(&mut self) -> Result<Node, ExpressionError> {
+        self.binary(&[("|", Infix::BitOr)], Parser::bit_xor)
+    }
+
+    /// `bit_and ('^' bit_and)*` in the programmer mode, `bit_and` otherwise.
+    fn bit_xor(&mut self) -> Result<Node, ExpressionError> {
+        let operators: &[(&str, Infix)] = match self.programmer {
+            true => &[("^", Infix::BitXor)],
+            false => &[],
+        };
+        self.binary(operators, Parser::bit_and)
+    }
+
+    /// `shift ('&' shift)*`
+    fn bit_and(&mut self) -> Result<Node, ExpressionError> {
+        self.binary(&[("&", Infix::BitAnd)], Parser::shift)
+    }
+
+    /// `sum (('<<' | '>>') sum)*`
+    fn shift(&mut self) -> Result<Node, ExpressionError> {
+        let operators = &[("<<", Infix::ShiftLeft), (">>", Infix::ShiftRight)];
+        self.binary(operators, Parser::sum)
+    }
+
+    /// `product (('+' | '-') product)*`
+    fn sum(&mut self) -> Result<Node, ExpressionError> {
+        let operators = &[("+", Infix::Add), ("-", Infix::Subtract)];
+        self.binary(operators, Parser::product)
+    }
+
+    /// `implicit (('*' | '/' | '%') implicit)*`
+    fn product(&mut self) -> Result<Node, ExpressionError> {
+        let operators = &[
+            ("*", Infix::Multiply),
+            ("/", Infix::Divide),
+            ("%", Infix::Remainder),
+        ];
+        self.binary(operators, Parser::implicit)
+    }
+
+    /// `prefix power*`, the implicit multiplication, if each `power` starts with a name, `(` or `[`.
+    fn implicit(&mut self) -> Result<Node, ExpressionError> {
+        let mut left = self.prefix()?;
+        while !self.keyword()
+            && matches!(
+                self.peek().map(|token| &token.kind),
+                Some(TokenKind::Identifier(_) | TokenKind::LeftParen | TokenKind::LeftBracket)
+            )
+        {
+            let right = self.power()?;
+            left = infix(Infix::ImplicitMultiply, left, right);
+        }
+        Ok(left)
+    }
+
+    /// `('+' | '-' | '~') prefix | power`
+    fn prefix(&mut self) -> Result<Node, ExpressionError> {
+        let operators = &[
+            ("+", Prefix::Plus),
+            ("-", Prefix::Minus),
+            ("~", Prefix::Not),
+        ];
+        match self.operator(operators) {
+            Some((operator, span)) => {
+                let operand = self.prefix()?;
+                let span = span.start..operand.span.end;
+                Ok(Node::new(
+                    NodeKind::Prefix(operator, Box::new(operand)),
+                    span,
+                ))
+            }
+            None => self.power(),
+        }
+    }
+
+    /// `postfix (('^' | '**') prefix)?`, so that the exponent may have a sign, e.g. `2^-1`.
+    fn power(&mut self) -> Result<Node, ExpressionError> {
+        let operators: &[(&str, Infix)] = match self.programmer {
+            true => &[("**", Infix::Power)],
+            false => &[("^", Infix::Power), ("**", Infix::Power)],
+        };
+        let base = self.postfix()?;
+        match self.operator(operators) {
+            Some(_) => {
+                let exponent = self.prefix()?;
+                Ok(infix(Infix::Power, base, exponent))
+            }
+            None => Ok(base),
+        }
+    }
+
+    /// `primary ('!' | '%')*`, where `%` is the percent if no operand follows it.
+    fn postfix(&mut self) -> Result<Node, ExpressionError> {
+        let mut operand = self.primary()?;
+        loop {
+            let operator = match self.peek() {
+                Some(Token {
+                    kind: TokenKind::Operator("!"),
+                    span,
+                }) => (Postfix::Factorial, span.clone()),
+                Some(Token {
+                    kind: TokenKind::Operator("%"),
+                    span,
+                }) if !matches!(
+                    self.peek_second(),
+                    Some(
+                        TokenKind::Number(_)
+                            | TokenKind::Identifier(_)
+                            | TokenKind::LeftParen
+                            | TokenKind::Operator("~")
+                    )
+                ) =>
+                {
+                    (Postfix::Percent, span.clone())
+                }
+                _ => return Ok(operand),
+            };
+            self.position += 1;
+            let span = operand.span.start..operator.1.end;
+            operand = Node::new(NodeKind::Postfix(operator.0, Box::new(operand)), span);
+        }
+    }
+
+    /// A number, a string, a variable, a function call, a matrix, or a parenthesized expression.
+    fn primary(&mut self) -> Result<Node, ExpressionError> {
+        let token = self.next().ok_or_else(|| self.missing_operand())?;
+        match token.kind {
+            TokenKind::Number(literal) => Ok(Node::new(NodeKind::Number(literal), token.span)),
+            TokenKind::Text(text) => Ok(Node::new(NodeKind::Text(text), token.span)),
+            TokenKind::Identifier(ref name) if name == IN => Err(self.unexpected(&token)),
+            TokenKind::Identifier(name) => {
+                if self.peek().map(|token| &token.kind) != Some(&TokenKind::LeftParen) {
+                    return Ok(Node::new(NodeKind::Variable(name), token.span));
+                }
+                self.position += 1;
+                self.depth += 1;
+                let mut args = vec![];
+                if !self.closing() {
+                    loop {
+                        let mut arg = self.bit_or()?;
+                        if args.is_empty() && name == numeric::SOLVE {
+                            if let Some(((), _)) = self.operator(&[("=", ())]) {
+                                let right = self.bit_or()?;
+                                let span = arg.span.start..right.span.end;
+                                arg = Node::new(
+                                    NodeKind::Equation(Box::new(arg), Box::new(right)),
+                                    span,
+                                );
+                            }
+                        }
+                        args.push(arg);
+                        if self.peek().map(|token| &token.kind) == Some(&TokenKind::Comma) {
+                            self.position += 1;
+                        } else {
+                            break;
+                        }
+                    }
+                }
+                let end = self.close()?;
+                Ok(Node::new(NodeKind::Call(name, args), token.span.start..end))
+            }
+            TokenKind::LeftParen => {
+                self.depth += 1;
+                let mut node = self.bit_or()?;
+                node.span = token.span.start..self.close()?;
+                Ok(node)
+            }
+            TokenKind::LeftBracket => self.matrix(token.span.start),
+            _ => Err(self.unexpected(&token)),
+        }
+    }
+
+    /// The rows of a matrix after its `[`, e.g. `1, 2; 3, 4]`, separated by `;`.
+    fn matrix(&mut self, start: usize) -> Result<Node, ExpressionError> {
+        let (mut rows, mut row) = (vec![], vec![]);
+        let end = loop {
+            row.push(self.bit_or()?);
+            match self.next() {
+                Some(Token {
+                    kind: TokenKind::Comma,
+                    ..
+                }) => {}
+                Some(Token {
+                    kind: TokenKind::Semicolon,
+                    ..
+                }) => rows.push(std::mem::take(&mut row)),
+                Some(Token {
+                    kind: TokenKind::RightBracket,
+                    span,
+                }) => {
+                    rows.push(row);
+                    break span.end;
+                }
+                Some(token) => return Err(self.unexpected(&token)),
+                None => {
+                    let end = self.expression.len();
+                    return Err(ExpressionError::new(ErrorKind::MissingBracket).at(Some(end..end)));
+                }
+            }
+        };
+
+        let expected = rows[0].len();
+        for (index, row) in rows.iter().enumerate() {
+            if row.len() != expected {
+                let span = row[0].span.start..row[row.len() - 1].span.end;
+                return Err(ExpressionError::new(ErrorKind::RaggedMatrix {
+                    row: index + 1,
+                    columns: row.len(),
+                    expected,
+                })
+                .at(Some(span)));
+            }
+        }
+        Ok(Node::new(NodeKind::Matrix(rows), start..end))
+    }
+
+    /// Whether the next token is `)`.
+    fn closing(&self) -> bool {
+        self.peek().map(|token| &token.kind) == Some(&TokenKind::RightParen)
+    }
+
+    /// Consumes `)`, and returns its end.
+    fn close(&mut self) -> Result<usize, ExpressionError> {
+        match self.next() {
+            Some(Token {
+                kind: TokenKind::RightParen,
+                span,
+            }) => {
+                self.depth -= 1;
+                Ok(span.end)
+            }
+            Some(token) => Err(self.unexpected(&token)),
+            None => {
+                let end = self.expression.len();
+                Err(
+                    ExpressionError::new(ErrorKind::MissingParenthesis(self.depth))
+                        .at(Some(end..end)),
+                )
+            }
+        }
+    }
+
+    /// The error of an unexpected token.
+    fn unexpected(&self, token: &Token) -> ExpressionError {
+        let text = self.expression[token.span.clone()].to_owned();
+        ExpressionError::new(ErrorKind::UnexpectedToken(text)).at(Some(token.span.clone()))
+    }
+
+    /// The error of an expression ending where an operand is expected.
+    fn missing_operand(&self) -> ExpressionError {
+        let end = self.expression.len();
+        ExpressionError::new(ErrorKind::MissingOperand).at(Some(end..end))
+    }
+}
+
+/// The infix node of the operands.
+fn infix(operator: Infix, left: Node, right: Node) -> Node {
+    let span = left.span.start..right.span.end;
+    Node::new(
+        NodeKind::Infix(operator, Box::new(left), Box::new(right)),
+        span,
+    )
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,54 @@
+mod parser {
+    use std::collections::BTreeMap;
+
+    use crate::calculator::{expression::evaluate, number::Mode, parser::parse};
+
+    /// Evaluates the expression with the variable `x = 2`.
+    fn eval(expression: &str) -> f64 {
+        let variables = BTreeMap::from([(String::from("x"), 2.0)]);
+        evaluate(expression, &variables).unwrap().to_f64()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(eval("2 + 3 * 4"), 14.0);
+        assert_eq!(eval("2 ^ 3 ^ 2"), 512.0);
+        assert_eq!(eval("-2^2"), -4.0);
+        assert_eq!(eval("2^-1"), 0.5);
+        assert_eq!(eval("(1 + 2) * 3"), 9.0);
+        assert_eq!(eval("10 - 4 - 3"), 3.0);
+        assert_eq!(eval("max(1, x, 3 - 4)"), 2.0);
+    }
+
+    #[test]
+    fn implicit_multiplication() {
+        assert_eq!(eval("2pi"), 2.0 * std::f64::consts::PI);
+        assert_eq!(eval("3(x + 1)"), 9.0);
+        assert_eq!(eval("2 x^2"), 8.0);
+        assert_eq!(eval("(x)(x + 1)"), 6.0);
+        assert_eq!(eval("2e"), 2.0 * std::f64::consts::E);
+        assert_eq!(eval("1 / 2x"), 0.25);
+    }
+
+    #[test]
+    fn literals_and_postfix_operators() {
+        assert_eq!(eval("1.5e3 + 2E-1 + .5"), 1500.7);
+        assert_eq!(eval("5!"), 120.0);
+        assert_eq!(eval("3!^2"), 36.0);
+        assert_eq!(eval("200 * 15%"), 30.0);
+        assert_eq!(eval("7 % 3"), 1.0);
+        assert_eq!(eval("50% + 1"), 1.5);
+    }
+
+    #[test]
+    fn tree() {
+        let tree = parse("2+3*4", Mode::Float).unwrap().tree();
+        assert_eq!(tree, "+\n├── 2\n└── *\n    ├── 3\n    └── 4\n");
+
+        let tree = parse("-2x!", Mode::Float).unwrap().tree();
+        assert_eq!(
+            tree,
+            "* (implicit)\n├── - (prefix)\n│   └── 2\n└── ! (postfix)\n    └── x\n"
+        );
+    }
+}
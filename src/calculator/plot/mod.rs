@@ -0,0 +1,400 @@
+//! Plot submodule.
+//!
+//! Samples functions of `x`, e.g. `sin(x)*x`, over a range, and draws them on one chart:
+//! to a PNG or SVG file with `plotters`, or as a braille preview in the terminal.
+//! The samples are computed with `f64` numbers, whatever the mode of the session; the samples
+//! where a function isn't defined, e.g. `1/x` at `0` or `sqrt(x)` below `0`, are gaps in the curve.
+
+use colored::Colorize;
+use plotters::{coord::Shift, prelude::*};
+use std::{ops::Range, path::Path};
+
+use super::{
+    expression::{self, ErrorKind, ExpressionError, Function, Scope},
+    number::{Mode, Number},
+};
+
+/// The variable of the plotted functions.
+pub const VARIABLE: &str = "x";
+
+/// The range of `x` if the plot has none.
+pub const DEFAULT_RANGE: (f64, f64) = (-10.0, 10.0);
+
+/// The number of samples of each function if the plot doesn't set it.
+pub const DEFAULT_SAMPLES: usize = 200;
+
+pub const MAX_SAMPLES: usize = 10_000;
+
+/// The size of the charts drawn to files, in pixels.
+const CHART_SIZE: (u32, u32) = (1280, 960);
+
+/// The size of the terminal preview, in characters.
+const PREVIEW_SIZE: (usize, usize) = (64, 16);
+
+/// The colors of the curves in the terminal preview, in the order of the functions.
+const PREVIEW_COLORS: &[colored::Color] = &[
+    colored::Color::Green,
+    colored::Color::Cyan,
+    colored::Color::Magenta,
+    colored::Color::Yellow,
+    colored::Color::Blue,
+    colored::Color::Red,
+];
+
+/// The arguments of the `:plot` command, e.g. `sin(x), cos(x) from -pi to pi samples 400 into plot.svg`.
+#[derive(Debug, PartialEq)]
+pub struct Request<'a> {
+    pub functions: Vec<&'a str>,
+    /// The bounds of `x`, as expressions.
+    pub range: Option<(&'a str, &'a str)>,
+    pub samples: Option<&'a str>,
+    pub file: Option<&'a Path>,
+}
+
+impl Request<'_> {
+    /// Parses the arguments; `None` if there is no function, or a keyword has no value.
+    /// The keywords follow the functions in the order `from`, `samples`, `into`.
+    pub fn parse(argument: &str) -> Option<Request<'_>> {
+        let (argument, file) = split_keyword(argument, "into")?;
+        let (argument, samples) = split_keyword(argument, "samples")?;
+        let (argument, range) = split_keyword(argument, "from")?;
+        let range = match range {
+            Some(range) => match split_keyword(range, "to")? {
+                (from, Some(to)) if !from.is_empty() => Some((from, to)),
+                _ => return None,
+            },
+            None => None,
+        };
+        let functions = split_functions(argument);
+        if functions.iter().any(|function| function.is_empty()) {
+            return None;
+        }
+        Some(Request {
+            functions,
+            range,
+            samples,
+            file: file.map(Path::new),
+        })
+    }
+}
+
+/// The text before the last whitespace-separated keyword, and the text after it, if any;
+/// `None` if the keyword is the last word.
+fn split_keyword<'a>(text: &'a str, keyword: &str) -> Option<(&'a str, Option<&'a str>)> {
+    let position = text.rmatch_indices(keyword).find(|(index, _)| {
+        let before = text[..*index].chars().next_back();
+        let after = text[index + keyword.len()..].chars().next();
+        before.is_some_and(char::is_whitespace) && after.is_none_or(char::is_whitespace)
+    });
+    match position {
+        Some((index, _)) => {
+            let value = text[index + keyword.len()..].trim();
+            match value.is_empty() {
+                true => None,
+                false => Some((text[..index].trim(), Some(value))),
+            }
+        }
+        None => Some((text.trim(), None)),
+    }
+}
+
+/// The functions separated by the commas outside of the parentheses, e.g. `max(x, 0), x^2`.
+fn split_functions(text: &str) -> Vec<&str> {
+    let (mut functions, mut start, mut depth) = (vec![], 0, 0);
+    for (index, char) in text.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                functions.push(text[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    functions.push(text[start..].trim());
+    functions
+}
+
+/// The samples of a function.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub function: String,
+    /// The values of `x`, and the values of the function where it's defined.
+    pub points: Vec<(f64, Option<f64>)>,
+}
+
+impl Series {
+    /// The runs of consecutive defined points.
+    fn segments(&self) -> Vec<Vec<(f64, f64)>> {
+        let mut segments = vec![vec![]];
+        for (x, y) in &self.points {
+            match y {
+                Some(y) => segments.last_mut().expect("A segment").push((*x, *y)),
+                None => segments.push(vec![]),
+            }
+        }
+        segments.retain(|segment| !segment.is_empty());
+        segments
+    }
+}
+
+/// The samples of the functions over the range of `x`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Plot {
+    pub series: Vec<Series>,
+    pub range: Range<f64>,
+}
+
+impl Plot {
+    /// The range of the values, with a margin, or `-1..1` if no function is defined in the range of `x`.
+    pub fn value_range(&self) -> Range<f64> {
+        let values = self
+            .series
+            .iter()
+            .flat_map(|series| series.points.iter().filter_map(|(_, y)| *y));
+        let (min, max) = values.fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), y| {
+            (min.min(y), max.max(y))
+        });
+        if min > max {
+            return -1.0..1.0;
+        }
+        if min == max {
+            return min - 1.0..max + 1.0;
+        }
+        let margin = (max - min) * 0.05;
+        min - margin..max + margin
+    }
+
+    /// Draws the chart to the PNG or SVG file, depending on its extension.
+    pub fn draw(&self, path: &Path) -> Result<(), String> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_lowercase);
+        match extension.as_deref() {
+            Some("png") => {
+                let root = BitMapBackend::new(path, CHART_SIZE).into_drawing_area();
+                self.chart(&root)?;
+                root.present().map_err(|error| error.to_string())
+            }
+            Some("svg") => {
+                let root = SVGBackend::new(path, CHART_SIZE).into_drawing_area();
+                self.chart(&root)?;
+                root.present().map_err(|error| error.to_string())
+            }
+            _ => Err(String::from(
+                "unsupported file format, expected a .png or .svg file",
+            )),
+        }
+    }
+
+    /// Draws the chart with the axes, the curves and their legend.
+    fn chart<DB: DrawingBackend>(&self, root: &DrawingArea<DB, Shift>) -> Result<(), String> {
+        let failed = |error: DrawingAreaErrorKind<DB::ErrorType>| error.to_string();
+        root.fill(&WHITE).map_err(failed)?;
+
+        let caption = self
+            .series
+            .iter()
+            .map(|series| series.function.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut chart = ChartBuilder::on(root)
+            .caption(caption, ("sans-serif", 40).into_font())
+            .margin(20)
+            .x_label_area_size(50)
+            .y_label_area_size(80)
+            .build_cartesian_2d(self.range.clone(), self.value_range())
+            .map_err(failed)?;
+        chart
+            .configure_mesh()
+            .x_desc(VARIABLE)
+            .y_desc("y")
+            .label_style(("sans-serif", 18))
+            .axis_desc_style(("sans-serif", 22))
+            .draw()
+            .map_err(failed)?;
+
+        for (index, series) in self.series.iter().enumerate() {
+            let color = Palette99::pick(index).stroke_width(2);
+            for (segment_index, segment) in series.segments().into_iter().enumerate() {
+                let drawn = chart
+                    .draw_series(LineSeries::new(segment, color))
+                    .map_err(failed)?;
+                if segment_index == 0 {
+                    drawn.label(&series.function).legend(move |(x, y)| {
+                        PathElement::new(vec![(x, y), (x + 20, y)], Palette99::pick(index))
+                    });
+                }
+            }
+        }
+        chart
+            .configure_series_labels()
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .label_font(("sans-serif", 20))
+            .draw()
+            .map_err(failed)
+    }
+
+    /// The chart drawn with braille characters, with the bounds of the axes and the legend.
+    pub fn preview(&self) -> Vec<String> {
+        let (width, height) = PREVIEW_SIZE;
+        let (columns, rows) = (width * 2, height * 4);
+        let value_range = self.value_range();
+        // The braille dots of each character, and the index of the last function drawn on it.
+        let mut cells = vec![vec![(0u8, None); width]; height];
+        let mut dot = |column: i64, row: i64, index: usize| {
+            if column < 0 || row < 0 || column >= columns as i64 || row >= rows as i64 {
+                return;
+            }
+            let (column, row) = (column as usize, row as usize);
+            let bit = match (column % 2, row % 4) {
+                (0, 3) => 0x40,
+                (1, 3) => 0x80,
+                (0, row) => 1 << row,
+                (_, row) => 1 << (row + 3),
+            };
+            let cell = &mut cells[row / 4][column / 2];
+            *cell = (cell.0 | bit, Some(index));
+        };
+
+        let scale = |value: f64, range: &Range<f64>, dots: usize| {
+            ((value - range.start) / (range.end - range.start) * (dots - 1) as f64).round() as i64
+        };
+        for (index, series) in self.series.iter().enumerate() {
+            for segment in series.segments() {
+                let dots: Vec<(i64, i64)> = segment
+                    .iter()
+                    .map(|(x, y)| {
+                        let column = scale(*x, &self.range, columns);
+                        let row = rows as i64 - 1 - scale(*y, &value_range, rows);
+                        (column, row)
+                    })
+                    .collect();
+                dot(dots[0].0, dots[0].1, index);
+                for pair in dots.windows(2) {
+                    let ((x0, y0), (x1, y1)) = (pair[0], pair[1]);
+                    let steps = (x1 - x0).abs().max((y1 - y0).abs()).max(1);
+                    for step in 1..=steps {
+                        dot(
+                            x0 + (x1 - x0) * step / steps,
+                            y0 + (y1 - y0) * step / steps,
+                            index,
+                        );
+                    }
+                }
+            }
+        }
+
+        let label = |value: f64| Number::Float(value).format(Mode::Decimal(2));
+        let (top, bottom) = (label(value_range.end), label(value_range.start));
+        let margin = top.chars().count().max(bottom.chars().count());
+        let mut lines = vec![];
+        for (row, cells) in cells.iter().enumerate() {
+            let axis = match row {
+                0 => format!("{:>margin$} ┤", top),
+                row if row == height - 1 => format!("{:>margin$} ┤", bottom),
+                _ => format!("{:>margin$} │", ""),
+            };
+            let curve: String = cells
+                .iter()
+                .map(|(bits, index)| {
+                    let char = char::from_u32(0x2800 + *bits as u32).unwrap_or(' ');
+                    match index {
+                        Some(index) => char
+                            .to_string()
+                            .color(PREVIEW_COLORS[index % PREVIEW_COLORS.len()])
+                            .to_string(),
+                        None => char.to_string(),
+                    }
+                })
+                .collect();
+            lines.push(axis + &curve);
+        }
+        lines.push(format!("{:>margin$} └{}", "", "─".repeat(width)));
+        let (start, end) = (label(self.range.start), label(self.range.end));
+        let padding = width.saturating_sub(start.chars().count() + end.chars().count());
+        lines.push(format!(
+            "{:>margin$}  {}{}{}",
+            "",
+            start,
+            " ".repeat(padding),
+            end
+        ));
+        for (index, series) in self.series.iter().enumerate() {
+            let color = PREVIEW_COLORS[index % PREVIEW_COLORS.len()];
+            lines.push(format!("{} {}", "──".color(color), series.function));
+        }
+        lines
+    }
+}
+
+/// Samples the function of `x` at evenly spaced values over the range, with the variables and
+/// the functions of the scope; a sample where the function isn't defined is a gap.
+pub fn sample(
+    function: &str,
+    scope: &dyn Scope,
+    range: &Range<f64>,
+    samples: usize,
+) -> Result<Series, ExpressionError> {
+    let mut points = vec![];
+    for index in 0..samples {
+        let x = range.start + (range.end - range.start) * index as f64 / (samples - 1) as f64;
+        let y = match expression::evaluate(function, &Sample { x, outer: scope }) {
+            Ok(value) => Some(value.to_f64()).filter(|y| y.is_finite()),
+            Err(error) if is_undefined(&error) => None,
+            Err(error) => return Err(error),
+        };
+        points.push((x, y));
+    }
+    Ok(Series {
+        function: function.to_owned(),
+        points,
+    })
+}
+
+/// Whether the failure means that the function isn't defined at the sample, rather than an invalid function.
+fn is_undefined(error: &ExpressionError) -> bool {
+    match &error.kind {
+        ErrorKind::DivisionByZero
+        | ErrorKind::Factorial(_)
+        | ErrorKind::NotANumber(_)
+        | ErrorKind::Overflow(_) => true,
+        ErrorKind::Function { error, .. } => is_undefined(error),
+        _ => false,
+    }
+}
+
+/// The variable `x` bound to a sample, in the float mode.
+struct Sample<'a> {
+    x: f64,
+    outer: &'a dyn Scope,
+}
+
+impl Scope for Sample<'_> {
+    fn variable(&self, name: &str) -> Option<Number> {
+        match name {
+            VARIABLE => Some(Number::Float(self.x)),
+            name => self.outer.variable(name),
+        }
+    }
+
+    fn names(&self) -> Vec<&str> {
+        let mut names = vec![VARIABLE];
+        names.extend(self.outer.names());
+        names
+    }
+
+    fn function(&self, name: &str) -> Option<&Function> {
+        self.outer.function(name)
+    }
+
+    fn function_names(&self) -> Vec<&str> {
+        self.outer.function_names()
+    }
+}
+
+#[cfg(test)]
+mod tests;
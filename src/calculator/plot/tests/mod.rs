@@ -0,0 +1,64 @@
+mod plot {
+    use std::path::Path;
+
+    use crate::calculator::{
+        plot::Request,
+        session::{CalculatorError, Outcome, Session},
+    };
+
+    #[test]
+    fn requests() {
+        assert_eq!(
+            Request::parse("max(x, 0), x^2 from -pi to 2pi samples 50 into plot.svg"),
+            Some(Request {
+                functions: vec!["max(x, 0)", "x^2"],
+                range: Some(("-pi", "2pi")),
+                samples: Some("50"),
+                file: Some(Path::new("plot.svg")),
+            })
+        );
+        assert_eq!(
+            Request::parse("sin(x)*x").map(|request| request.range),
+            Some(None)
+        );
+        assert_eq!(Request::parse("sin(x) from 1"), None);
+        assert_eq!(Request::parse("sin(x), from 1 to 2"), None);
+        assert_eq!(Request::parse("sin(x) into"), None);
+    }
+
+    #[test]
+    fn sampling() {
+        let mut session = Session::new();
+        session.execute("x = 100").unwrap();
+        session.execute("f(t) = 1 / t").unwrap();
+        let Outcome::Plot(plot) = session
+            .execute(":plot x + 1, f(x) from -1 to 1 samples 5")
+            .unwrap()
+        else {
+            panic!("expected a plot");
+        };
+        assert_eq!(plot.series[0].points[0], (-1.0, Some(0.0)));
+        assert_eq!(plot.series[1].points[2], (0.0, None));
+        assert_eq!(plot.value_range(), -2.2..2.2);
+        let preview = plot.preview();
+        assert!(preview[0].starts_with(" 2.2 ┤"));
+        assert!(preview.last().unwrap().ends_with("f(x)"));
+
+        assert!(matches!(
+            session.execute(":plot y from 0 to 1"),
+            Err(CalculatorError::Expression { .. })
+        ));
+        assert!(matches!(
+            session.execute(":plot x from 1 to 0"),
+            Err(CalculatorError::PlotRange(..))
+        ));
+        assert!(matches!(
+            session.execute(":plot x samples 1"),
+            Err(CalculatorError::InvalidSamples(_))
+        ));
+        assert!(matches!(
+            session.execute(":plot x into plot.txt"),
+            Err(CalculatorError::Draw { .. })
+        ));
+    }
+}
@@ -0,0 +1,645 @@
+//! Session submodule.
+//!
+//! A calculator session evaluates expressions, assignments like `x = 3.5`, function definitions
+//! like `f(x, y) = x^2 + y`, and commands like `:vars`.
+//! The variables, the functions and the previous result, `ans`, persist within the session.
+//! A variable shadows the built-in unit of the same name, e.g. `m = 5` makes `3 m` equal to `15`.
+//! A function may call other functions, but not itself, even indirectly.
+//! The arithmetic mode of the session, see the `number` submodule, is selected with `:mode`;
+//! the body of a function is parsed with the syntax of the mode of its definition, e.g. `^` is
+//! the bitwise xor in a function defined in the programmer mode.
+//! A sandboxed session, e.g. of the server, can't read or write files: `load`, `:save`, `:load`
+//! and the plots into a file are rejected.
+//! A session may have a budget of evaluation steps, the evaluated nodes of the syntax trees, so that
+//! an expensive expression, e.g. an integral, fails instead of running for seconds.
+
+use std::{
+    cell::Cell,
+    collections::{BTreeMap, BTreeSet},
+    fs, io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use super::{
+    ast::Node,
+    expression::{self, ErrorKind, ExpressionError, Function, Scope, CONSTANTS, FUNCTIONS},
+    integer::{IntegerType, TYPES},
+    number::{Mode, Number, MAX_PLACES},
+    parser,
+    plot::{self, Plot},
+};
+
+/// The name of the previous result.
+pub const ANS: &str = "ans";
+
+/// The commands of the session.
+pub const COMMANDS: &[(&str, &str)] = &[
+    (":vars", "list the variables and the previous result"),
+    (":funcs", "list the functions"),
+    (
+        ":clear",
+        "remove the variables, the functions and the previous result",
+    ),
+    (
+        ":save FILE",
+        "save the mode, the variables and the functions to the file",
+    ),
+    (
+        ":load FILE",
+        "evaluate the assignments and expressions of the file",
+    ),
+    (":ast EXPR", "print the syntax tree of the expression"),
+    (
+        ":plot FUNCS",
+        "plot functions of `x`, e.g. `sin(x), cos(x) from -pi to pi samples 400 into plot.svg`",
+    ),
+    (
+        ":mode [MODE]",
+        "print or set the arithmetic: `float`, `exact`, `decimal N`, or an integer type like `u32`",
+    ),
+    (":help", "list the commands"),
+    (":quit", "end the session"),
+];
+
+/// Calculator failure.
+#[derive(Debug, Error)]
+pub enum CalculatorError {
+    #[error("invalid expression {expression:?}")]
+    Expression {
+        expression: String,
+        #[source]
+        source: ExpressionError,
+    },
+    #[error(
+        "invalid variable name {0:?}, expected a letter or `_` followed by letters, digits or `_`"
+    )]
+    InvalidName(String),
+    #[error("{0:?} is a reserved name")]
+    ReservedName(String),
+    #[error("{0:?} is already defined as a {1}")]
+    Defined(String, &'static str),
+    #[error("invalid function definition {0:?}, expected e.g. `f(x, y) = x^2 + y`")]
+    InvalidDefinition(String),
+    #[error("the parameter {0:?} is repeated")]
+    DuplicateParameter(String),
+    #[error("the function {name:?} is recursive: {cycle}")]
+    Recursive { name: String, cycle: String },
+    #[error("unknown command {0:?}, see :help")]
+    UnknownCommand(String),
+    #[error("the command {0} requires a file")]
+    MissingFile(&'static str),
+    #[error("the command {0} accesses files, which is not allowed in a sandboxed session")]
+    Sandboxed(&'static str),
+    #[error(
+        "invalid mode {0:?}, expected `float`, `exact`, `decimal N` with N up to {max}, or one of {types}",
+        max = MAX_PLACES,
+        types = TYPES.join(", ")
+    )]
+    InvalidMode(String),
+    #[error("can't read the session file {path:?}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("can't write the session file {path:?}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error(
+        "invalid plot {0:?}, expected functions of `x` optionally followed by `from A to B`, `samples N` and `into FILE`"
+    )]
+    InvalidPlot(String),
+    #[error("invalid sample count {0:?}, expected an integer from 2 to {max}", max = plot::MAX_SAMPLES)]
+    InvalidSamples(String),
+    #[error("invalid plot range from {0} to {1}, expected finite bounds in increasing order")]
+    PlotRange(f64, f64),
+    #[error("can't draw the plot {path:?}: {message}")]
+    Draw { path: PathBuf, message: String },
+    #[error("can't load the session file {path:?}, line {line}")]
+    Load {
+        path: PathBuf,
+        line: usize,
+        #[source]
+        source: Box<CalculatorError>,
+    },
+}
+
+impl CalculatorError {
+    /// The message of the error followed by the messages of its causes.
+    pub fn describe(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(cause) = source {
+            message.push_str(": ");
+            message.push_str(&cause.to_string());
+            source = cause.source();
+        }
+        message
+    }
+
+    /// The expression failure and its expression, also within a session file.
+    pub fn expression_error(&self) -> Option<(&str, &ExpressionError)> {
+        match self {
+            CalculatorError::Expression { expression, source } => Some((expression, source)),
+            CalculatorError::Load { source, .. } => source.expression_error(),
+            _ => None,
+        }
+    }
+}
+
+/// Custom result type for the calculator.
+pub type Result<T> = std::result::Result<T, CalculatorError>;
+
+/// The outcome of a session input line.
+#[derive(Debug, PartialEq)]
+pub enum Outcome {
+    /// The result of an expression, or the assigned value of a variable.
+    Value {
+        name: Option<String>,
+        value: Number,
+    },
+    /// The variables, and the previous result if any.
+    Variables(Vec<(String, Number)>),
+    /// A function definition.
+    Defined(Function),
+    Functions(Vec<Function>),
+    Cleared,
+    Saved {
+        path: PathBuf,
+        variables: usize,
+        functions: usize,
+    },
+    Loaded {
+        path: PathBuf,
+        statements: usize,
+    },
+    /// The syntax tree drawing of an expression.
+    Ast(String),
+    /// The samples of the plotted functions, for the terminal preview.
+    Plot(Plot),
+    /// A plot drawn to the file.
+    Plotted {
+        path: PathBuf,
+        functions: usize,
+    },
+    /// The arithmetic mode, after it's set if any.
+    Mode(Mode),
+    Help,
+    Quit,
+    /// An empty line or a comment.
+    Empty,
+}
+
+/// The calculator session.
+#[derive(Debug, Default)]
+pub struct Session {
+    variables: BTreeMap<String, Number>,
+    functions: BTreeMap<String, Function>,
+    ans: Option<Number>,
+    mode: Mode,
+    sandboxed: bool,
+    /// The largest number of steps of an evaluation, and the remaining steps of the current one.
+    budget: Option<(u64, Cell<u64>)>,
+}
+
+impl Session {
+    /// Session constructor.
+    pub fn new() -> Session {
+        Session::default()
+    }
+
+    /// Makes the session sandboxed, without access to the files.
+    pub fn sandbox(mut self) -> Session {
+        self.sandboxed = true;
+        self
+    }
+
+    /// Limits the evaluations of the session to the number of steps.
+    pub fn budget(mut self, steps: u64) -> Session {
+        self.budget = Some((steps, Cell::new(steps)));
+        self
+    }
+
+    /// The variables of the session.
+    pub fn variables(&self) -> &BTreeMap<String, Number> {
+        &self.variables
+    }
+
+    /// The functions of the session.
+    pub fn functions(&self) -> &BTreeMap<String, Function> {
+        &self.functions
+    }
+
+    /// The previous result.
+    pub fn ans(&self) -> Option<&Number> {
+        self.ans.as_ref()
+    }
+
+    /// The arithmetic mode.
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    /// Sets the arithmetic mode.
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+    }
+
+    /// Executes the input line: a command, a function definition, an assignment, or an expression.
+    pub fn execute(&mut self, line: &str) -> Result<Outcome> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(Outcome::Empty);
+        }
+        if line.starts_with(':') {
+            return self.command(line);
+        }
+        self.statement(line)
+    }
+
+    /// Evaluates the expression with the variables and the functions of the session.
+    pub fn evaluate(&self, expression: &str) -> Result<Number> {
+        if let Some((steps, remaining)) = &self.budget {
+            remaining.set(*steps);
+        }
+        expression::evaluate(expression, self).map_err(|source| CalculatorError::Expression {
+            expression: expression.to_owned(),
+            source,
+        })
+    }
+
+    /// Defines a function, or evaluates an assignment or an expression and stores the result as `ans`.
+    fn statement(&mut self, line: &str) -> Result<Outcome> {
+        let (name, expression) = match split_assignment(line) {
+            Some((target, body)) if target.contains('(') => {
+                return self.define(target.trim(), body.trim())
+            }
+            Some((name, expression)) => (Some(validate_name(name.trim())?), expression.trim()),
+            None => (None, line),
+        };
+        if let Some(name) = name
+            .as_ref()
+            .filter(|name| self.functions.contains_key(*name))
+        {
+            return Err(CalculatorError::Defined(name.clone(), "function"));
+        }
+
+        let value = self.evaluate(expression)?;
+        if let Some(name) = &name {
+            self.variables.insert(name.clone(), value.clone());
+        }
+        self.ans = Some(value.clone());
+        Ok(Outcome::Value { name, value })
+    }
+
+    /// Defines the function, e.g. `f(x, y)` with the body `x^2 + y`.
+    fn define(&mut self, signature: &str, body: &str) -> Result<Outcome> {
+        let invalid = || CalculatorError::InvalidDefinition(signature.to_owned());
+        let (name, parameters) = signature.split_once('(').ok_or_else(invalid)?;
+        let parameters = parameters.strip_suffix(')').ok_or_else(invalid)?;
+
+        let name = validate_name(name.trim())?;
+        if self.variables.contains_key(&name) {
+            return Err(CalculatorError::Defined(name, "variable"));
+        }
+        let mut names: Vec<String> = vec![];
+        let parameters = parameters.trim();
+        for parameter in parameters.split(',').filter(|_| !parameters.is_empty()) {
+            let parameter = validate_name(parameter.trim())?;
+            if names.contains(&parameter) {
+                return Err(CalculatorError::DuplicateParameter(parameter));
+            }
+            names.push(parameter);
+        }
+
+        let node =
+            parser::parse(body, self.mode).map_err(|source| CalculatorError::Expression {
+                expression: body.to_owned(),
+                source,
+            })?;
+        let mut cycle = vec![name.clone()];
+        if self.reaches(&name, &node, &mut cycle, &mut BTreeSet::new()) {
+            return Err(CalculatorError::Recursive {
+                name,
+                cycle: cycle.join(" -> "),
+            });
+        }
+
+        let function = Function {
+            name: name.clone(),
+            parameters: names,
+            body: body.to_owned(),
+            node,
+            mode: self.mode,
+        };
+        self.functions.insert(name, function.clone());
+        Ok(Outcome::Defined(function))
+    }
+
+    /// Whether the node calls the target function, directly or through other functions;
+    /// `path` is the chain of calls to the target.
+    fn reaches<'a>(
+        &'a self,
+        target: &str,
+        node: &'a Node,
+        path: &mut Vec<String>,
+        visited: &mut BTreeSet<&'a str>,
+    ) -> bool {
+        for call in node.calls() {
+            path.push(call.to_owned());
+            if call == target {
+                return true;
+            }
+            if visited.insert(call) {
+                if let Some(function) = self.functions.get(call) {
+                    if self.reaches(target, &function.node, path, visited) {
+                        return true;
+                    }
+                }
+            }
+            path.pop();
+        }
+        false
+    }
+
+    /// Executes the command.
+    fn command(&mut self, line: &str) -> Result<Outcome> {
+        let (command, argument) = match line.split_once(char::is_whitespace) {
+            Some((command, argument)) => (command, argument.trim()),
+            None => (line, ""),
+        };
+        let file = |command| match argument {
+            _ if self.sandboxed => Err(CalculatorError::Sandboxed(command)),
+            "" => Err(CalculatorError::MissingFile(command)),
+            path => Ok(Path::new(path)),
+        };
+
+        match command {
+            ":vars" => {
+                let mut variables: Vec<(String, Number)> = self
+                    .variables
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.clone()))
+                    .collect();
+                if let Some(ans) = &self.ans {
+                    variables.push((ANS.to_owned(), ans.clone()));
+                }
+                Ok(Outcome::Variables(variables))
+            }
+            ":funcs" => Ok(Outcome::Functions(
+                self.functions.values().cloned().collect(),
+            )),
+            ":clear" => {
+                *self = Session {
+                    mode: self.mode,
+                    sandboxed: self.sandboxed,
+                    budget: self.budget.take(),
+                    ..Session::new()
+                };
+                Ok(Outcome::Cleared)
+            }
+            ":mode" => {
+                if !argument.is_empty() {
+                    self.mode = parse_mode(argument)?;
+                }
+                Ok(Outcome::Mode(self.mode))
+            }
+            ":save" => self.save(file(":save")?),
+            ":load" => self.load(file(":load")?),
+            ":ast" => {
+                let node = parser::parse(argument, self.mode).map_err(|source| {
+                    CalculatorError::Expression {
+                        expression: argument.to_owned(),
+                        source,
+                    }
+                })?;
+                Ok(Outcome::Ast(node.tree()))
+            }
+            ":plot" => self.plot(argument),
+            ":help" => Ok(Outcome::Help),
+            ":quit" | ":q" | ":exit" => Ok(Outcome::Quit),
+            _ => Err(CalculatorError::UnknownCommand(command.to_owned())),
+        }
+    }
+
+    /// Samples the functions of the plot, and draws them to the file if any.
+    fn plot(&self, argument: &str) -> Result<Outcome> {
+        let request = plot::Request::parse(argument)
+            .ok_or_else(|| CalculatorError::InvalidPlot(argument.to_owned()))?;
+        if self.sandboxed && request.file.is_some() {
+            return Err(CalculatorError::Sandboxed(":plot"));
+        }
+        let (from, to) = match request.range {
+            Some((from, to)) => (self.evaluate(from)?.to_f64(), self.evaluate(to)?.to_f64()),
+            None => plot::DEFAULT_RANGE,
+        };
+        if !(from.is_finite() && to.is_finite() && from < to) {
+            return Err(CalculatorError::PlotRange(from, to));
+        }
+        let samples = match request.samples {
+            Some(samples) => samples
+                .parse::<usize>()
+                .ok()
+                .filter(|samples| (2..=plot::MAX_SAMPLES).contains(samples))
+                .ok_or_else(|| CalculatorError::InvalidSamples(samples.to_owned()))?,
+            None => plot::DEFAULT_SAMPLES,
+        };
+
+        let range = from..to;
+        let series = request
+            .functions
+            .iter()
+            .map(|function| {
+                plot::sample(function, self, &range, samples).map_err(|source| {
+                    CalculatorError::Expression {
+                        expression: function.to_string(),
+                        source,
+                    }
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let plot = Plot { series, range };
+
+        match request.file {
+            Some(path) => {
+                plot.draw(path).map_err(|message| CalculatorError::Draw {
+                    path: path.to_owned(),
+                    message,
+                })?;
+                Ok(Outcome::Plotted {
+                    path: path.to_owned(),
+                    functions: plot.series.len(),
+                })
+            }
+            None => Ok(Outcome::Plot(plot)),
+        }
+    }
+
+    /// Saves the mode, the variables as assignments, and the functions as definitions,
+    /// each one after the mode of its definition; the file ends with the mode of the session.
+    fn save(&self, path: &Path) -> Result<Outcome> {
+        let mut content = String::from("# rust-workspace calculator session\n");
+        content.push_str(&format!(":mode {}\n", self.mode));
+        for (name, value) in &self.variables {
+            content.push_str(&format!("{} = {}\n", name, value.to_expression()));
+        }
+        let mut mode = self.mode;
+        for function in self.functions.values() {
+            if function.mode != mode {
+                mode = function.mode;
+                content.push_str(&format!(":mode {}\n", mode));
+            }
+            content.push_str(&format!("{}\n", function));
+        }
+        if mode != self.mode {
+            content.push_str(&format!(":mode {}\n", self.mode));
+        }
+        fs::write(path, content).map_err(|source| CalculatorError::Write {
+            path: path.to_owned(),
+            source,
+        })?;
+        Ok(Outcome::Saved {
+            path: path.to_owned(),
+            variables: self.variables.len(),
+            functions: self.functions.len(),
+        })
+    }
+
+    /// Evaluates the definitions, the assignments and the expressions of the file, and sets its modes;
+    /// the other commands are not allowed.
+    fn load(&mut self, path: &Path) -> Result<Outcome> {
+        let content = fs::read_to_string(path).map_err(|source| CalculatorError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+
+        let mut statements = 0;
+        for (index, line) in content.lines().enumerate() {
+            let line = line.trim();
+            let result = if let Some(mode) = line.strip_prefix(":mode ") {
+                parse_mode(mode).map(|mode| self.mode = mode)
+            } else if line.starts_with(':') {
+                Err(CalculatorError::UnknownCommand(line.to_owned()))
+            } else if line.is_empty() || line.starts_with('#') {
+                continue;
+            } else {
+                statements += 1;
+                self.statement(line).map(|_| ())
+            };
+            result.map_err(|source| CalculatorError::Load {
+                path: path.to_owned(),
+                line: index + 1,
+                source: Box::new(source),
+            })?;
+        }
+
+        Ok(Outcome::Loaded {
+            path: path.to_owned(),
+            statements,
+        })
+    }
+}
+
+impl Scope for Session {
+    fn variable(&self, name: &str) -> Option<Number> {
+        match name {
+            ANS => self.ans.clone(),
+            _ => self.variables.get(name).cloned(),
+        }
+    }
+
+    fn names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self.variables.keys().map(String::as_str).collect();
+        if self.ans.is_some() {
+            names.push(ANS);
+        }
+        names
+    }
+
+    fn function(&self, name: &str) -> Option<&Function> {
+        self.functions.get(name)
+    }
+
+    fn function_names(&self) -> Vec<&str> {
+        self.functions.keys().map(String::as_str).collect()
+    }
+
+    fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    fn sandboxed(&self) -> bool {
+        self.sandboxed
+    }
+
+    fn step(&self) -> std::result::Result<(), ErrorKind> {
+        match &self.budget {
+            Some((steps, remaining)) => {
+                let left = remaining
+                    .get()
+                    .checked_sub(1)
+                    .ok_or(ErrorKind::Budget(*steps))?;
+                remaining.set(left);
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// Splits the assignment or the definition at its `=`, which is outside of the parentheses,
+/// unlike the `=` of `solve(x^2 = 2, x, 1)`.
+fn split_assignment(line: &str) -> Option<(&str, &str)> {
+    let mut depth = 0;
+    for (index, char) in line.char_indices() {
+        match char {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            '=' if depth == 0 => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parses the arithmetic mode: `float`, `exact`, `decimal N`, or an integer type like `u32`.
+pub fn parse_mode(mode: &str) -> Result<Mode> {
+    let invalid = || CalculatorError::InvalidMode(mode.to_owned());
+    let words: Vec<&str> = mode.split_whitespace().collect();
+    match words.as_slice() {
+        ["float"] => Ok(Mode::Float),
+        ["exact"] => Ok(Mode::Exact),
+        ["decimal", places] => match places.parse::<usize>() {
+            Ok(places) if places <= MAX_PLACES => Ok(Mode::Decimal(places)),
+            _ => Err(invalid()),
+        },
+        [name] => name
+            .parse::<IntegerType>()
+            .map(Mode::Programmer)
+            .map_err(|_| invalid()),
+        _ => Err(invalid()),
+    }
+}
+
+/// Checks that the variable name is an identifier, and not a reserved name.
+fn validate_name(name: &str) -> Result<String> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|char| char.is_ascii_alphanumeric() || char == '_');
+    if !valid {
+        return Err(CalculatorError::InvalidName(name.to_owned()));
+    }
+    if name == ANS || name == parser::IN || CONSTANTS.contains(&name) || FUNCTIONS.contains(&name) {
+        return Err(CalculatorError::ReservedName(name.to_owned()));
+    }
+    Ok(name.to_owned())
+}
+
+#[cfg(test)]
+mod tests;
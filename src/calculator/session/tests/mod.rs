@@ -0,0 +1,214 @@
+mod session {
+    use std::{fs, path::PathBuf};
+
+    use num_bigint::BigInt;
+
+    use crate::calculator::{
+        integer::IntegerType,
+        number::{Mode, Number, Rational},
+        session::{CalculatorError, Outcome, Session},
+    };
+
+    /// The value of the outcome.
+    fn value(outcome: Outcome) -> f64 {
+        match outcome {
+            Outcome::Value { value, .. } => value.to_f64(),
+            outcome => panic!("Expected a value, got {:?}", outcome),
+        }
+    }
+
+    #[test]
+    fn assignments_and_ans() {
+        let mut session = Session::new();
+        assert_eq!(
+            session.execute("x = 3.5").unwrap(),
+            Outcome::Value {
+                name: Some(String::from("x")),
+                value: Number::Float(3.5)
+            }
+        );
+        assert_eq!(value(session.execute("x * 2").unwrap()), 7.0);
+        assert_eq!(value(session.execute("ans + 1").unwrap()), 8.0);
+        assert_eq!(value(session.execute("y = ans / x").unwrap()), 8.0 / 3.5);
+        assert_eq!(session.variables().len(), 2);
+        assert_eq!(session.execute("  # comment").unwrap(), Outcome::Empty);
+    }
+
+    #[test]
+    fn invalid_input() {
+        let mut session = Session::new();
+        assert!(matches!(
+            session.execute("ans"),
+            Err(CalculatorError::Expression { .. })
+        ));
+        assert!(matches!(
+            session.execute("2x = 1"),
+            Err(CalculatorError::InvalidName(_))
+        ));
+        assert!(matches!(
+            session.execute("pi = 3"),
+            Err(CalculatorError::ReservedName(_))
+        ));
+        assert!(matches!(
+            session.execute(":unknown"),
+            Err(CalculatorError::UnknownCommand(_))
+        ));
+        assert!(matches!(
+            session.execute(":save"),
+            Err(CalculatorError::MissingFile(":save"))
+        ));
+        assert!(session.variables().is_empty());
+    }
+
+    #[test]
+    fn commands() {
+        let mut session = Session::new();
+        session.execute("a = 1").unwrap();
+        session.execute("b = a + 1").unwrap();
+        assert_eq!(
+            session.execute(":vars").unwrap(),
+            Outcome::Variables(vec![
+                (String::from("a"), Number::Float(1.0)),
+                (String::from("b"), Number::Float(2.0)),
+                (String::from("ans"), Number::Float(2.0)),
+            ])
+        );
+        assert_eq!(session.execute(":clear").unwrap(), Outcome::Cleared);
+        assert_eq!(
+            session.execute(":vars").unwrap(),
+            Outcome::Variables(vec![])
+        );
+        assert_eq!(
+            session.execute(":mode exact").unwrap(),
+            Outcome::Mode(Mode::Exact)
+        );
+        assert_eq!(
+            session.execute("1/3").unwrap(),
+            Outcome::Value {
+                name: None,
+                value: Number::Exact(Rational::new(BigInt::from(1), BigInt::from(3)))
+            }
+        );
+        assert_eq!(
+            session.execute(":mode decimal 4").unwrap(),
+            Outcome::Mode(Mode::Decimal(4))
+        );
+        assert!(matches!(
+            session.execute(":mode decimal 1001"),
+            Err(CalculatorError::InvalidMode(_))
+        ));
+        assert_eq!(session.execute(":q").unwrap(), Outcome::Quit);
+
+        let mut session = Session::new().sandbox();
+        session.execute(":clear").unwrap();
+        for command in [
+            ":save session.txt",
+            ":load session.txt",
+            ":plot x into plot.svg",
+        ] {
+            assert!(matches!(
+                session.execute(command),
+                Err(CalculatorError::Sandboxed(_))
+            ));
+        }
+        assert!(matches!(session.execute(":plot x"), Ok(Outcome::Plot(_))));
+    }
+
+    #[test]
+    fn functions() {
+        let mut session = Session::new();
+        assert!(matches!(
+            session.execute("f(x, y) = x^2 + y").unwrap(),
+            Outcome::Defined(_)
+        ));
+        session.execute("g(x) = 2 f(x, 1)").unwrap();
+        session.execute("k = 3").unwrap();
+        session.execute("h() = k + 1").unwrap();
+        assert_eq!(value(session.execute("g(3) + h()").unwrap()), 24.0);
+        assert_eq!(session.functions()["g"].to_string(), "g(x) = 2 f(x, 1)");
+
+        assert!(matches!(
+            session.execute("f(x) = f(x - 1)"),
+            Err(CalculatorError::Recursive { .. })
+        ));
+        match session.execute("f(x, y) = g(x) + y") {
+            Err(CalculatorError::Recursive { cycle, .. }) => assert_eq!(cycle, "f -> g -> f"),
+            result => panic!("Expected a recursive function, got {:?}", result),
+        }
+        assert!(matches!(
+            session.execute("k(x) = x"),
+            Err(CalculatorError::Defined(..))
+        ));
+        assert!(matches!(
+            session.execute("f = 1"),
+            Err(CalculatorError::Defined(..))
+        ));
+        assert!(matches!(
+            session.execute("p(x, x) = x"),
+            Err(CalculatorError::DuplicateParameter(_))
+        ));
+        assert!(matches!(
+            session.execute("f(1)"),
+            Err(CalculatorError::Expression { .. })
+        ));
+        assert_eq!(session.functions()["f"].to_string(), "f(x, y) = x^2 + y");
+
+        session.execute("inv(x) = 1 / x").unwrap();
+        let error = session.execute("1 + inv(k - 3)").unwrap_err();
+        assert!(error
+            .describe()
+            .ends_with("in the function `inv`: division by zero"));
+        assert_eq!(error.expression_error().unwrap().1.span, Some(4..14));
+    }
+
+    #[test]
+    fn save_and_load() {
+        let path: PathBuf = std::env::temp_dir().join("rust-workspace-session.calc");
+        let mut session = Session::new();
+        session.execute("rate = 1200").unwrap();
+        session.execute("nodes = rate / 300").unwrap();
+        session.execute("cost(n, price) = n * price").unwrap();
+        let save = format!(":save {}", path.display());
+        assert_eq!(
+            session.execute(&save).unwrap(),
+            Outcome::Saved {
+                path: path.clone(),
+                variables: 2,
+                functions: 1
+            }
+        );
+
+        let mut session = Session::new();
+        let load = format!(":load {}", path.display());
+        assert_eq!(
+            session.execute(&load).unwrap(),
+            Outcome::Loaded {
+                path: path.clone(),
+                statements: 3
+            }
+        );
+        assert_eq!(session.variables()["nodes"], Number::Float(4.0));
+        assert_eq!(value(session.execute("cost(nodes, 2.5)").unwrap()), 10.0);
+
+        let mut session = Session::new();
+        session.execute(":mode u8").unwrap();
+        session.execute("f(x) = x ^ 1").unwrap();
+        session.execute(":mode float").unwrap();
+        session.execute("g(x) = x ^ 2").unwrap();
+        session.execute(":mode exact").unwrap();
+        session.execute(&save).unwrap();
+        let mut session = Session::new();
+        session.execute(&load).unwrap();
+        assert_eq!(session.mode(), Mode::Exact);
+        let u8 = "u8".parse::<IntegerType>().unwrap();
+        assert_eq!(session.functions()["f"].mode, Mode::Programmer(u8));
+        assert_eq!(value(session.execute("g(3)").unwrap()), 9.0);
+        session.execute(":mode u8").unwrap();
+        assert_eq!(value(session.execute("f(6)").unwrap()), 7.0);
+
+        fs::write(&path, "a = 1\n:vars\n").unwrap();
+        let error = session.execute(&load).unwrap_err();
+        fs::remove_file(&path).unwrap();
+        assert!(matches!(error, CalculatorError::Load { line: 2, .. }));
+    }
+}
@@ -0,0 +1,218 @@
+//! Statistics submodule.
+//!
+//! The statistical functions of the calculator, over lists, i.e. vectors, e.g. `mean([1, 2, 3])`,
+//! or over several numbers, e.g. `median(3, 1, 2)`; a matrix counts as the list of its elements,
+//! and so it does for `min` and `max`.
+//! The variance and the standard deviation are those of a sample, the percentiles are interpolated
+//! linearly between the closest ranks, and a histogram is a matrix with a row per bin: its lower
+//! bound, its upper bound and its count.
+//!
+//! `load("latency.csv", "p99")` is the list of the numbers of a CSV column, by the name of its
+//! header, to analyze measurements without opening a notebook; the empty cells are skipped.
+
+use std::path::Path;
+
+use super::{
+    expression::ErrorKind,
+    matrix::Matrix,
+    number::{Mode, Number},
+};
+
+/// The names of the statistical functions, with the description of their arguments.
+pub const FUNCTIONS: &[(&str, &str)] = &[
+    ("sum", "a list or numbers"),
+    ("mean", "a list or numbers"),
+    ("median", "a list or numbers"),
+    ("variance", "a list or numbers, at least 2"),
+    ("stddev", "a list or numbers, at least 2"),
+    (
+        "percentile",
+        "a list and a percent from 0 to 100, e.g. `percentile([1, 2, 3], 95)`",
+    ),
+    (
+        "histogram",
+        "a list, and optionally a number of bins from 1 to 1000",
+    ),
+];
+
+/// The function loading a CSV column.
+pub const LOAD: &str = "load";
+
+/// The arguments of `load`.
+pub const LOAD_ARGUMENTS: &str =
+    "a CSV file and a column name, e.g. `load(\"latency.csv\", \"p99\")`";
+
+/// The largest number of bins of a histogram.
+const MAX_BINS: f64 = 1000.0;
+
+/// The description of the arguments of the statistical function.
+pub fn expected(name: &str) -> Option<&'static str> {
+    FUNCTIONS
+        .iter()
+        .find(|(function, _)| *function == name)
+        .map(|(_, expected)| *expected)
+}
+
+/// Calls the statistical function; `operation` describes the call.
+pub fn call(
+    name: &str,
+    args: &[Number],
+    operation: impl Fn() -> String,
+) -> Result<Number, ErrorKind> {
+    let arguments = || ErrorKind::Arguments {
+        name: name.to_owned(),
+        expected: expected(name).unwrap_or_default().to_owned(),
+    };
+    let (list, parameter) = match (name, args) {
+        ("percentile", [list, percent]) => (list, Some(percent)),
+        ("histogram", [list, bins]) => (list, Some(bins)),
+        ("histogram", [list]) => (list, None),
+        ("percentile" | "histogram", _) => return Err(arguments()),
+        _ => {
+            let values = values(args, &operation)?;
+            return match name {
+                _ if values.is_empty() => Err(arguments()),
+                "sum" => Ok(Number::Float(values.iter().sum())),
+                "min" => Ok(Number::Float(
+                    values.iter().copied().fold(f64::INFINITY, f64::min),
+                )),
+                "max" => Ok(Number::Float(
+                    values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                )),
+                "mean" => Ok(Number::Float(mean(&values))),
+                "median" => Ok(Number::Float(percentile(values, 50.0))),
+                "variance" | "stddev" if values.len() < 2 => Err(arguments()),
+                "variance" => Ok(Number::Float(variance(&values))),
+                _ => Ok(Number::Float(variance(&values).sqrt())),
+            };
+        }
+    };
+
+    let values = values(std::slice::from_ref(list), &operation)?;
+    let parameter = match parameter.map(Number::real) {
+        Some(Some(value)) => Some(value),
+        Some(None) => return Err(arguments()),
+        None => None,
+    };
+    match (name, parameter) {
+        ("percentile", Some(percent)) if (0.0..=100.0).contains(&percent) => {
+            Ok(Number::Float(percentile(values, percent)))
+        }
+        ("percentile", _) => Err(arguments()),
+        (_, Some(bins)) if bins.fract() != 0.0 || !(1.0..=MAX_BINS).contains(&bins) => {
+            Err(arguments())
+        }
+        (_, bins) => Ok(Number::Matrix(histogram(
+            &values,
+            bins.map(|bins| bins as usize),
+        ))),
+    }
+}
+
+/// The real numbers of the arguments, with the elements of the matrices.
+fn values(args: &[Number], operation: impl Fn() -> String) -> Result<Vec<f64>, ErrorKind> {
+    let mut values = vec![];
+    for arg in args {
+        match arg {
+            Number::Matrix(matrix) => values.extend(matrix.rows().into_iter().flatten()),
+            Number::Quantity(_) => return Err(ErrorKind::Dimensionless(operation())),
+            Number::Complex(_) => return Err(ErrorKind::Complex(operation())),
+            number => values.push(number.to_f64()),
+        }
+    }
+    Ok(values)
+}
+
+fn mean(values: &[f64]) -> f64 {
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// The sample variance of at least 2 values.
+fn variance(values: &[f64]) -> f64 {
+    let mean = mean(values);
+    let squares: f64 = values.iter().map(|value| (value - mean).powi(2)).sum();
+    squares / (values.len() - 1) as f64
+}
+
+/// The percentile of the values, interpolated linearly between the closest ranks.
+fn percentile(mut values: Vec<f64>, percent: f64) -> f64 {
+    values.sort_by(f64::total_cmp);
+    let rank = percent / 100.0 * (values.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    values[lower] + (values[upper] - values[lower]) * (rank - lower as f64)
+}
+
+/// The histogram of the values, with equal bins from the smallest value to the largest one;
+/// the number of bins is given by Sturges' rule by default.
+fn histogram(values: &[f64], bins: Option<usize>) -> Matrix {
+    let (min, max) = values
+        .iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(*value), max.max(*value))
+        });
+    let bins = match (bins, min == max) {
+        (_, true) => 1,
+        (Some(bins), false) => bins,
+        (None, false) => (values.len() as f64).log2().ceil() as usize + 1,
+    };
+    let width = (max - min) / bins as f64;
+    let mut counts = vec![0usize; bins];
+    for value in values {
+        // The largest value is in the last bin, whose upper bound is included.
+        let bin = match width > 0.0 {
+            true => (((value - min) / width) as usize).min(bins - 1),
+            false => 0,
+        };
+        counts[bin] += 1;
+    }
+    let rows: Vec<Vec<f64>> = counts
+        .iter()
+        .enumerate()
+        .map(|(bin, count)| {
+            let lower = min + width * bin as f64;
+            let upper = match bin + 1 == bins {
+                true => max,
+                false => min + width * (bin + 1) as f64,
+            };
+            vec![lower, upper, *count as f64]
+        })
+        .collect();
+    Matrix::new(&rows)
+}
+
+/// The numbers of the column of the CSV file, by the name of its header, as a row vector.
+pub fn load(path: &str, column: &str) -> Result<Number, ErrorKind> {
+    let failed = |message: String| ErrorKind::Load(format!("{:?}: {}", path, message));
+    let mut reader =
+        csv::Reader::from_path(Path::new(path)).map_err(|error| failed(error.to_string()))?;
+    let headers = reader
+        .headers()
+        .map_err(|error| failed(error.to_string()))?
+        .clone();
+    let Some(index) = headers.iter().position(|header| header.trim() == column) else {
+        return Err(failed(format!("no column {:?}", column)));
+    };
+
+    let mut values = vec![];
+    for record in reader.records() {
+        let record = record.map_err(|error| failed(error.to_string()))?;
+        let line = record.position().map_or(0, |position| position.line());
+        let cell = record.get(index).unwrap_or_default().trim();
+        if cell.is_empty() {
+            continue;
+        }
+        let value = Number::literal(cell, Mode::Float)
+            .ok()
+            .map(|value| value.to_f64())
+            .filter(|value| value.is_finite())
+            .ok_or_else(|| failed(format!("{:?} on the line {} is not a number", cell, line)))?;
+        values.push(value);
+    }
+    match values.is_empty() {
+        true => Err(failed(format!("the column {:?} has no numbers", column))),
+        false => Ok(Number::Matrix(Matrix::new(&[values]))),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+mod statistics {
+    use std::{fs, path::PathBuf};
+
+    use crate::calculator::{
+        expression::{evaluate, ErrorKind},
+        number::Mode,
+        session::Session,
+    };
+
+    /// Evaluates the expression in the session, and formats the result with 4 decimal places.
+    fn eval(session: &Session, expression: &str) -> Result<String, ErrorKind> {
+        evaluate(expression, session)
+            .map(|value| value.format(Mode::Decimal(4)))
+            .map_err(|error| error.kind)
+    }
+
+    #[test]
+    fn functions() {
+        let mut session = Session::new();
+        session.execute("l = [2, 4, 4, 4, 5, 5, 7, 9]").unwrap();
+        assert_eq!(eval(&session, "sum(l)").unwrap(), "40");
+        assert_eq!(eval(&session, "mean(l)").unwrap(), "5");
+        assert_eq!(eval(&session, "median(l)").unwrap(), "4.5");
+        assert_eq!(eval(&session, "median(3, 1, 2)").unwrap(), "2");
+        assert_eq!(eval(&session, "variance(l)").unwrap(), "4.5714");
+        assert_eq!(eval(&session, "stddev([1; 2; 3; 4])").unwrap(), "1.291");
+        assert_eq!(eval(&session, "percentile(l, 95)").unwrap(), "8.3");
+        assert_eq!(eval(&session, "max(l) - min(l, 0)").unwrap(), "9");
+        assert_eq!(
+            eval(&session, "histogram(l, 3)").unwrap(),
+            "[2, 4.3333, 4; 4.3333, 6.6667, 2; 6.6667, 9, 2]"
+        );
+        assert_eq!(eval(&session, "histogram([1, 1])").unwrap(), "[1, 1, 2]");
+
+        let arguments = |name: &str, expected: &str| {
+            Err(ErrorKind::Arguments {
+                name: name.to_owned(),
+                expected: expected.to_owned(),
+            })
+        };
+        assert_eq!(
+            eval(&session, "stddev(1)"),
+            arguments("stddev", "a list or numbers, at least 2")
+        );
+        assert_eq!(
+            eval(&session, "percentile(l, 101)"),
+            arguments(
+                "percentile",
+                "a list and a percent from 0 to 100, e.g. `percentile([1, 2, 3], 95)`"
+            )
+        );
+        assert_eq!(
+            eval(&session, "mean(l) + \"p99\""),
+            Err(ErrorKind::Text(String::from("p99")))
+        );
+    }
+
+    #[test]
+    fn load() {
+        let path: PathBuf = std::env::temp_dir().join("rust-workspace-latency.csv");
+        fs::write(&path, "endpoint,p50,p99\n/a,12,250\n/b,8,\n/c,10,170.5\n").unwrap();
+        let session = Session::new();
+        let load = |column: &str| {
+            let expression = format!("load({:?}, {:?})", path.display().to_string(), column);
+            eval(&session, &expression)
+        };
+        assert_eq!(load("p99").unwrap(), "[250, 170.5]");
+        assert!(matches!(load("p95"), Err(ErrorKind::Load(_))));
+        assert!(matches!(load("endpoint"), Err(ErrorKind::Load(_))));
+        let Err(ErrorKind::Load(message)) = load("p95") else {
+            panic!("the column p95 is missing");
+        };
+        assert!(!message.contains("endpoint"));
+        let expression = format!("load({:?}, \"p99\")", path.display().to_string());
+        assert_eq!(
+            eval(&Session::new().sandbox(), &expression),
+            Err(ErrorKind::Sandboxed(String::from("load")))
+        );
+        let mean = format!("mean(load({:?}, \"p50\"))", path.display().to_string());
+        assert_eq!(eval(&session, &mean).unwrap(), "10");
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,354 @@
+//! Unit submodule.
+//!
+//! The built-in units of length, mass, time, temperature, data sizes and data rates, and the quantities:
+//! a value in the base units, `kg`, `m`, `s`, `K` and `B`, with the exponents of its dimension,
+//! e.g. `m/s^2` for an acceleration. The quantities of different dimensions can be multiplied and
+//! divided, but not added, so that `3 kg + 2 m` is an error.
+//!
+//! The temperatures `C` and `F` have an offset: they are absolute temperatures, and must follow
+//! a number, e.g. `20 C`, or be the target of a conversion, e.g. `in F`.
+
+use std::fmt;
+
+use super::expression::ErrorKind;
+
+/// The base units, in the order of the dimension exponents.
+const BASE_UNITS: [&str; 5] = ["kg", "m", "s", "K", "B"];
+
+/// The exponents of the base units of a quantity.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Dimension([i8; 5]);
+
+impl Dimension {
+    /// The dimension of the plain numbers.
+    pub const NONE: Dimension = Dimension([0; 5]);
+    const MASS: Dimension = Dimension([1, 0, 0, 0, 0]);
+    const LENGTH: Dimension = Dimension([0, 1, 0, 0, 0]);
+    const TIME: Dimension = Dimension([0, 0, 1, 0, 0]);
+    const TEMPERATURE: Dimension = Dimension([0, 0, 0, 1, 0]);
+    const DATA: Dimension = Dimension([0, 0, 0, 0, 1]);
+    const DATA_RATE: Dimension = Dimension([0, 0, -1, 0, 1]);
+
+    pub fn is_none(&self) -> bool {
+        *self == Dimension::NONE
+    }
+
+    /// The dimension of the product, `None` if an exponent overflows.
+    fn multiply(&self, other: &Dimension, sign: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for (exponent, other) in exponents.iter_mut().zip(other.0) {
+            *exponent = exponent.checked_add(other.checked_mul(sign)?)?;
+        }
+        Some(Dimension(exponents))
+    }
+
+    /// The dimension of the power, `None` if an exponent overflows.
+    fn pow(&self, power: i8) -> Option<Dimension> {
+        let mut exponents = self.0;
+        for exponent in exponents.iter_mut() {
+            *exponent = exponent.checked_mul(power)?;
+        }
+        Some(Dimension(exponents))
+    }
+}
+
+impl fmt::Display for Dimension {
+    /// The base units, e.g. `kg*m/s^2`, or `s^-1` without positive exponent.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let unit = |name: &str, exponent: i8| match exponent {
+            1 => name.to_owned(),
+            exponent => format!("{}^{}", name, exponent),
+        };
+        let units = BASE_UNITS.iter().zip(self.0);
+        let numerator: Vec<String> = units
+            .clone()
+            .filter(|(_, exponent)| *exponent > 0)
+            .map(|(name, exponent)| unit(name, exponent))
+            .collect();
+        let denominator: Vec<(&&str, i8)> = units.filter(|(_, exponent)| *exponent < 0).collect();
+
+        if numerator.is_empty() {
+            let units: Vec<String> = denominator
+                .iter()
+                .map(|(name, exponent)| unit(name, *exponent))
+                .collect();
+            return match units.is_empty() {
+                true => write!(f, "no unit"),
+                false => write!(f, "{}", units.join("*")),
+            };
+        }
+        write!(f, "{}", numerator.join("*"))?;
+        for (name, exponent) in denominator {
+            write!(f, "/{}", unit(name, -exponent))?;
+        }
+        Ok(())
+    }
+}
+
+/// A built-in unit: a value in the unit is `(value + offset) * factor` in the base units.
+#[derive(Debug, PartialEq)]
+pub struct Unit {
+    pub name: &'static str,
+    pub factor: f64,
+    pub offset: f64,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    /// Whether it's a temperature with an offset, `C` or `F`.
+    pub fn is_affine(&self) -> bool {
+        self.offset != 0.0
+    }
+
+    /// The label to print the quantities in the unit.
+    pub fn label(&self) -> Label {
+        Label {
+            name: self.name.to_owned(),
+            factor: self.factor,
+            offset: self.offset,
+        }
+    }
+}
+
+/// The unit without offset.
+const fn unit(name: &'static str, factor: f64, dimension: Dimension) -> Unit {
+    Unit {
+        name,
+        factor,
+        offset: 0.0,
+        dimension,
+    }
+}
+
+/// The built-in units.
+pub const UNITS: &[Unit] = &[
+    unit("m", 1.0, Dimension::LENGTH),
+    unit("km", 1e3, Dimension::LENGTH),
+    unit("cm", 1e-2, Dimension::LENGTH),
+    unit("mm", 1e-3, Dimension::LENGTH),
+    unit("um", 1e-6, Dimension::LENGTH),
+    unit("nm", 1e-9, Dimension::LENGTH),
+    unit("mi", 1609.344, Dimension::LENGTH),
+    unit("yd", 0.9144, Dimension::LENGTH),
+    unit("ft", 0.3048, Dimension::LENGTH),
+    unit("inch", 0.0254, Dimension::LENGTH),
+    unit("kg", 1.0, Dimension::MASS),
+    unit("g", 1e-3, Dimension::MASS),
+    unit("mg", 1e-6, Dimension::MASS),
+    unit("t", 1e3, Dimension::MASS),
+    unit("lb", 0.45359237, Dimension::MASS),
+    unit("oz", 0.028349523125, Dimension::MASS),
+    unit("s", 1.0, Dimension::TIME),
+    unit("ms", 1e-3, Dimension::TIME),
+    unit("us", 1e-6, Dimension::TIME),
+    unit("ns", 1e-9, Dimension::TIME),
+    unit("min", 60.0, Dimension::TIME),
+    unit("h", 3600.0, Dimension::TIME),
+    unit("day", 86400.0, Dimension::TIME),
+    unit("week", 604800.0, Dimension::TIME),
+    unit("K", 1.0, Dimension::TEMPERATURE),
+    Unit {
+        name: "C",
+        factor: 1.0,
+        offset: 273.15,
+        dimension: Dimension::TEMPERATURE,
+    },
+    Unit {
+        name: "F",
+        factor: 5.0 / 9.0,
+        offset: 459.67,
+        dimension: Dimension::TEMPERATURE,
+    },
+    unit("B", 1.0, Dimension::DATA),
+    unit("kB", 1e3, Dimension::DATA),
+    unit("MB", 1e6, Dimension::DATA),
+    unit("GB", 1e9, Dimension::DATA),
+    unit("TB", 1e12, Dimension::DATA),
+    unit("PB", 1e15, Dimension::DATA),
+    unit("KiB", 1024.0, Dimension::DATA),
+    unit("MiB", 1048576.0, Dimension::DATA),
+    unit("GiB", 1073741824.0, Dimension::DATA),
+    unit("TiB", 1099511627776.0, Dimension::DATA),
+    unit("PiB", 1125899906842624.0, Dimension::DATA),
+    unit("bit", 0.125, Dimension::DATA),
+    unit("kbit", 125.0, Dimension::DATA),
+    unit("Mbit", 125e3, Dimension::DATA),
+    unit("Gbit", 125e6, Dimension::DATA),
+    unit("Tbit", 125e9, Dimension::DATA),
+    unit("bps", 0.125, Dimension::DATA_RATE),
+    unit("kbps", 125.0, Dimension::DATA_RATE),
+    unit("Mbps", 125e3, Dimension::DATA_RATE),
+    unit("Gbps", 125e6, Dimension::DATA_RATE),
+];
+
+/// The built-in unit.
+pub fn find(name: &str) -> Option<&'static Unit> {
+    UNITS.iter().find(|unit| unit.name == name)
+}
+
+/// The unit a quantity is printed in, the target of a conversion, e.g. `mi` or `Mbit/s`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Label {
+    pub name: String,
+    pub factor: f64,
+    pub offset: f64,
+}
+
+/// A value with a dimension.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantity {
+    /// The value in the base units.
+    value: f64,
+    dimension: Dimension,
+    /// The unit to print the value in, the base units otherwise.
+    label: Option<Label>,
+}
+
+impl Quantity {
+    /// The quantity of the value in the base units.
+    pub fn new(value: f64, dimension: Dimension) -> Quantity {
+        Quantity {
+            value,
+            dimension,
+            label: None,
+        }
+    }
+
+    /// The value in the unit, e.g. `20 C`.
+    pub fn of(value: f64, unit: &Unit) -> Quantity {
+        Quantity::new((value + unit.offset) * unit.factor, unit.dimension)
+    }
+
+    /// The value in the base units.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        self.dimension
+    }
+
+    /// The value in the unit it's printed in. A fractional value is rounded to 15 significant digits
+    /// before the offset is subtracted, to hide the rounding errors of the conversions, e.g. `20 C in F`
+    /// is `68` rather than `67.99999999999994`.
+    pub fn value_in_label(&self) -> f64 {
+        let (scaled, offset) = match &self.label {
+            Some(label) => (self.value / label.factor, label.offset),
+            None => (self.value, 0.0),
+        };
+        if scaled.fract() == 0.0 || !scaled.is_finite() {
+            return scaled - offset;
+        }
+        let decimals = 14 - scaled.abs().log10().floor() as i32;
+        let rounded = match usize::try_from(decimals) {
+            Ok(decimals) if decimals <= 20 => format!("{:.*}", decimals, scaled - offset),
+            _ => format!("{:.14e}", scaled - offset),
+        };
+        rounded.parse().unwrap_or(scaled - offset)
+    }
+
+    /// Whether it's printed in the unit of a conversion.
+    pub fn is_labeled(&self) -> bool {
+        self.label.is_some()
+    }
+
+    /// The name of the unit it's printed in.
+    pub fn unit(&self) -> String {
+        match &self.label {
+            Some(label) => label.name.clone(),
+            None => self.dimension.to_string(),
+        }
+    }
+
+    /// The quantity printed in the unit of the dimension; `operation` describes the conversion.
+    pub fn convert(
+        &self,
+        label: Label,
+        dimension: Dimension,
+        operation: impl Fn() -> String,
+    ) -> Result<Quantity, ErrorKind> {
+        self.check(dimension, operation)?;
+        Ok(Quantity {
+            label: Some(label),
+            ..self.clone()
+        })
+    }
+
+    /// The quantity multiplied by the number, still printed in its unit.
+    pub fn scale(&self, factor: f64) -> Quantity {
+        Quantity {
+            value: self.value * factor,
+            ..self.clone()
+        }
+    }
+
+    /// The sum or the difference of quantities of the same dimension; `sign` is `-1` for the difference.
+    pub fn add(
+        &self,
+        other: &Quantity,
+        sign: f64,
+        operation: impl Fn() -> String,
+    ) -> Result<Quantity, ErrorKind> {
+        self.check(other.dimension, operation)?;
+        Ok(Quantity::new(
+            self.value + sign * other.value,
+            self.dimension,
+        ))
+    }
+
+    /// The remainder of quantities of the same dimension.
+    pub fn remainder(
+        &self,
+        other: &Quantity,
+        operation: impl Fn() -> String,
+    ) -> Result<Quantity, ErrorKind> {
+        self.check(other.dimension, operation)?;
+        Ok(Quantity::new(self.value % other.value, self.dimension))
+    }
+
+    /// The product, or the quotient if `sign` is `-1`.
+    pub fn multiply(
+        &self,
+        other: &Quantity,
+        sign: i8,
+        operation: impl Fn() -> String,
+    ) -> Result<Quantity, ErrorKind> {
+        let dimension = self
+            .dimension
+            .multiply(&other.dimension, sign)
+            .ok_or_else(|| ErrorKind::Dimensionless(operation()))?;
+        let value = match sign {
+            1 => self.value * other.value,
+            _ => self.value / other.value,
+        };
+        Ok(Quantity::new(value, dimension))
+    }
+
+    /// The power with an integer exponent.
+    pub fn pow(
+        &self,
+        exponent: f64,
+        operation: impl Fn() -> String,
+    ) -> Result<Quantity, ErrorKind> {
+        let dimension = Some(exponent)
+            .filter(|exponent| exponent.fract() == 0.0 && exponent.abs() <= i8::MAX as f64)
+            .and_then(|exponent| self.dimension.pow(exponent as i8))
+            .ok_or_else(|| ErrorKind::Dimensionless(operation()))?;
+        Ok(Quantity::new(self.value.powf(exponent), dimension))
+    }
+
+    /// Checks that the quantity has the dimension.
+    pub fn check(
+        &self,
+        dimension: Dimension,
+        operation: impl Fn() -> String,
+    ) -> Result<(), ErrorKind> {
+        match self.dimension == dimension {
+            true => Ok(()),
+            false => Err(ErrorKind::Units(operation(), self.dimension, dimension)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
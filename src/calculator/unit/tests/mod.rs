@@ -0,0 +1,67 @@
+mod unit {
+    use crate::calculator::{
+        expression::{evaluate, ErrorKind},
+        number::Mode,
+        session::Session,
+        unit::{find, Quantity},
+    };
+
+    /// Evaluates the expression in a new session, and formats the result.
+    fn eval(expression: &str) -> Result<String, ErrorKind> {
+        evaluate(expression, &Session::new())
+            .map(|value| value.format(Mode::Float))
+            .map_err(|error| error.kind)
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(eval("3 km + 200 m in mi").unwrap(), "1.98838781515947 mi");
+        assert_eq!(eval("3 km + 200 m").unwrap(), "3200 m");
+        assert_eq!(
+            eval("512 MiB / 4 s in Mbit/s").unwrap(),
+            "1073.741824 Mbit/s"
+        );
+        assert_eq!(eval("100 Mbps * 1 h in GB").unwrap(), "45 GB");
+        assert_eq!(eval("1 GiB / (10 MB/s)").unwrap(), "107.3741824 s");
+        assert_eq!(eval("20 C in F").unwrap(), "68 F");
+        assert_eq!(eval("-40 F in C").unwrap(), "-40 C");
+        assert_eq!(eval("0 K in C").unwrap(), "-273.15 C");
+        assert_eq!(eval("2 m * 3 m").unwrap(), "6 m^2");
+        assert_eq!(eval("6 km / 2 m").unwrap(), "3000");
+        assert_eq!(eval("max(1 ft, 1 inch) in inch").unwrap(), "12 inch");
+        assert_eq!(Quantity::of(1.0, find("t").unwrap()).value(), 1000.0);
+        assert_eq!(find("lightyear"), None);
+    }
+
+    #[test]
+    fn dimensional_analysis() {
+        assert_eq!(
+            eval("3 kg + 2 m"),
+            Err(ErrorKind::Units(
+                String::from("3 kg + 2 m"),
+                find("kg").unwrap().dimension,
+                find("m").unwrap().dimension
+            ))
+        );
+        assert!(matches!(eval("3 in km"), Err(ErrorKind::Units(..))));
+        assert!(matches!(eval("1 h in m"), Err(ErrorKind::Units(..))));
+        assert_eq!(
+            eval("5 km in 2"),
+            Err(ErrorKind::NotAUnit(String::from("2")))
+        );
+        assert!(matches!(
+            eval("sqrt(4 m)"),
+            Err(ErrorKind::Dimensionless(_))
+        ));
+        assert!(matches!(
+            eval("2 ^ (1 s)"),
+            Err(ErrorKind::Dimensionless(_))
+        ));
+        assert_eq!(eval("C + 1"), Err(ErrorKind::AffineUnit(String::from("C"))));
+
+        let mut session = Session::new();
+        session.execute("m = 5").unwrap();
+        assert_eq!(session.evaluate("3 m").unwrap().to_f64(), 15.0);
+        assert!(session.execute("in = 1").is_err());
+    }
+}
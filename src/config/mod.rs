@@ -0,0 +1,519 @@
+//! Configuration module.
+//!
+//! The configuration is resolved once, in the following order, each layer overriding the previous one:
+//! - built-in defaults;
+//! - the configuration file, `rust-workspace.toml` in the current directory or the `--config` argument;
+//! - environment variables, e.g. `RUST_WORKSPACE_DATA_PIPELINE__REPOS_PER_PAGE=10`;
+//! - command line overrides, e.g. `--set data_pipeline.repos_per_page=10`.
+//!
+//! Program arguments, e.g. `--max-iterations`, override the configuration values.
+
+use clap::Args;
+use colored::Colorize;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{
+    collections::BTreeMap,
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+use thiserror::Error;
+
+use crate::output;
+
+/// The exit code used when the configuration is invalid.
+pub const EXIT_CODE: i32 = 8;
+
+/// The default configuration file, relative to the current directory.
+pub const CONFIG_FILE: &str = "rust-workspace.toml";
+
+/// The prefix of the configuration environment variables.
+pub const ENV_PREFIX: &str = "RUST_WORKSPACE_";
+
+/// The configuration of the programs.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct Config {
+    pub guessing_game: GuessingGameConfig,
+    pub weather: WeatherConfig,
+    pub calculator: CalculatorConfig,
+    pub linfa_train: LinfaTrainConfig,
+    pub data_pipeline: DataPipelineConfig,
+    pub server: ServerConfig,
+}
+
+/// The guessing game configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GuessingGameConfig {
+    /// The minimum secret number.
+    pub min: i32,
+    /// The maximum secret number.
+    pub max: i32,
+    /// The number of guesses before the game is lost.
+    pub attempts: u32,
+    /// The finished games, as JSON lines.
+    pub scores_file: PathBuf,
+}
+
+impl Default for GuessingGameConfig {
+    fn default() -> Self {
+        GuessingGameConfig {
+            min: 1,
+            max: 100,
+            attempts: 7,
+            scores_file: PathBuf::from(".data/guessing_game_scores.jsonl"),
+        }
+    }
+}
+
+/// The weather program configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct WeatherConfig {
+    /// The OpenWeather current weather API endpoint.
+    pub api_url: String,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        WeatherConfig {
+            api_url: String::from("http://api.openweathermap.org/data/2.5/weather"),
+        }
+    }
+}
+
+/// The calculator configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CalculatorConfig {
+    /// The history of the calculator input lines.
+    pub history_file: PathBuf,
+}
+
+impl Default for CalculatorConfig {
+    fn default() -> Self {
+        CalculatorConfig {
+            history_file: PathBuf::from(".data/calculator_history"),
+        }
+    }
+}
+
+/// The model trainers configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct LinfaTrainConfig {
+    /// The CSV dataset, where the last column contains the targets.
+    pub dataset_path: PathBuf,
+    /// The serialized logistic regression model.
+    pub logistic_regression_model_path: PathBuf,
+    /// The serialized decision tree model.
+    pub decision_tree_model_path: PathBuf,
+    /// The directory of the generated plots.
+    pub plots_path: PathBuf,
+    /// The maximum number of iterations of the logistic regression solver.
+    pub max_iterations: u64,
+    /// The maximum depth of the decision tree.
+    pub max_depth: usize,
+}
+
+impl Default for LinfaTrainConfig {
+    fn default() -> Self {
+        LinfaTrainConfig {
+            dataset_path: PathBuf::from(".data/input/diabetes.csv"),
+            logistic_regression_model_path: PathBuf::from(
+                ".data/output/diabetes_model.logistic_regression.cbor",
+            ),
+            decision_tree_model_path: PathBuf::from(
+                ".data/output/diabetes_model.decision_tree.cbor",
+            ),
+            plots_path: PathBuf::from(".data/plots"),
+            max_iterations: 500,
+            max_depth: 500,
+        }
+    }
+}
+
+/// The data pipeline configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct DataPipelineConfig {
+    /// The env file with the secrets, relative to the current directory.
+    pub env_file: PathBuf,
+    /// The directory of the collected GitHub data.
+    pub github_output_path: PathBuf,
+    /// The directory of the encrypted artifacts, relative to the current directory.
+    pub artifact_path: PathBuf,
+    /// The number of repositories per page of the repository search.
+    pub repos_per_page: i64,
+    /// The number of workflow runs fetched per repository.
+    pub workflow_runs_per_page: i64,
+}
+
+impl Default for DataPipelineConfig {
+    fn default() -> Self {
+        DataPipelineConfig {
+            env_file: PathBuf::from(".env"),
+            github_output_path: PathBuf::from("./.data/output/github"),
+            artifact_path: PathBuf::from("./.data/artifact/github"),
+            repos_per_page: 5,
+            workflow_runs_per_page: 100,
+        }
+    }
+}
+
+/// The HTTP server configuration.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ServerConfig {
+    /// The socket address the server listens on.
+    pub address: String,
+}
+
+impl Default for ServerConfig {
+    fn default() -> Self {
+        ServerConfig {
+            address: String::from("127.0.0.1:8080"),
+        }
+    }
+}
+
+/// The layer a configuration value comes from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "layer", content = "name", rename_all = "snake_case")]
+pub enum Source {
+    Default,
+    File(PathBuf),
+    Env(String),
+    Cli,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::File(path) => write!(f, "file {}", path.display()),
+            Source::Env(name) => write!(f, "env {}", name),
+            Source::Cli => write!(f, "--set"),
+        }
+    }
+}
+
+/// Configuration resolution failure.
+#[derive(Debug, Error)]
+pub enum ConfigError {
+    #[error("can't read the configuration file {path:?}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("can't parse the configuration file {path:?}")]
+    Parse {
+        path: PathBuf,
+        #[source]
+        source: toml::de::Error,
+    },
+    #[error("unknown configuration key {key:?} ({origin})")]
+    UnknownKey { key: String, origin: Source },
+    #[error("invalid value of the configuration key {key:?} ({origin}): {message}")]
+    InvalidValue {
+        key: String,
+        origin: Source,
+        message: String,
+    },
+    #[error("invalid override {0:?}, expected KEY=VALUE")]
+    InvalidOverride(String),
+}
+
+/// The resolved configuration with the source of each value.
+#[derive(Debug)]
+pub struct Resolved {
+    pub config: Config,
+    /// The sources of the values by dotted key, e.g. `data_pipeline.repos_per_page`.
+    pub sources: BTreeMap<String, Source>,
+}
+
+/// The resolved configuration.
+static RESOLVED: OnceLock<Resolved> = OnceLock::new();
+
+/// Arguments of the configuration layer.
+#[derive(Args, Debug, Default)]
+pub struct ConfigArguments {
+    /// The configuration file; `rust-workspace.toml` in the current directory is used if it exists.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub config: Option<PathBuf>,
+    /// Overrides a configuration value, e.g. `--set guessing_game.max=1000`; can be repeated.
+    #[arg(long = "set", global = true, value_name = "KEY=VALUE")]
+    pub overrides: Vec<String>,
+}
+
+/// Resolves the configuration from the process environment and stores it for `get`.
+pub fn init(args: &ConfigArguments) -> Result<(), ConfigError> {
+    let file = match &args.config {
+        Some(path) => Some(path.clone()),
+        None => Some(PathBuf::from(CONFIG_FILE)).filter(|path| path.exists()),
+    };
+    let env_vars: BTreeMap<String, String> = env::vars()
+        .filter(|(name, _)| name.starts_with(ENV_PREFIX))
+        .collect();
+    let resolved = resolve(file.as_deref(), &env_vars, &args.overrides)?;
+    let _ = RESOLVED.set(resolved);
+    Ok(())
+}
+
+/// The configuration; the built-in defaults if the configuration was not initialized.
+pub fn get() -> &'static Config {
+    &resolved().config
+}
+
+/// The resolved configuration; the built-in defaults if the configuration was not initialized.
+pub fn resolved() -> &'static Resolved {
+    RESOLVED.get_or_init(|| {
+        resolve(None, &BTreeMap::new(), &[]).expect("The default configuration is valid")
+    })
+}
+
+/// Resolves the configuration layers.
+pub fn resolve(
+    file: Option<&Path>,
+    env_vars: &BTreeMap<String, String>,
+    overrides: &[String],
+) -> Result<Resolved, ConfigError> {
+    let defaults = flatten(&serde_json::to_value(Config::default()).unwrap_or_default());
+    let mut values = defaults.clone();
+    let mut sources: BTreeMap<String, Source> = defaults
+        .keys()
+        .map(|key| (key.clone(), Source::Default))
+        .collect();
+
+    if let Some(path) = file {
+        let content = fs::read_to_string(path).map_err(|source| ConfigError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+        let table =
+            toml::from_str::<toml::Table>(&content).map_err(|source| ConfigError::Parse {
+                path: path.to_owned(),
+                source,
+            })?;
+        let file_values = flatten(&serde_json::to_value(table).unwrap_or_default());
+        for (key, value) in file_values {
+            let source = Source::File(path.to_owned());
+            set(&defaults, &mut values, &mut sources, &key, value, source)?;
+        }
+    }
+
+    for key in defaults.keys() {
+        let name = env_var_name(key);
+        if let Some(raw) = env_vars.get(&name) {
+            let value = parse_value(raw, &defaults[key]);
+            set(
+                &defaults,
+                &mut values,
+                &mut sources,
+                key,
+                value,
+                Source::Env(name),
+            )?;
+        }
+    }
+
+    for item in overrides {
+        let (key, raw) = item
+            .split_once('=')
+            .ok_or_else(|| ConfigError::InvalidOverride(item.clone()))?;
+        let key = key.trim();
+        let default = defaults.get(key).ok_or_else(|| ConfigError::UnknownKey {
+            key: key.to_owned(),
+            origin: Source::Cli,
+        })?;
+        let value = parse_value(raw.trim(), default);
+        set(
+            &defaults,
+            &mut values,
+            &mut sources,
+            key,
+            value,
+            Source::Cli,
+        )?;
+    }
+
+    let config = deserialize(&values).map_err(|message| {
+        invalid_key(&defaults, &values, &sources).unwrap_or(ConfigError::InvalidValue {
+            key: String::new(),
+            origin: Source::Default,
+            message,
+        })
+    })?;
+
+    if config.guessing_game.min > config.guessing_game.max {
+        let key = "guessing_game.max";
+        return Err(ConfigError::InvalidValue {
+            key: key.to_owned(),
+            origin: sources[key].clone(),
+            message: String::from("the maximum is less than the minimum"),
+        });
+    }
+    if config.guessing_game.attempts == 0 {
+        let key = "guessing_game.attempts";
+        return Err(ConfigError::InvalidValue {
+            key: key.to_owned(),
+            origin: sources[key].clone(),
+            message: String::from("at least 1 attempt is required"),
+        });
+    }
+
+    Ok(Resolved { config, sources })
+}
+
+/// Deserializes the configuration from the values by dotted key.
+fn deserialize(values: &BTreeMap<String, Value>) -> Result<Config, String> {
+    serde_json::from_value::<Config>(unflatten(values)).map_err(|error| error.to_string())
+}
+
+/// Finds the overridden value that can't be deserialized, e.g. a negative number of pages.
+fn invalid_key(
+    defaults: &BTreeMap<String, Value>,
+    values: &BTreeMap<String, Value>,
+    sources: &BTreeMap<String, Source>,
+) -> Option<ConfigError> {
+    sources
+        .iter()
+        .filter(|(_, source)| **source != Source::Default)
+        .find_map(|(key, source)| {
+            let mut candidate = defaults.clone();
+            candidate.insert(key.clone(), values[key].clone());
+            deserialize(&candidate)
+                .err()
+                .map(|message| ConfigError::InvalidValue {
+                    key: key.clone(),
+                    origin: source.clone(),
+                    message,
+                })
+        })
+}
+
+/// The environment variable of the dotted key, e.g. `RUST_WORKSPACE_WEATHER__API_URL`.
+pub fn env_var_name(key: &str) -> String {
+    ENV_PREFIX.to_owned() + &key.replace('.', "__").to_uppercase()
+}
+
+/// Sets the value of a known key, if the value has the type of the default value.
+fn set(
+    defaults: &BTreeMap<String, Value>,
+    values: &mut BTreeMap<String, Value>,
+    sources: &mut BTreeMap<String, Source>,
+    key: &str,
+    value: Value,
+    source: Source,
+) -> Result<(), ConfigError> {
+    let Some(default) = defaults.get(key) else {
+        return Err(ConfigError::UnknownKey {
+            key: key.to_owned(),
+            origin: source,
+        });
+    };
+
+    let valid = match default {
+        Value::String(_) => value.is_string(),
+        Value::Number(number) if number.is_f64() => value.is_number(),
+        Value::Number(_) => value.is_i64() || value.is_u64(),
+        Value::Bool(_) => value.is_boolean(),
+        _ => true,
+    };
+    if !valid {
+        return Err(ConfigError::InvalidValue {
+            key: key.to_owned(),
+            origin: source,
+            message: format!("expected a value like {}, got {}", default, value),
+        });
+    }
+
+    values.insert(key.to_owned(), value);
+    sources.insert(key.to_owned(), source);
+    Ok(())
+}
+
+/// Parses a raw string value with the type of the default value.
+fn parse_value(raw: &str, default: &Value) -> Value {
+    match default {
+        Value::String(_) => Value::String(raw.to_owned()),
+        _ => serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.to_owned())),
+    }
+}
+
+/// Flattens the nested object to dotted keys.
+fn flatten(value: &Value) -> BTreeMap<String, Value> {
+    let mut result = BTreeMap::new();
+    flatten_into(&mut result, "", value);
+    result
+}
+
+/// Flattens the nested object to dotted keys with the prefix.
+fn flatten_into(result: &mut BTreeMap<String, Value>, prefix: &str, value: &Value) {
+    match value {
+        Value::Object(map) => {
+            for (key, value) in map {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    prefix.to_owned() + "." + key
+                };
+                flatten_into(result, &key, value);
+            }
+        }
+        _ => {
+            result.insert(prefix.to_owned(), value.clone());
+        }
+    }
+}
+
+/// Restores the nested object from dotted keys.
+fn unflatten(values: &BTreeMap<String, Value>) -> Value {
+    let mut root = Map::new();
+    for (key, value) in values {
+        let mut map = &mut root;
+        let mut parts = key.split('.').peekable();
+        while let Some(part) = parts.next() {
+            if parts.peek().is_none() {
+                map.insert(part.to_owned(), value.clone());
+            } else {
+                map = map
+                    .entry(part.to_owned())
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .expect("The configuration keys are not nested in values");
+            }
+        }
+    }
+    Value::Object(root)
+}
+
+/// Arguments of the `config show` command.
+#[derive(Args, Default)]
+pub struct ShowArguments {}
+
+/// The `config.value` output record.
+#[derive(Serialize)]
+struct ValueRecord<'a> {
+    key: &'a str,
+    value: &'a Value,
+    source: &'a Source,
+}
+
+/// Prints the effective configuration and the source of each value.
+pub fn show(_args: ShowArguments) {
+    let resolved = resolved();
+    let values = flatten(&serde_json::to_value(&resolved.config).unwrap_or_default());
+
+    human_println!("\n{}", "Effective configuration:".green().bold());
+
+    for (key, value) in &values {
+        let source = resolved.sources.get(key).unwrap_or(&Source::Default);
+        human_println!(
+            "{} = {} {}",
+            key.cyan(),
+            value,
+            format!("({})", source).dimmed()
+        );
+        output::emit("config.value", &ValueRecord { key, value, source });
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,81 @@
+mod config {
+    use std::{collections::BTreeMap, fs};
+
+    use crate::config::{env_var_name, resolve, ConfigError, Source};
+
+    #[test]
+    fn defaults() {
+        let resolved = resolve(None, &BTreeMap::new(), &[]).unwrap();
+        assert_eq!(resolved.config.data_pipeline.repos_per_page, 5);
+        assert_eq!(resolved.config.guessing_game.max, 100);
+        assert!(resolved
+            .sources
+            .values()
+            .all(|source| *source == Source::Default));
+    }
+
+    #[test]
+    fn layers() {
+        let path = std::env::temp_dir().join("rust-workspace-config-layers.toml");
+        fs::write(
+            &path,
+            "[data_pipeline]\nrepos_per_page = 10\nworkflow_runs_per_page = 20\n[guessing_game]\nmax = 50\n",
+        )
+        .unwrap();
+        let env_vars = BTreeMap::from([
+            (
+                env_var_name("data_pipeline.workflow_runs_per_page"),
+                String::from("30"),
+            ),
+            (env_var_name("guessing_game.max"), String::from("60")),
+        ]);
+        let overrides = [String::from("guessing_game.max=70")];
+
+        let resolved = resolve(Some(&path), &env_vars, &overrides).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let config = resolved.config;
+        assert_eq!(config.data_pipeline.repos_per_page, 10);
+        assert_eq!(config.data_pipeline.workflow_runs_per_page, 30);
+        assert_eq!(config.guessing_game.max, 70);
+        assert_eq!(config.guessing_game.min, 1);
+
+        let sources = resolved.sources;
+        assert_eq!(
+            sources["data_pipeline.repos_per_page"],
+            Source::File(path.clone())
+        );
+        assert_eq!(
+            sources["data_pipeline.workflow_runs_per_page"],
+            Source::Env(String::from(
+                "RUST_WORKSPACE_DATA_PIPELINE__WORKFLOW_RUNS_PER_PAGE"
+            ))
+        );
+        assert_eq!(sources["guessing_game.max"], Source::Cli);
+        assert_eq!(sources["guessing_game.min"], Source::Default);
+    }
+
+    #[test]
+    fn unknown_key() {
+        let overrides = [String::from("guessing_game.maximum=70")];
+        let result = resolve(None, &BTreeMap::new(), &overrides);
+        assert!(matches!(result, Err(ConfigError::UnknownKey { .. })));
+    }
+
+    #[test]
+    fn invalid_value() {
+        let invalid = [
+            "data_pipeline.repos_per_page=many",
+            "linfa_train.max_iterations=-1",
+            "guessing_game.min=1000",
+            "guessing_game.attempts=0",
+        ];
+        for item in invalid {
+            let result = resolve(None, &BTreeMap::new(), &[String::from(item)]);
+            match result {
+                Err(ConfigError::InvalidValue { key, .. }) => assert!(!key.is_empty()),
+                _ => panic!("Expected an invalid value error for {:?}", item),
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! Console module.
+//!
+//! The programs read input lines and write human-readable text, with the registered secrets masked,
+//! through the console,
+//! which is the standard input and output of the process, or a scripted session.
+//! A session runs the programs of a registry with the given arguments and input on the current thread,
+//! and captures the output and the exit code instead of exiting the process, e.g. in tests.
+//! The records of the programs are captured in the output of the session, framed as in the process,
+//! and the human-readable text in the errors in the JSON output modes, see the `output` module.
+
+use clap::Command;
+use std::{
+    cell::RefCell,
+    ffi::OsString,
+    fmt::{self, Write as _},
+    io::{self, BufRead, Cursor, Write},
+    panic::{self, AssertUnwindSafe},
+    process,
+};
+
+use crate::{
+    output::{self, OutputFormat},
+    program::Registry,
+    redact,
+};
+
+thread_local! {
+    /// The session of the current thread, if any.
+    static SESSION: RefCell<Option<SessionState>> = const { RefCell::new(None) };
+}
+
+/// The input and the captured output of a running session.
+struct SessionState {
+    input: Box<dyn BufRead>,
+    interactive: bool,
+    format: OutputFormat,
+    /// The emitted records, framed in the output when the session ends.
+    records: Vec<serde_json::Value>,
+    output: String,
+    errors: String,
+}
+
+/// The payload unwinding a session when a program exits.
+struct Exit(i32);
+
+/// Reads a line, including the line break, from the session input or the standard input.
+/// Returns 0 if the input is closed.
+pub fn read_line(line: &mut String) -> io::Result<usize> {
+    let scripted = SESSION.with(|session| {
+        session
+            .borrow_mut()
+            .as_mut()
+            .map(|state| state.input.read_line(line))
+    });
+    scripted.unwrap_or_else(|| io::stdin().read_line(line))
+}
+
+/// Whether a session runs on the current thread.
+pub fn is_scripted() -> bool {
+    interactive().is_some()
+}
+
+/// Whether the session allows prompting input; `None` outside of a session.
+pub fn interactive() -> Option<bool> {
+    SESSION.with(|session| session.borrow().as_ref().map(|state| state.interactive))
+}
+
+/// The output format of the session; `None` outside of a session.
+pub fn format() -> Option<OutputFormat> {
+    SESSION.with(|session| session.borrow().as_ref().map(|state| state.format))
+}
+
+/// Prints the human-readable text with the registered secrets masked, see the `human_println!` macro.
+pub fn print(args: fmt::Arguments) {
+    let text = args.to_string();
+    let text = redact::registered(&text);
+    let text_output = output::is_text();
+    let captured = capture(&text, |state| {
+        if text_output {
+            &mut state.output
+        } else {
+            &mut state.errors
+        }
+    });
+    if captured {
+        return;
+    }
+    if text_output {
+        let _ = writeln!(io::stdout(), "{}", text);
+    } else {
+        let _ = writeln!(io::stderr(), "{}", text);
+    }
+}
+
+/// Prints the error text with the registered secrets masked to the standard error,
+/// see the `human_eprintln!` macro.
+pub fn eprint(args: fmt::Arguments) {
+    let text = args.to_string();
+    let text = redact::registered(&text);
+    if !capture(&text, |state| &mut state.errors) {
+        let _ = writeln!(io::stderr(), "{}", text);
+    }
+}
+
+/// Captures the record in the session, see the `output::emit` function;
+/// returns the record outside of a session.
+pub fn record(record: serde_json::Value) -> Option<serde_json::Value> {
+    SESSION.with(|session| match session.borrow_mut().as_mut() {
+        Some(state) => {
+            state.records.push(record);
+            None
+        }
+        None => Some(record),
+    })
+}
+
+/// Exits the process with the code after printing the buffered records,
+/// or ends the session with the code.
+pub fn exit(code: i32) -> ! {
+    if is_scripted() {
+        panic::resume_unwind(Box::new(Exit(code)));
+    }
+    output::finish();
+    process::exit(code);
+}
+
+/// Appends the text line to the session buffer; returns false outside of a session.
+fn capture(text: &str, buffer: impl FnOnce(&mut SessionState) -> &mut String) -> bool {
+    SESSION.with(|session| match session.borrow_mut().as_mut() {
+        Some(state) => {
+            let _ = writeln!(buffer(state), "{}", text);
+            true
+        }
+        None => false,
+    })
+}
+
+/// A scripted run of the programs.
+pub struct Session {
+    args: Vec<OsString>,
+    input: Box<dyn BufRead>,
+    interactive: bool,
+    format: OutputFormat,
+}
+
+/// The captured output of a session.
+#[derive(Debug)]
+pub struct Transcript {
+    /// The human-readable text in the text output mode, or the records in the JSON output modes.
+    pub output: String,
+    /// The error text, including the usage errors, and the human-readable text in the JSON output modes.
+    pub errors: String,
+    /// The exit code the process would exit with.
+    pub exit_code: i32,
+}
+
+impl Session {
+    /// Session constructor.
+    /// The arguments are the command line arguments without the binary name, e.g. `["calculator", "2+2"]`.
+    pub fn new<I, T>(args: I) -> Session
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString>,
+    {
+        Session {
+            args: args.into_iter().map(Into::into).collect(),
+            input: Box::new(io::empty()),
+            interactive: true,
+            format: OutputFormat::Text,
+        }
+    }
+
+    /// Sets the input.
+    pub fn input(mut self, input: impl BufRead + 'static) -> Self {
+        self.input = Box::new(input);
+        self
+    }
+
+    /// Sets the input lines.
+    pub fn lines(self, lines: &[&str]) -> Self {
+        let input: String = lines.iter().map(|line| line.to_string() + "\n").collect();
+        self.input(Cursor::new(input.into_bytes()))
+    }
+
+    /// Disables the prompts, like the `--non-interactive` argument.
+    pub fn non_interactive(mut self) -> Self {
+        self.interactive = false;
+        self
+    }
+
+    /// Sets the output format, like the `--output` argument.
+    pub fn output(mut self, format: OutputFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Runs the programs of the registry and returns the transcript.
+    pub fn run(self, registry: &Registry) -> Transcript {
+        let previous = SESSION.with(|session| {
+            session.borrow_mut().replace(SessionState {
+                input: self.input,
+                interactive: self.interactive,
+                format: self.format,
+                records: vec![],
+                output: String::new(),
+                errors: String::new(),
+            })
+        });
+
+        let command = registry.command(Command::new("rust-workspace"));
+        let args = std::iter::once(OsString::from("rust-workspace")).chain(self.args);
+        let exit_code = match command.try_get_matches_from(args) {
+            Ok(matches) => {
+                match panic::catch_unwind(AssertUnwindSafe(|| registry.dispatch(&matches))) {
+                    Ok(()) => 0,
+                    Err(payload) => match payload.downcast::<Exit>() {
+                        Ok(exit) => exit.0,
+                        Err(payload) => {
+                            SESSION.with(|session| *session.borrow_mut() = previous);
+                            panic::resume_unwind(payload);
+                        }
+                    },
+                }
+            }
+            Err(error) => {
+                eprint(format_args!("{}", error.render()));
+                error.exit_code()
+            }
+        };
+
+        let mut state = SESSION
+            .with(|session| std::mem::replace(&mut *session.borrow_mut(), previous))
+            .expect("The session is running");
+
+        let mut records = vec![];
+        let _ = output::write_records(&mut records, state.format, &state.records);
+        state.output += &String::from_utf8_lossy(&records);
+
+        Transcript {
+            output: state.output,
+            errors: state.errors,
+            exit_code,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests;
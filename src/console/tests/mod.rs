@@ -0,0 +1,245 @@
+mod session {
+    use crate::calculator;
+    use crate::config;
+    use crate::console::{Session, Transcript};
+    use crate::output::OutputFormat;
+    use crate::programs;
+    use crate::prompt;
+
+    /// Runs the programs of the workspace with the arguments and the input lines.
+    fn run(args: &[&str], lines: &[&str]) -> Transcript {
+        Session::new(args).lines(lines).run(&programs())
+    }
+
+    #[test]
+    fn menu_by_index_and_name() {
+        let transcript = run(&[], &["3", "2+2"]);
+        assert!(transcript.output.contains("Available programs:"));
+        assert!(transcript.output.contains("You selected: calculator"));
+        assert!(transcript.output.contains("4"));
+
+        let transcript = run(&[], &["calculator", "2*3"]);
+        assert!(transcript.output.contains("You selected: calculator"));
+        assert!(transcript.output.contains("6"));
+    }
+
+    #[test]
+    fn menu_reprompts_invalid_input() {
+        let transcript = run(&[], &["99", "unknown", "calculator", "1+1"]);
+        let reprompts = transcript
+            .output
+            .matches("The program does not exist.")
+            .count();
+        assert_eq!(reprompts, 2);
+        assert_eq!(transcript.output.matches("Available programs:").count(), 3);
+        assert!(transcript.output.contains("You selected: calculator"));
+    }
+
+    #[test]
+    fn nested_menu() {
+        let transcript = run(&["system-information"], &["9", "memory"]);
+        assert!(transcript.output.contains("Available subprograms:"));
+        assert!(transcript.output.contains("The subprogram does not exist."));
+        assert!(transcript.output.contains("Memory information:"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = run(&["linfa-train"], &["unknown"]);
+        assert!(transcript.output.contains("Available training programs:"));
+        assert!(transcript
+            .output
+            .contains("The training program does not exist."));
+    }
+
+    #[test]
+    fn closed_input() {
+        let transcript = run(&["calculator"], &["2+2"]);
+        assert!(transcript.output.contains("Result"));
+        assert!(transcript.errors.is_empty());
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = run(&["guessing-game"], &["0"]);
+        assert!(transcript.errors.contains("the standard input is closed"));
+        assert_eq!(transcript.exit_code, prompt::EXIT_CODE);
+    }
+
+    #[test]
+    fn calculator_repl() {
+        let transcript = run(&["calculator"], &["x = 2", "x * 3", "y", ":vars", ":quit"]);
+        assert!(transcript.output.contains("Result: 6"));
+        assert!(transcript.output.contains("invalid expression \"y\""));
+        assert!(transcript.output.contains("ans = 6"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = Session::new(["calculator", "1+"])
+            .non_interactive()
+            .run(&programs());
+        assert_eq!(transcript.exit_code, calculator::EXIT_CODE);
+    }
+
+    #[test]
+    fn calculator_leading_minus() {
+        let transcript = Session::new(["calculator", "-2^2"])
+            .non_interactive()
+            .run(&programs());
+        assert!(transcript.output.contains("Result: -4"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = Session::new(["calculator", "-3!", "--exact"])
+            .non_interactive()
+            .run(&programs());
+        assert!(transcript.output.contains("Result: -6"));
+        assert_eq!(transcript.exit_code, 0);
+    }
+
+    #[test]
+    fn program_data_is_not_redacted() {
+        let transcript = run(&["calculator", "token = 5"], &[]);
+        assert!(transcript.output.contains("token = 5"));
+        assert!(!transcript.output.contains("***"));
+    }
+
+    #[test]
+    fn calculator_batch() {
+        let transcript = Session::new(["calculator", "--batch", "--format", "csv"])
+            .lines(&["x = 2", "x * 3", "y"])
+            .non_interactive()
+            .run(&programs());
+        assert!(transcript.output.starts_with("line,input,variable,result"));
+        assert!(transcript.output.contains("2,x * 3,,6,,"));
+        assert!(transcript.errors.contains("line 3: invalid expression"));
+        assert_eq!(transcript.exit_code, calculator::EXIT_CODE);
+    }
+
+    #[test]
+    fn records() {
+        let transcript = Session::new(["calculator", "--batch"])
+            .lines(&["x = 2", "x * 3"])
+            .output(OutputFormat::Ndjson)
+            .run(&programs());
+        let records: Vec<serde_json::Value> = transcript
+            .output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0]["type"], "calculator.line");
+        assert_eq!(records[1]["result"], 6.0);
+        assert!(transcript.errors.contains("x * 3"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = Session::new(["calculator", "pj"])
+            .non_interactive()
+            .output(OutputFormat::Json)
+            .run(&programs());
+        let records: serde_json::Value = serde_json::from_str(&transcript.output).unwrap();
+        assert_eq!(records[0]["type"], "calculator.error");
+        assert_eq!(records[0]["expression"], "pj");
+        assert!(transcript.errors.contains("unknown variable"));
+        assert_eq!(transcript.exit_code, calculator::EXIT_CODE);
+
+        let transcript = run(&["calculator", "2+2"], &[]);
+        assert!(!transcript.output.contains("calculator.result"));
+    }
+
+    #[test]
+    fn guessing_game_reprompts_invalid_guess() {
+        let transcript = run(&["guessing-game"], &["abc", "0", "101"]);
+        assert_eq!(
+            transcript
+                .output
+                .matches("Please input your guess:")
+                .count(),
+            4
+        );
+        assert!(transcript.output.contains("Too small!"));
+        assert!(transcript.output.contains("Too big!"));
+        assert_eq!(transcript.exit_code, prompt::EXIT_CODE);
+    }
+
+    #[test]
+    fn guessing_game_loss() {
+        let transcript = run(
+            &[
+                "guessing-game",
+                "--min",
+                "5",
+                "--max",
+                "5",
+                "--attempts",
+                "2",
+            ],
+            &["4", "6"],
+        );
+        assert!(!transcript.output.contains("The secret number is"));
+        assert!(transcript.output.contains("Attempts left: 1"));
+        assert!(transcript.output.contains("The secret number was: 5"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = run(&["guessing-game", "--min", "5", "--max", "1"], &[]);
+        assert!(transcript
+            .errors
+            .contains("the minimum 5 is greater than the maximum 1"));
+        assert_eq!(transcript.exit_code, config::EXIT_CODE);
+    }
+
+    #[test]
+    fn guessing_game_reverse() {
+        let reverse = ["guessing-game", "--reverse", "--min", "1", "--max", "10"];
+        let transcript = run(&reverse, &["lower", "maybe", "h", "c"]);
+        assert!(transcript.output.contains("My guess: 5"));
+        assert!(transcript
+            .output
+            .contains("I guessed your number 3 in 3 guesses, the optimum for the range is 4"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = run(&reverse, &["h", "l", "h", "l"]);
+        assert!(transcript
+            .output
+            .contains("No number from 1 to 10 is higher than 6 and lower than 7."));
+        assert_eq!(transcript.exit_code, 0);
+    }
+
+    #[test]
+    fn non_interactive() {
+        let transcript = Session::new(["calculator", "2+2"])
+            .non_interactive()
+            .run(&programs());
+        assert!(transcript.output.contains("4"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = Session::new(["calculator"])
+            .non_interactive()
+            .run(&programs());
+        assert!(transcript
+            .errors
+            .contains("the program runs in the non-interactive mode"));
+        assert_eq!(transcript.exit_code, prompt::EXIT_CODE);
+    }
+
+    #[test]
+    fn help() {
+        let transcript = run(&["--help"], &[]);
+        assert!(transcript.errors.contains("Guess the secret number"));
+        assert!(transcript
+            .errors
+            .contains("Serve the programs as a JSON HTTP API"));
+        assert!(!transcript.errors.contains("Input arguments"));
+        assert_eq!(transcript.exit_code, 0);
+
+        let transcript = run(&["guessing-game", "--help"], &[]);
+        assert!(transcript.errors.starts_with("Guess the secret number"));
+
+        for group in ["data-pipeline", "linfa-train"] {
+            let transcript = run(&[group, "--help"], &[]);
+            assert!(!transcript.errors.contains("Input arguments"));
+            assert!(!transcript.errors.contains("arguments of the"));
+        }
+    }
+
+    #[test]
+    fn usage_error() {
+        let transcript = run(&["guessing-game", "--guess", "abc"], &[]);
+        assert!(transcript.errors.contains("invalid value"));
+        assert_eq!(transcript.exit_code, 2);
+    }
+}
@@ -1,19 +1,12 @@
 //! Artifact configuration submodule.
 
-use colored::Colorize;
-use std::env::{self};
+use std::{
+    env::{self},
+    path::Path,
+};
 
-/// Artifact module context configuration.
-pub fn choose_context(contexts: [&str; 2], context: Option<String>) -> usize {
-    let p = ArtifactConfiguration::new(contexts);
-    p.choose_context(context)
-}
-
-/// Artifact module file system configuration.
-pub fn fs_config(contexts: [&str; 2], collection: String) -> ArtifactFileConfig {
-    let p = ArtifactConfiguration::new(contexts);
-    p.fs_config(collection)
-}
+use crate::config::DataPipelineConfig;
+use crate::data_pipeline::{error::FsError, Collection};
 
 pub struct ArtifactFileConfig {
     pub json_collection_path: String,
@@ -22,66 +15,37 @@ pub struct ArtifactFileConfig {
     pub encrypted_artifact_file_name: String,
 }
 
-struct ArtifactConfiguration<'a> {
-    contexts: [&'a str; 2],
-}
-
-impl<'a> ArtifactConfiguration<'a> {
-    /// Program constructor.
-    fn new(contexts: [&'a str; 2]) -> ArtifactConfiguration {
-        ArtifactConfiguration { contexts }
-    }
-
-    /// Artifact module context configuration.
-    fn choose_context(&self, context: Option<String>) -> usize {
-        let is_some = context.is_some();
-        let context_input = if is_some {
-            match context.unwrap().trim().parse::<String>() {
-                Ok(value) => value,
-                Err(_) => String::new(),
-            }
-        } else {
-            String::new()
-        };
-
-        let mut index = usize::MAX;
-        for (i, ctx) in self.contexts.iter().enumerate() {
-            if ctx.to_owned().eq(context_input.as_str()) {
-                index = i;
-                break;
-            }
-        }
-
-        index
-    }
-
-    /// Artifact module file system configuration.
-    fn fs_config(&self, collection: String) -> ArtifactFileConfig {
-        let cwd = match env::current_dir() {
-            Ok(value) => {
-                println!("{}: {:?}", "Current directory".cyan().bold(), value);
-                value.display().to_string()
-            }
-            Err(error) => {
-                panic!("{:?}", error);
-            }
-        };
-
-        let json_base_path = "./.data/output/github/";
-        let json_collection_path = json_base_path.to_owned() + collection.as_str() + "/";
-
-        let artifact_base_path = cwd + "/.data/artifact/github/";
-        let artifact_file_name = "github-".to_string() + collection.as_str() + ".tar.gz";
-        let encrypted_artifact_file_name =
-            "github-".to_string() + collection.as_str() + ".tar.gz.gpg";
-
-        ArtifactFileConfig {
-            json_collection_path,
-            artifact_base_path,
-            artifact_file_name,
-            encrypted_artifact_file_name,
-        }
-    }
+/// Artifact module file system configuration.
+pub fn fs_config(
+    collection: Collection,
+    config: &DataPipelineConfig,
+) -> Result<ArtifactFileConfig, FsError> {
+    let cwd = env::current_dir()
+        .map_err(|error| FsError::new("resolve the current directory", ".", error))?;
+    log::debug!("the current directory is {}", cwd.display());
+    let cwd = cwd.display().to_string();
+
+    let json_collection_path = config
+        .github_output_path
+        .join(collection.name())
+        .display()
+        .to_string()
+        + "/";
+
+    let artifact_base_path = Path::new(&cwd)
+        .join(&config.artifact_path)
+        .display()
+        .to_string()
+        + "/";
+    let artifact_file_name = "github-".to_string() + collection.name() + ".tar.gz";
+    let encrypted_artifact_file_name = "github-".to_string() + collection.name() + ".tar.gz.gpg";
+
+    Ok(ArtifactFileConfig {
+        json_collection_path,
+        artifact_base_path,
+        artifact_file_name,
+        encrypted_artifact_file_name,
+    })
 }
 
 #[cfg(test)]
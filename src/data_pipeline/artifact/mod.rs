@@ -1,70 +1,106 @@
 //! Artifact module for the data pipeline.
 
-use colored::Colorize;
-use std::fs;
+use std::{
+    fs, io,
+    process::{Command, ExitStatus},
+};
+use thiserror::Error;
+
+use super::{error::FsError, Collection};
+use crate::config::DataPipelineConfig;
+
+pub use self::create_artifact::ArtifactCreator;
+pub use self::restore_artifact::ArtifactRestorer;
 
 mod configuration;
 mod create_artifact;
 mod restore_artifact;
 
-/// Supported contexts.
-pub type Contexts<'a> = [&'a str; 2];
-/// Supported contexts.
-pub const CONTEXTS: Contexts = ["Create artifact", "Restore artifact"];
-
-/// The entry point of the program.
-pub fn main(contexts: Contexts, context_arg: Option<String>, collection: String) {
-    DataPipelineArtifact::new(contexts, context_arg, collection);
+/// Artifact creation or restoration failure.
+#[derive(Debug, Error)]
+pub enum ArtifactError {
+    #[error("can't run {program}")]
+    Spawn {
+        program: String,
+        #[source]
+        source: io::Error,
+    },
+    #[error("{program} failed with {status}: {stderr}")]
+    Command {
+        program: String,
+        status: ExitStatus,
+        stderr: String,
+    },
+    #[error(transparent)]
+    Fs(#[from] FsError),
 }
 
-struct DataPipelineArtifact<'a> {
-    contexts: Contexts<'a>,
+/// Custom result type for artifact operations.
+type Result<T> = std::result::Result<T, ArtifactError>;
+
+/// The artifact operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    /// Creates an encrypted artifact of the collected data.
+    Create,
+    /// Restores the data of an encrypted artifact.
+    Restore,
 }
 
-impl<'a> DataPipelineArtifact<'a> {
-    /// Program constructor.
-    fn new(
-        contexts: Contexts,
-        context_arg: Option<String>,
-        collection: String,
-    ) -> DataPipelineArtifact {
-        let mut program = DataPipelineArtifact { contexts };
-        program.init(context_arg, collection);
-        program
+impl Operation {
+    /// The name of the operation, used in the output records.
+    pub fn name(self) -> &'static str {
+        match self {
+            Operation::Create => "create",
+            Operation::Restore => "restore",
+        }
     }
+}
 
-    /// Initializes the program.
-    fn init(&mut self, context: Option<String>, collection: String) {
-        println!("\n{}", "DataPipelineArtifact initialized.".blue().bold());
-
-        println!("\n{} {:?}", "Selected context".blue().bold(), context);
-
-        let context_index = configuration::choose_context(self.contexts, context);
-
-        let fs_config = configuration::fs_config(self.contexts, collection);
+/// Executes the artifact operation for the collection with the paths of the configuration.
+/// Returns the path of the encrypted artifact if created, or the decrypted artifact if restored.
+pub fn execute(
+    operation: Operation,
+    collection: Collection,
+    passphrase: &str,
+    config: &DataPipelineConfig,
+) -> Result<String> {
+    let fs_config = configuration::fs_config(collection, config)?;
 
-        match context_index {
-            0 => {
-                let create_dir_result = fs::create_dir_all(&fs_config.artifact_base_path);
-                if let Ok(_tmp) = create_dir_result {
-                    let source_path = fs_config.json_collection_path;
-                    let output_path = fs_config.artifact_base_path + &fs_config.artifact_file_name;
-                    create_artifact::main(&output_path, &source_path);
-                }
-            }
-            1 => restore_artifact::main(
+    match operation {
+        Operation::Create => {
+            fs::create_dir_all(&fs_config.artifact_base_path).map_err(|error| {
+                FsError::new("create the directory", &fs_config.artifact_base_path, error)
+            })?;
+            let source_path = fs_config.json_collection_path;
+            let output_path = fs_config.artifact_base_path + &fs_config.artifact_file_name;
+            ArtifactCreator::new(passphrase).create(&output_path, &source_path)
+        }
+        Operation::Restore => {
+            ArtifactRestorer::new(passphrase).restore(
                 &fs_config.artifact_base_path,
                 &fs_config.artifact_file_name,
                 &fs_config.encrypted_artifact_file_name,
-            ),
-            _ => {
-                println!(
-                    "\n{}",
-                    "Nothing to execute. The context is not supported"
-                        .red()
-                        .bold()
-                )
-            }
+            )?;
+            Ok(fs_config.artifact_base_path + &fs_config.artifact_file_name)
         }
     }
 }
+
+/// Runs the command and fails if it does not exit successfully.
+fn run(command: &mut Command) -> Result<()> {
+    let program = command.get_program().to_string_lossy().to_string();
+    let output = command.output().map_err(|source| ArtifactError::Spawn {
+        program: program.clone(),
+        source,
+    })?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(ArtifactError::Command {
+            program,
+            status: output.status,
+            stderr: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        })
+    }
+}
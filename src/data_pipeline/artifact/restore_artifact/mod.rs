@@ -1,133 +1,64 @@
 //! Artifact restoration module for the data pipeline.
 
-use std::{
-    env::{self},
-    process::Command,
-};
+use std::process::Command;
 
-use colored::Colorize;
+use super::{run, Result};
 
-/// The entry point of the program.
-pub fn main(
-    artifact_base_path: &str,
-    artifact_file_name: &str,
-    encrypted_artifact_file_name: &str,
-) {
-    ArtifactRestorer::new(
-        artifact_base_path,
-        artifact_file_name,
-        encrypted_artifact_file_name,
-    );
+/// Restores encrypted archives.
+pub struct ArtifactRestorer {
+    passphrase: String,
+    output_path: String,
 }
 
-struct ArtifactRestorer;
-
 impl ArtifactRestorer {
-    /// Program constructor.
-    fn new(
-        artifact_base_path: &str,
-        artifact_file_name: &str,
-        encrypted_artifact_file_name: &str,
-    ) -> ArtifactRestorer {
-        let program = ArtifactRestorer;
-        program.init(
-            artifact_base_path,
-            artifact_file_name,
-            encrypted_artifact_file_name,
-        );
-        program
+    /// Artifact restorer constructor.
+    /// The artifacts are unpacked to the current directory by default.
+    pub fn new(passphrase: &str) -> ArtifactRestorer {
+        ArtifactRestorer {
+            passphrase: passphrase.trim().to_owned(),
+            output_path: String::from("./"),
+        }
     }
 
-    /// Initializes the program.
-    fn init(
-        &self,
-        artifact_base_path: &str,
-        artifact_file_name: &str,
-        encrypted_artifact_file_name: &str,
-    ) {
-        println!("\n{}", "ArtifactRestorer initialized.".blue().bold());
-
-        println!("{} {:?}", "Artifact base path".cyan(), artifact_base_path);
-        println!("{} {:?}", "Artifact file name".cyan(), artifact_file_name);
-        println!(
-            "{} {:?}",
-            "Encrypted artifact file name".cyan(),
-            encrypted_artifact_file_name
-        );
-
-        self.restore_artifact(
-            artifact_base_path,
-            artifact_file_name,
-            encrypted_artifact_file_name,
-        )
+    /// Sets the directory the artifacts are unpacked to.
+    pub fn output_path(mut self, output_path: &str) -> Self {
+        self.output_path = output_path.to_owned();
+        self
     }
 
-    fn restore_artifact(
+    /// Decrypts and unpacks the artifact.
+    pub fn restore(
         &self,
         artifact_base_path: &str,
         artifact_file_name: &str,
         encrypted_artifact_file_name: &str,
-    ) {
-        println!("\n{}", "Restoring artifact...".cyan().bold());
+    ) -> Result<()> {
+        log::info!("restoring the artifact");
 
         let artifact_path = artifact_base_path.to_owned() + artifact_file_name;
         let encrypted_artifact_path = artifact_base_path.to_owned() + encrypted_artifact_file_name;
 
-        let gpg_passphrase_env = env::var("GPG_PASSPHRASE");
-        let gpg_passphrase = match gpg_passphrase_env.unwrap().trim().parse::<String>() {
-            Ok(value) => value,
-            Err(_) => String::new(),
-        };
+        log::info!("encrypted artifact path: {:?}", encrypted_artifact_path);
 
-        println!(
-            "\n{}:\n{:?}",
-            "Encrypted artifact path".green().bold(),
-            encrypted_artifact_path
-        );
+        run(Command::new("gpg").args([
+            "--batch",
+            "--yes",
+            "--passphrase",
+            &self.passphrase,
+            "--decrypt",
+            "--output",
+            &artifact_path,
+            &encrypted_artifact_path,
+        ]))?;
 
-        match Command::new("gpg")
-            .args([
-                "--batch",
-                "--yes",
-                "--passphrase",
-                &gpg_passphrase,
-                "--decrypt",
-                "--output",
-                &artifact_path,
-                &encrypted_artifact_path,
-            ])
-            .output()
-        {
-            Ok(output) => {
-                println!(
-                    "\n{}\n{:?}",
-                    "Decrypt artifact success".bold().green(),
-                    output
-                );
-            }
-            Err(error) => {
-                panic!("\n{}\n{:?}", "Decrypt artifact error".bold().red(), error);
-            }
-        }
+        log::info!("decrypted the archive");
 
-        println!("\n{}: {:?}", "Artifact path".green().bold(), artifact_path);
+        log::info!("artifact path: {:?}", artifact_path);
 
-        let output_path = "./";
+        run(Command::new("tar").args(["-xzf", &artifact_path, "--directory", &self.output_path]))?;
 
-        match Command::new("tar")
-            .args(["-xzf", &artifact_path, "--directory", output_path])
-            .output()
-        {
-            Ok(output) => {
-                println!(
-                    "\n{}\n{:?}",
-                    "Unpack artifact success".bold().green(),
-                    output
-                );
-            }
-            Err(error) => {
-                panic!("\n{}\n{:?}", "Unpack artifact error".bold().red(), error);
-            }
-        }
+        log::info!("unpacked the archive");
+
+        Ok(())
     }
 }
@@ -1,25 +1,36 @@
 mod data_pipeline_configuration {
-    use crate::data_pipeline::{
-        artifact::CONTEXTS, configuration::DataPipelineConfiguration, mongo::COLLECTIONS,
-    };
+    use crate::data_pipeline::{configuration::choose_collection, programs, Collection};
 
     #[test]
     fn choose_context() {
-        let program = DataPipelineConfiguration::new(CONTEXTS, COLLECTIONS);
-        let contexts = program.contexts;
-        for (i, record) in contexts.iter().enumerate() {
-            let index = program.choose_context(Some(i.to_string()));
-            assert_eq!(contexts.get(index), Some(record));
+        let contexts = programs();
+        for (i, record) in contexts.programs().enumerate() {
+            let context = contexts.choose(Some(i.to_string()));
+            assert_eq!(context.name(), record.name());
         }
     }
 
     #[test]
-    fn choose_collection() {
-        let program = DataPipelineConfiguration::new(CONTEXTS, COLLECTIONS);
-        let collections = program.collections;
-        for (i, record) in collections.iter().enumerate() {
-            let index = program.choose_collection(Some(i.to_string()));
-            assert_eq!(collections.get(index), Some(record));
+    fn choose_context_by_name() {
+        let contexts = programs();
+        for record in contexts.programs() {
+            let context = contexts.choose(Some(record.name().to_string()));
+            assert_eq!(context.name(), record.name());
+        }
+    }
+
+    #[test]
+    fn choose_collection_by_argument() {
+        for collection in Collection::ALL {
+            assert_eq!(choose_collection(Some(collection)), collection);
+        }
+    }
+
+    #[test]
+    fn collection_names() {
+        for collection in Collection::ALL {
+            assert_eq!(collection.to_string(), collection.name());
+            assert_eq!(serde_json::to_value(collection).unwrap(), collection.name());
         }
     }
 }
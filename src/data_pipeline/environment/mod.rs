@@ -1,121 +1,114 @@
 //! Environment loader module for the data pipeline.
 
-use colored::Colorize;
 use std::{
     collections::HashMap,
-    env::{self},
+    env::{self, VarError},
     fs::{self},
+    io,
+    path::{Path, PathBuf},
 };
+use thiserror::Error;
+
+use crate::redact;
+
+/// Environment loading failure.
+#[derive(Debug, Error)]
+pub enum EnvironmentError {
+    #[error("can't resolve the current directory")]
+    CurrentDir(#[source] io::Error),
+    #[error("can't read the env file {path:?}")]
+    Read {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    #[error("the environment variable {key} is not available")]
+    MissingVariable {
+        key: String,
+        #[source]
+        source: VarError,
+    },
+}
+
+/// Custom result type for the environment loader.
+type Result<T> = std::result::Result<T, EnvironmentError>;
 
 /// The entry point of the program.
-pub fn main(relative_env_path: Option<String>) {
-    DataPipelineEnvironment::new(relative_env_path);
+/// Loads the variables of the env file, without changing the process environment.
+/// The path is relative to the current directory, `.env` by default.
+pub fn main(relative_env_path: Option<PathBuf>) -> Result<HashMap<String, String>> {
+    DataPipelineEnvironment::new(relative_env_path).load_env_vars()
 }
 
-struct DataPipelineEnvironment {
-    relative_env_path: Option<String>,
+/// Reads variables from the env file.
+pub fn read(env_path: &Path) -> io::Result<HashMap<String, String>> {
+    let env_content = fs::read_to_string(env_path)?;
+    Ok(parse(&env_content))
 }
 
-impl DataPipelineEnvironment {
-    /// Program constructor.
-    fn new(relative_env_path: Option<String>) -> DataPipelineEnvironment {
-        let mut program = DataPipelineEnvironment { relative_env_path };
-        program.init();
-        program
+/// Parses `KEY=value` lines of the env file content.
+pub fn parse(env_content: &str) -> HashMap<String, String> {
+    env_content
+        .lines()
+        .filter(|x| x.contains('='))
+        .map(|x| {
+            let mut split_pair = x.split('=').collect::<Vec<&str>>();
+            let key = split_pair.remove(0).trim().to_string();
+            let value = split_pair.join("");
+            (key, value)
+        })
+        .collect()
+}
+
+/// Reads the variable of the env file, or of the process environment if the env file doesn't set it.
+pub fn var(env: &HashMap<String, String>, key: &str) -> Result<String> {
+    match env.get(key) {
+        Some(value) => Ok(value.clone()),
+        None => env::var(key).map_err(|source| EnvironmentError::MissingVariable {
+            key: key.to_owned(),
+            source,
+        }),
     }
+}
 
-    /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "DataPipelineEnvironment initialized.".blue().bold());
+struct DataPipelineEnvironment {
+    relative_env_path: Option<PathBuf>,
+}
 
-        self.load_env_vars();
+impl DataPipelineEnvironment {
+    /// Program constructor.
+    fn new(relative_env_path: Option<PathBuf>) -> DataPipelineEnvironment {
+        DataPipelineEnvironment { relative_env_path }
     }
 
     /// Load environment variables read from the .env file.
-    fn load_env_vars(&self) -> HashMap<String, String> {
-        let result = self.read_env();
-        let config = match result {
-            Ok(env) => {
-                let iter = env.iter();
-                for record in iter {
-                    let key = record.0;
-                    let value = record.1;
-                    env::set_var(key, value);
-                    println!("key: {key}\nvalue: {value}");
-                }
-                env
-            }
-            Err(error) => {
-                println!(
-                    "\n{}: {:?}",
-                    "There was an error reading the .env file".red(),
-                    error
-                );
-                HashMap::from([])
+    /// The values of the secret keys are registered for redaction.
+    fn load_env_vars(&self) -> Result<HashMap<String, String>> {
+        let env = self.read_env()?;
+        for (key, value) in env.iter() {
+            if redact::is_secret_key(key) {
+                redact::register(value);
             }
-        };
-        config
+            log::debug!("loaded the environment variable {}", key);
+        }
+        Ok(env)
     }
 
     /// Read variables from the .env file.
-    fn read_env(&self) -> std::io::Result<HashMap<String, String>> {
-        let cwd = env::current_dir()?;
-
-        println!("The current directory is {}", cwd.display());
+    fn read_env(&self) -> Result<HashMap<String, String>> {
+        let cwd = env::current_dir().map_err(EnvironmentError::CurrentDir)?;
 
-        let default_relative_env_path = "/.env".to_string();
-        let custom_relative_env_path = self.relative_env_path.clone();
+        log::debug!("the current directory is {}", cwd.display());
 
-        let relative_env_path = if let Some(val) = custom_relative_env_path {
-            match val.trim().parse::<String>() {
-                Ok(value) => value,
-                Err(_) => default_relative_env_path,
-            }
-        } else {
-            default_relative_env_path
-        };
+        let relative_env_path = self
+            .relative_env_path
+            .clone()
+            .unwrap_or_else(|| PathBuf::from(".env"));
 
-        let env_path = cwd.display().to_string() + relative_env_path.as_str();
-        let env_path_str = env_path.as_str();
-        let env_content_result = fs::read_to_string(env_path_str);
-        let Ok(env_content) = env_content_result else {
-            panic!("\n{} {:?}", "Can't read directory".red().bold(), env_path);
-        };
-
-        println!("Text content:\n{env_content}");
-
-        let lines = self.read_lines(env_path_str);
-        let config: Vec<_> = lines
-            .iter()
-            .filter(|x| x.contains('='))
-            .flat_map(|x| {
-                let mut split_pair = x.split('=').collect::<Vec<&str>>();
-                let split_key = split_pair.first();
-                let some_key = split_key.is_some();
-                let key = if some_key {
-                    match split_key.unwrap().trim().parse::<String>() {
-                        Ok(value) => value,
-                        Err(_) => String::new(),
-                    }
-                } else {
-                    String::new()
-                };
-                split_pair.remove(0);
-                let value = split_pair.join("");
-                let map = vec![(key, value)];
-                map
-            })
-            .collect();
-        let map: HashMap<String, String> = HashMap::from_iter(config);
-        Ok(map)
-    }
-
-    /// Read a file line by line.
-    fn read_lines(&self, filename: &str) -> Vec<String> {
-        let mut result = Vec::new();
-        for line in fs::read_to_string(filename).unwrap().lines() {
-            result.push(line.to_string())
-        }
-        result
+        let env_path = cwd.join(relative_env_path);
+        read(&env_path).map_err(|source| EnvironmentError::Read {
+            path: env_path,
+            source,
+        })
     }
 }
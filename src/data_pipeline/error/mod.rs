@@ -0,0 +1,169 @@
+//! Error module for the data pipeline.
+//!
+//! Each subsystem has its own error type, and `DataPipelineError` wraps them.
+//! The category of an error defines the exit code of the process.
+
+use colored::Colorize;
+use serde::Serialize;
+use std::{error::Error, io, path::PathBuf};
+use thiserror::Error;
+
+use crate::{console, output};
+
+use super::{
+    artifact::ArtifactError, environment::EnvironmentError, github::GitHubError,
+    mongo::MongoDbError,
+};
+
+/// A file system operation failure.
+#[derive(Debug, Error)]
+#[error("can't {action} {path:?}")]
+pub struct FsError {
+    action: &'static str,
+    path: PathBuf,
+    #[source]
+    source: io::Error,
+}
+
+impl FsError {
+    /// File system error constructor.
+    /// The action describes the failed operation, e.g. `read the directory`.
+    pub fn new(action: &'static str, path: impl Into<PathBuf>, source: io::Error) -> FsError {
+        FsError {
+            action,
+            path: path.into(),
+            source,
+        }
+    }
+}
+
+/// A data pipeline failure.
+#[derive(Debug, Error)]
+pub enum DataPipelineError {
+    #[error(transparent)]
+    Environment(#[from] EnvironmentError),
+    #[error(transparent)]
+    Fs(#[from] FsError),
+    #[error(transparent)]
+    GitHub(#[from] GitHubError),
+    #[error(transparent)]
+    MongoDb(#[from] MongoDbError),
+    #[error(transparent)]
+    Artifact(#[from] ArtifactError),
+    #[error("can't start the async runtime")]
+    Runtime(#[source] io::Error),
+}
+
+/// The category of a data pipeline failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// The `.env` file or an environment variable is missing.
+    Environment,
+    /// A file or a directory can't be read or written.
+    Fs,
+    /// A GitHub API request failed.
+    GitHub,
+    /// A MongoDB operation failed.
+    MongoDb,
+    /// An artifact can't be created or restored.
+    Artifact,
+    /// Any other failure.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// The name of the category.
+    pub fn name(&self) -> &'static str {
+        match self {
+            ErrorCategory::Environment => "environment",
+            ErrorCategory::Fs => "filesystem",
+            ErrorCategory::GitHub => "github",
+            ErrorCategory::MongoDb => "mongodb",
+            ErrorCategory::Artifact => "artifact",
+            ErrorCategory::Internal => "internal",
+        }
+    }
+
+    /// The exit code of the process.
+    /// The code 2 is reserved for missing inputs, see the `prompt` module.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ErrorCategory::Internal => 1,
+            ErrorCategory::Environment => 3,
+            ErrorCategory::Fs => 4,
+            ErrorCategory::GitHub => 5,
+            ErrorCategory::MongoDb => 6,
+            ErrorCategory::Artifact => 7,
+        }
+    }
+}
+
+impl DataPipelineError {
+    /// The category of the error.
+    /// File system failures of the subsystems are categorized as file system errors.
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            DataPipelineError::Environment(_) => ErrorCategory::Environment,
+            DataPipelineError::Fs(_)
+            | DataPipelineError::GitHub(GitHubError::Fs(_))
+            | DataPipelineError::MongoDb(MongoDbError::Fs(_))
+            | DataPipelineError::Artifact(ArtifactError::Fs(_)) => ErrorCategory::Fs,
+            DataPipelineError::GitHub(_) => ErrorCategory::GitHub,
+            DataPipelineError::MongoDb(_) => ErrorCategory::MongoDb,
+            DataPipelineError::Artifact(_) => ErrorCategory::Artifact,
+            DataPipelineError::Runtime(_) => ErrorCategory::Internal,
+        }
+    }
+}
+
+/// The `data_pipeline.error` output record.
+#[derive(Serialize)]
+struct ErrorRecord {
+    category: &'static str,
+    exit_code: i32,
+    message: String,
+    /// The cause chain, from the direct cause to the root cause.
+    causes: Vec<String>,
+}
+
+/// The cause chain of the error, from the direct cause to the root cause.
+pub fn causes(error: &DataPipelineError) -> Vec<String> {
+    let mut causes = vec![];
+    let mut source = error.source();
+    while let Some(cause) = source {
+        causes.push(cause.to_string());
+        source = cause.source();
+    }
+    causes
+}
+
+/// Prints the error with its cause chain and exits with the exit code of the error category.
+pub fn report(error: &DataPipelineError) -> ! {
+    let category = error.category();
+
+    human_eprintln!(
+        "\n{} ({}): {}",
+        "Data pipeline error".red().bold(),
+        category.name(),
+        error
+    );
+
+    let causes = causes(error);
+    for cause in &causes {
+        human_eprintln!("  {}: {}", "caused by".red(), cause);
+    }
+
+    output::emit(
+        "data_pipeline.error",
+        &ErrorRecord {
+            category: category.name(),
+            exit_code: category.exit_code(),
+            message: error.to_string(),
+            causes,
+        },
+    );
+    console::exit(category.exit_code());
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,103 @@
+mod data_pipeline_error {
+    use std::{env::VarError, error::Error, io, path::PathBuf};
+
+    use crate::data_pipeline::{
+        artifact::ArtifactError,
+        environment::EnvironmentError,
+        error::{DataPipelineError, ErrorCategory, FsError},
+        github::GitHubError,
+        mongo::MongoDbError,
+    };
+
+    fn fs_error() -> FsError {
+        FsError::new(
+            "read the directory",
+            "./.data/output/github/repos",
+            io::Error::from(io::ErrorKind::NotFound),
+        )
+    }
+
+    #[test]
+    fn category() {
+        let errors = [
+            (
+                DataPipelineError::from(EnvironmentError::MissingVariable {
+                    key: String::from("GITHUB_TOKEN"),
+                    source: VarError::NotPresent,
+                }),
+                ErrorCategory::Environment,
+            ),
+            (DataPipelineError::from(fs_error()), ErrorCategory::Fs),
+            (
+                DataPipelineError::from(GitHubError::MissingOwner(String::from("repo"))),
+                ErrorCategory::GitHub,
+            ),
+            (
+                DataPipelineError::from(MongoDbError::DataDir {
+                    data_dir: PathBuf::from("./.data/output/github/repos"),
+                    collection: String::from("workflows"),
+                }),
+                ErrorCategory::MongoDb,
+            ),
+            (
+                DataPipelineError::from(ArtifactError::Spawn {
+                    program: String::from("gpg"),
+                    source: io::Error::from(io::ErrorKind::NotFound),
+                }),
+                ErrorCategory::Artifact,
+            ),
+            (
+                DataPipelineError::Runtime(io::Error::from(io::ErrorKind::Other)),
+                ErrorCategory::Internal,
+            ),
+        ];
+        for (error, category) in errors {
+            assert_eq!(error.category(), category);
+        }
+    }
+
+    #[test]
+    fn subsystem_fs_error_category() {
+        let errors = [
+            DataPipelineError::from(GitHubError::from(fs_error())),
+            DataPipelineError::from(MongoDbError::from(fs_error())),
+            DataPipelineError::from(ArtifactError::from(fs_error())),
+        ];
+        for error in errors {
+            assert_eq!(error.category(), ErrorCategory::Fs);
+        }
+    }
+
+    #[test]
+    fn exit_codes_are_distinct() {
+        let categories = [
+            ErrorCategory::Environment,
+            ErrorCategory::Fs,
+            ErrorCategory::GitHub,
+            ErrorCategory::MongoDb,
+            ErrorCategory::Artifact,
+            ErrorCategory::Internal,
+        ];
+        for (i, category) in categories.iter().enumerate() {
+            assert_ne!(category.exit_code(), 0);
+            assert_ne!(category.exit_code(), crate::prompt::EXIT_CODE);
+            for other in &categories[i + 1..] {
+                assert_ne!(category.exit_code(), other.exit_code());
+            }
+        }
+    }
+
+    #[test]
+    fn cause_chain() {
+        let error = DataPipelineError::from(GitHubError::from(fs_error()));
+        assert_eq!(
+            error.to_string(),
+            "can't read the directory \"./.data/output/github/repos\""
+        );
+        let source = error.source().map(|source| source.to_string());
+        assert_eq!(
+            source,
+            Some(io::Error::from(io::ErrorKind::NotFound).to_string())
+        );
+    }
+}
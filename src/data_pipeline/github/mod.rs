@@ -1,53 +1,103 @@
 //! GitHub module for the data pipeline.
 
-use octorust::{types::Order, types::SearchReposSort};
+use octorust::{
+    types::SearchReposSort,
+    types::{Order, Repository},
+    ClientError,
+};
+use std::{
+    fs::{self, File},
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+use super::error::FsError;
+use super::{Collection, Progress};
+use crate::config::DataPipelineConfig;
 
 pub use self::repositories::ReposFetchResult;
 pub use self::workflows::WorkflowRunsFetchResult;
 
+/// GitHub data collection failure.
+#[derive(Debug, Error)]
+pub enum GitHubError {
+    #[error("can't create the GitHub client")]
+    Client(#[source] Box<ClientError>),
+    #[error("the GitHub request failed")]
+    Request(#[source] Box<ClientError>),
+    #[error("can't wait for the GitHub API rate limit reset")]
+    RateLimitWait(#[source] io::Error),
+    #[error("the repository {0:?} has no owner")]
+    MissingOwner(String),
+    #[error("can't write the GitHub data to {path:?}")]
+    Write {
+        path: PathBuf,
+        #[source]
+        source: serde_json::Error,
+    },
+    #[error(transparent)]
+    Fs(#[from] FsError),
+}
+
 /// Custom result type for fetch results.
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+type Result<T> = std::result::Result<T, GitHubError>;
 
 mod rate_limit_handler;
 mod repositories;
 mod workflows;
 
 pub struct DataPipelineGitHubFsConfiguration {
-    pub repos_output: String,
-    pub workflows_output: String,
+    pub repos_output: PathBuf,
+    pub workflows_output: PathBuf,
 }
 
-/// The entry point of the program.
-pub fn main() -> DataPipelineGitHub {
-    DataPipelineGitHub::new()
+impl DataPipelineGitHubFsConfiguration {
+    /// The output directories in the GitHub output directory.
+    pub fn new(output_path: &Path) -> Self {
+        DataPipelineGitHubFsConfiguration {
+            repos_output: output_path.join("repos"),
+            workflows_output: output_path.join("workflows"),
+        }
+    }
 }
 
 pub struct DataPipelineGitHub {
     pub configuration: DataPipelineGitHubFsConfiguration,
+    /// The number of repositories per page of the repository search.
+    pub repos_per_page: i64,
+    /// The number of workflow runs fetched per repository.
+    pub workflow_runs_per_page: i64,
     repos_ctx: repositories::DataPipelineGitHubRepos,
     workflows_ctx: workflows::DataPipelineGitHubWorkflows,
 }
 
 impl DataPipelineGitHub {
-    /// Program constructor.
-    fn new() -> DataPipelineGitHub {
-        let configuration = DataPipelineGitHubFsConfiguration {
-            repos_output: String::from("/.data/output/github/repos"),
-            workflows_output: String::from("/.data/output/github/workflows"),
-        };
+    /// GitHub client constructor with the `data_pipeline` configuration.
+    pub fn new(token: &str, config: &DataPipelineConfig) -> DataPipelineGitHub {
         let repos_ctx = repositories::DataPipelineGitHubRepos {
+            token: token.to_owned(),
             rate_limit_handler: rate_limit_handler::GitHubRateLimitHandler,
         };
         let workflows_ctx = workflows::DataPipelineGitHubWorkflows {
+            token: token.to_owned(),
             rate_limit_handler: rate_limit_handler::GitHubRateLimitHandler,
         };
         DataPipelineGitHub {
-            configuration,
+            configuration: DataPipelineGitHubFsConfiguration::new(&config.github_output_path),
+            repos_per_page: config.repos_per_page,
+            workflow_runs_per_page: config.workflow_runs_per_page,
             repos_ctx,
             workflows_ctx,
         }
     }
 
+    /// Sets the output directories of the collectors.
+    pub fn with_configuration(mut self, configuration: DataPipelineGitHubFsConfiguration) -> Self {
+        self.configuration = configuration;
+        self
+    }
+
     /// GitHub repositories request.
     pub async fn repos_request(
         &self,
@@ -76,4 +126,146 @@ impl DataPipelineGitHub {
             .workflow_runs_request(owner, repo, branch, created, per_page, page)
             .await
     }
+
+    /// Collects metadata of the user repositories to the repos output directory.
+    /// The progress is reported after every page.
+    /// Returns the number of collected repositories.
+    pub async fn collect_repos(&self, user: &str, report: impl Fn(&Progress)) -> Result<i64> {
+        let search_term = user.trim();
+
+        log::info!("collecting the repositories of {:?}", search_term);
+
+        let query_string = "  in:name in:description in:readme user:".to_string() + search_term;
+        let base_path = &self.configuration.repos_output;
+        create_dir_all(base_path)?;
+
+        let mut page = 0;
+
+        loop {
+            page += 1;
+
+            let data = self
+                .repos_request(
+                    &query_string,
+                    SearchReposSort::Noop,
+                    Order::Asc,
+                    self.repos_per_page,
+                    page,
+                )
+                .await?;
+
+            if data.retry {
+                page -= 1;
+                continue;
+            }
+
+            let path = base_path.join("github-repos-".to_string() + &page.to_string() + ".json");
+            write(&path, &data.items)?;
+            let total = data.total;
+
+            let progress = (page * self.repos_per_page).min(total);
+            report(&Progress {
+                collection: Collection::Repos,
+                progress,
+                total,
+            });
+
+            if total <= progress {
+                log::info!("collected {} repositories", total);
+                return Ok(total);
+            }
+        }
+    }
+
+    /// Collects workflow runs metadata of the collected repositories to the workflows output directory.
+    /// The progress is reported after every repository.
+    /// Returns the number of processed repositories.
+    pub async fn collect_workflows(&self, report: impl Fn(&Progress)) -> Result<usize> {
+        let records = self.collect_documents()?;
+        let records_len = records.len();
+        let base_path = &self.configuration.workflows_output;
+        create_dir_all(base_path)?;
+
+        let mut record_index = 0;
+
+        while record_index < records_len {
+            let record = &records[record_index];
+
+            let owner = &record
+                .owner
+                .as_ref()
+                .ok_or_else(|| GitHubError::MissingOwner(record.full_name.clone()))?
+                .login;
+            log::debug!("owner {:?}", owner);
+            let repo = &record.name;
+            log::debug!("repo {:?}", repo);
+            let branch = &record.default_branch;
+            log::debug!("branch {:?}", branch);
+
+            let data = self
+                .workflow_runs_request(owner, repo, branch, "", self.workflow_runs_per_page, 1)
+                .await?;
+
+            if data.retry {
+                continue;
+            }
+
+            if !data.items.is_empty() {
+                let path = base_path.join(record.name.clone() + ".json");
+                write(&path, &data.items)?;
+            }
+            record_index += 1;
+
+            log::debug!("workflow runs {:?}", data.total);
+            report(&Progress {
+                collection: Collection::Workflows,
+                progress: record_index as i64,
+                total: records_len as i64,
+            });
+        }
+
+        Ok(records_len)
+    }
+
+    /// Collects repository records for the repository workflow collector.
+    pub fn collect_documents(&self) -> Result<Vec<Repository>> {
+        let base_path = &self.configuration.repos_output;
+        log::debug!("base path {:?}", base_path);
+
+        let mut docs: Vec<Repository> = vec![];
+
+        let read_dir = fs::read_dir(base_path)
+            .map_err(|error| FsError::new("read the directory", base_path, error))?;
+        for dir_entry in read_dir {
+            let dir_entry =
+                dir_entry.map_err(|error| FsError::new("read the directory", base_path, error))?;
+            log::debug!("dir entry {:?}", dir_entry);
+
+            let file_content = fs::read_to_string(dir_entry.path())
+                .map_err(|error| FsError::new("read the file", dir_entry.path(), error))?;
+
+            let parse_result = serde_json::from_str::<Vec<Repository>>(&file_content);
+            if let Ok(mut json) = parse_result {
+                docs.append(&mut json);
+            } else {
+                log::warn!("can't deserialize the JSON file {:?}", dir_entry.path());
+            }
+        }
+        Ok(docs)
+    }
+}
+
+/// Creates the output directory.
+fn create_dir_all(path: &Path) -> Result<()> {
+    fs::create_dir_all(path)
+        .map_err(|error| FsError::new("create the directory", path, error).into())
+}
+
+/// Writes the collected data to the JSON file.
+fn write<T: serde::Serialize>(path: &Path, data: &T) -> Result<()> {
+    let file = File::create(path).map_err(|error| FsError::new("create the file", path, error))?;
+    serde_json::to_writer_pretty(file, data).map_err(|source| GitHubError::Write {
+        path: path.to_owned(),
+        source,
+    })
 }
@@ -1,34 +1,24 @@
 //! GitHub API rate limit handler submodule for the data pipeline.
 
-use colored::Colorize;
 use octorust::ClientError;
-use std::process::Command;
+use std::{io, process::Command};
 
 pub struct GitHubRateLimitHandler;
 
 impl GitHubRateLimitHandler {
     /// Sleep for N seconds defined by the `wait_timeout` value.
-    pub fn sleep_for(&self, wait_timeout: i64) {
-        match Command::new("sleep").arg(wait_timeout.to_string()).spawn() {
-            Ok(mut child) => {
-                println!("\n{}", "Can sleep".bold().green());
-                match child.wait() {
-                    Ok(exit_status) => {
-                        println!("{}: {:?}", "Sleep success".bold().green(), exit_status);
-                    }
-                    Err(err) => {
-                        panic!("{}\n{:?}", "Sleep error".bold().red(), err);
-                    }
-                }
-            }
-            Err(err) => {
-                panic!("{}\n{:?}", "Can't sleep".bold().red(), err);
-            }
-        }
+    pub fn sleep_for(&self, wait_timeout: i64) -> io::Result<()> {
+        let mut child = Command::new("sleep")
+            .arg(wait_timeout.to_string())
+            .spawn()?;
+        log::debug!("waiting for the rate limit reset");
+        let exit_status = child.wait()?;
+        log::debug!("the rate limit wait exited with {:?}", exit_status);
+        Ok(())
     }
 
     /// Process GitHub API `ClientError` and derive the value of the `wait_timeout` variable.
-    pub fn error_handler(&self, error: ClientError) -> i64 {
+    pub fn error_handler(&self, error: &ClientError) -> i64 {
         let err = error.to_string();
 
         let rate_limit_regx =
@@ -44,23 +34,18 @@ impl GitHubRateLimitHandler {
         });
         let wait_timeout = match captures.as_deref() {
             Some(["Rate limited for the next", x, "seconds"]) => {
-                let x: i64 = x.parse().expect("Can't parse a number");
-                x
+                let wait_timeout = x.parse::<i64>().unwrap_or_default();
+                log::warn!(
+                    "the GitHub API rate limit is hit, waiting for {} seconds",
+                    wait_timeout
+                );
+                wait_timeout
             }
             _ => {
-                println!(
-                    "{}\n{:?}",
-                    "It looks like the root cause of the error is not a rate limit hit.".cyan(),
-                    &err
-                );
+                log::warn!("the GitHub error is not a rate limit hit: {:?}", &err);
                 0
             }
         };
-        println!(
-            "{}: {:?}",
-            "GitHub API rate limit hit. Have to wait for".red(),
-            wait_timeout
-        );
 
         wait_timeout
     }
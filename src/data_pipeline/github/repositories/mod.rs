@@ -1,18 +1,13 @@
 //! GitHubRepos submodule for the data pipeline.
 
-use colored::Colorize;
+use super::rate_limit_handler::GitHubRateLimitHandler;
+use super::{GitHubError, Result};
 use octorust::{
     auth::Credentials,
     types::SearchReposSort,
     types::{Order, RepoSearchResultItem},
     Client,
 };
-use std::env::{self};
-
-use super::rate_limit_handler::GitHubRateLimitHandler;
-
-/// Custom result type for fetch results.
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
 /// GitHub repos fetch result.
 pub struct ReposFetchResult {
@@ -22,6 +17,7 @@ pub struct ReposFetchResult {
 }
 
 pub struct DataPipelineGitHubRepos {
+    pub token: String,
     pub rate_limit_handler: GitHubRateLimitHandler,
 }
 
@@ -35,60 +31,48 @@ impl DataPipelineGitHubRepos {
         per_page: i64,
         page: i64,
     ) -> Result<ReposFetchResult> {
-        let token_env = env::var("GITHUB_TOKEN");
-        let token = match token_env.unwrap().trim().parse::<String>() {
-            Ok(value) => value,
-            Err(_) => String::new(),
-        };
+        let client = Client::new(
+            String::from("user-agent-name"),
+            Credentials::Token(self.token.trim().to_string()),
+        )
+        .map_err(|error| GitHubError::Client(Box::new(error)))?;
 
-        let github = Client::new(String::from("user-agent-name"), Credentials::Token(token));
-
-        let mut retry: bool = false;
-
-        let client = github.unwrap();
         let search = client.search();
         let result = search.repos(q, sort, order, per_page, page).await;
-        let raw_res = match result {
-            Ok(res) => Ok(res),
+        let res = match result {
+            Ok(res) => res,
             Err(error) => {
-                println!(
-                    "\n{}: {:?}",
-                    "There was an error getting data from GitHub".red(),
-                    error
-                );
+                log::warn!("can't get the data from GitHub: {:?}", error);
 
-                let wait_timeout = self.rate_limit_handler.error_handler(error);
-                if wait_timeout > 0 {
-                    retry = true;
-                    self.rate_limit_handler.sleep_for(wait_timeout);
+                let wait_timeout = self.rate_limit_handler.error_handler(&error);
+                if wait_timeout == 0 {
+                    return Err(GitHubError::Request(Box::new(error)));
                 }
 
-                Err(())
+                self.rate_limit_handler
+                    .sleep_for(wait_timeout)
+                    .map_err(GitHubError::RateLimitWait)?;
+
+                let result = ReposFetchResult {
+                    items: Vec::<RepoSearchResultItem>::new(),
+                    total: 0,
+                    retry: true,
+                };
+                return Ok(result);
             }
         };
 
-        if retry {
-            let result = ReposFetchResult {
-                items: Vec::<RepoSearchResultItem>::new(),
-                total: 0,
-                retry: true,
-            };
-            return Ok(result);
-        }
-
-        let res = raw_res.unwrap();
         let body = res.body.to_owned();
         let items = body.items;
         for item in items {
             let name = item.full_name;
-            println!("\n{}: {}", "Data".cyan(), name);
+            log::debug!("data: {}", name);
         }
 
-        println!("{}: {}", "Response".green(), res.status);
-        println!("{}: {:#?}\n", "Headers".green(), res.headers);
-        // println!("{}: {:#?}\n", "Body".green(), res.body);
+        log::debug!("response: {}", res.status);
+        log::debug!("headers {:?}", res.headers);
 
-        println!("\n\n{}", "Done!".green().bold());
+        log::debug!("the request is done");
 
         let items = res.body.items.to_owned();
         let total = res.body.total_count.to_owned();
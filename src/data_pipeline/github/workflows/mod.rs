@@ -1,13 +1,8 @@
 //! GitHubWorkflows module for the data pipeline.
 
-use colored::Colorize;
-use octorust::{auth::Credentials, types::WorkflowRun, types::WorkflowRunStatus, Client};
-use std::env::{self};
-
 use super::rate_limit_handler::GitHubRateLimitHandler;
-
-/// Custom result type for fetch results.
-type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
+use super::{GitHubError, Result};
+use octorust::{auth::Credentials, types::WorkflowRun, types::WorkflowRunStatus, Client};
 
 /// GitHub repo workflow runs fetch result.
 pub struct WorkflowRunsFetchResult {
@@ -17,6 +12,7 @@ pub struct WorkflowRunsFetchResult {
 }
 
 pub struct DataPipelineGitHubWorkflows {
+    pub token: String,
     pub rate_limit_handler: GitHubRateLimitHandler,
 }
 
@@ -31,17 +27,12 @@ impl DataPipelineGitHubWorkflows {
         per_page: i64,
         page: i64,
     ) -> Result<WorkflowRunsFetchResult> {
-        let token_env = env::var("GITHUB_TOKEN");
-        let token = match token_env.unwrap().trim().parse::<String>() {
-            Ok(value) => value,
-            Err(_) => String::new(),
-        };
+        let client = Client::new(
+            String::from("user-agent-name"),
+            Credentials::Token(self.token.trim().to_string()),
+        )
+        .map_err(|error| GitHubError::Client(Box::new(error)))?;
 
-        let github = Client::new(String::from("user-agent-name"), Credentials::Token(token));
-
-        let mut retry: bool = false;
-
-        let client = github.unwrap();
         let actions = client.actions();
         let result = actions
             .list_workflow_runs_for_repo(
@@ -56,47 +47,40 @@ impl DataPipelineGitHubWorkflows {
                 created,
             )
             .await;
-        let raw_res = match result {
-            Ok(res) => Ok(res),
+        let res = match result {
+            Ok(res) => res,
             Err(error) => {
-                println!(
-                    "\n{}: {:?}",
-                    "There was an error getting data from GitHub".red(),
-                    error
-                );
+                log::warn!("can't get the data from GitHub: {:?}", error);
 
-                let wait_timeout = self.rate_limit_handler.error_handler(error);
-                if wait_timeout > 0 {
-                    retry = true;
-                    self.rate_limit_handler.sleep_for(wait_timeout);
+                let wait_timeout = self.rate_limit_handler.error_handler(&error);
+                if wait_timeout == 0 {
+                    return Err(GitHubError::Request(Box::new(error)));
                 }
 
-                Err(())
+                self.rate_limit_handler
+                    .sleep_for(wait_timeout)
+                    .map_err(GitHubError::RateLimitWait)?;
+
+                let result = WorkflowRunsFetchResult {
+                    items: Vec::<WorkflowRun>::new(),
+                    total: 0,
+                    retry: true,
+                };
+                return Ok(result);
             }
         };
 
-        if retry {
-            let result = WorkflowRunsFetchResult {
-                items: Vec::<WorkflowRun>::new(),
-                total: 0,
-                retry: true,
-            };
-            return Ok(result);
-        }
-
-        let res = raw_res.unwrap();
         let body = res.body.to_owned();
         let items = body.workflow_runs;
         for item in items {
             let name = item.name;
-            println!("\n{}: {}", "Data".cyan(), name);
+            log::debug!("data: {}", name);
         }
 
-        println!("{}: {}", "Response".green(), res.status);
-        println!("{}: {:#?}\n", "Headers".green(), res.headers);
-        // println!("{}: {:#?}\n", "Body".green(), res.body);
+        log::debug!("response: {}", res.status);
+        log::debug!("headers {:?}", res.headers);
 
-        println!("\n\n{}", "Done!".green().bold());
+        log::debug!("the request is done");
 
         let items = res.body.workflow_runs.to_owned();
         let total = res.body.total_count.to_owned();
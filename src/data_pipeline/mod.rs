@@ -6,333 +6,357 @@
 //! GPG_PASSPHRASE=...
 //! MONGODB_CONNECTION_STRING=...
 //! MONGODB_DATABASE=...
+//!
+//! `run` doesn't read the configuration, the environment or the console: the CLI loads the options
+//! and prints the progress and the outcome, and the server runs it as background jobs.
 
+use clap::ValueEnum;
 use colored::Colorize;
-use octorust::{
-    types::SearchReposSort,
-    types::{Order, RepoSearchResultItem, Repository, WorkflowRun},
-};
-use std::{
-    env,
-    fs::{self, File},
-    io,
-};
-
-mod artifact;
-mod configuration;
-mod environment;
-mod github;
-mod mongo;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, env::VarError, fmt};
+
+use crate::config::{self, DataPipelineConfig};
+use crate::program::{Entry, Registry};
+use crate::{output, prompt};
 
-/// The entry point of the program.
-pub fn main() {
-    DataPipeline::new();
+pub mod artifact;
+mod configuration;
+pub mod environment;
+pub mod error;
+pub mod github;
+pub mod mongo;
+
+pub use self::configuration::{CollectArguments, RestoreArguments};
+pub use self::environment::EnvironmentError;
+pub use self::error::{DataPipelineError, ErrorCategory};
+
+/// Custom result type for the data pipeline.
+type Result<T> = std::result::Result<T, DataPipelineError>;
+
+/// The contexts of the program, in the order of the interactive menu.
+pub fn programs() -> Registry {
+    Registry::new("context")
+        .register(Entry::new(
+            "collect",
+            "Collect GitHub data, create an encrypted artifact, and import the data into MongoDB",
+            collect,
+        ))
+        .register(Entry::new(
+            "restore",
+            "Restore an encrypted artifact and import the data into MongoDB",
+            restore,
+        ))
 }
 
-struct DataPipeline<'a> {
-    contexts: artifact::Contexts<'a>,
-    collections: mongo::Collections<'a>,
-    configuration: configuration::DataPipelineConfiguration<'a>,
-    github: github::DataPipelineGitHub,
+/// The contexts of the data pipeline.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Context {
+    /// Collects GitHub data before creating the artifact.
+    Collect,
+    /// Restores an existing artifact.
+    Restore,
 }
 
-impl<'a> DataPipeline<'a> {
-    /// Program constructor.
-    fn new() -> DataPipeline<'a> {
-        let contexts: artifact::Contexts = artifact::CONTEXTS;
-        let collections: mongo::Collections = mongo::COLLECTIONS;
-        let configuration: configuration::DataPipelineConfiguration =
-            configuration::main(contexts, collections);
-        let github: github::DataPipelineGitHub = github::main();
-        let mut program = DataPipeline {
-            contexts,
-            collections,
-            configuration,
-            github,
-        };
-        program.init();
-        program
+impl Context {
+    /// The artifact operation of the data pipeline context.
+    fn artifact_operation(self) -> artifact::Operation {
+        match self {
+            Context::Collect => artifact::Operation::Create,
+            Context::Restore => artifact::Operation::Restore,
+        }
     }
+}
 
-    /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "DataPipeline initialized.".blue().bold());
+/// The collections of the data pipeline, in the order of the interactive menu.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Collection {
+    /// The metadata of the repositories of a GitHub user.
+    Repos,
+    /// The workflow runs of the collected repositories.
+    Workflows,
+}
 
-        environment::main(None);
+impl Collection {
+    /// All the collections, in the order of the interactive menu.
+    pub const ALL: [Collection; 2] = [Collection::Repos, Collection::Workflows];
 
-        let args = self.configuration.args();
+    /// The name of the collection, used in the paths, the menu and the arguments.
+    pub fn name(self) -> &'static str {
+        match self {
+            Collection::Repos => "repos",
+            Collection::Workflows => "workflows",
+        }
+    }
 
-        let context_arg = args.context.to_owned();
+    /// The description of the collection, used in the menu.
+    pub fn description(self) -> &'static str {
+        match self {
+            Collection::Repos => "The metadata of the repositories of a GitHub user",
+            Collection::Workflows => "The workflow runs of the collected repositories",
+        }
+    }
+}
 
-        let context_index = self.configuration.choose_context(context_arg);
+impl fmt::Display for Collection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
 
-        let context = self.contexts[context_index];
+/// The secrets of the data pipeline; a missing secret fails the run only if the context needs it.
+#[derive(Clone, Default)]
+pub struct Credentials {
+    pub github_token: Option<String>,
+    pub gpg_passphrase: Option<String>,
+    pub mongodb_connection_string: Option<String>,
+    pub mongodb_database: Option<String>,
+}
 
-        let collection_arg = args.collection.to_owned();
+impl Credentials {
+    /// The credentials of the env file variables, or of the process environment.
+    pub fn new(env: &HashMap<String, String>) -> Credentials {
+        let var = |key| environment::var(env, key).ok();
+        Credentials {
+            github_token: var("GITHUB_TOKEN"),
+            gpg_passphrase: var("GPG_PASSPHRASE"),
+            mongodb_connection_string: var("MONGODB_CONNECTION_STRING"),
+            mongodb_database: var("MONGODB_DATABASE"),
+        }
+    }
+}
 
-        let collection_index = self.configuration.choose_collection(collection_arg);
+/// The credentials and the paths of a data pipeline run.
+#[derive(Clone, Default)]
+pub struct Options {
+    pub credentials: Credentials,
+    /// The paths and the page sizes; the env file is not used by `run`.
+    pub config: DataPipelineConfig,
+}
 
-        let collection = self.collections[collection_index];
+/// Loads the options of the configuration, with the credentials of its env file.
+/// The secret values are registered for redaction.
+pub fn options(config: &DataPipelineConfig) -> Result<Options> {
+    let env = environment::main(Some(config.env_file.clone()))?;
+    Ok(Options {
+        credentials: Credentials::new(&env),
+        config: config.clone(),
+    })
+}
 
-        match context_index {
-            0 => self.execute(args.search_term, context.to_owned(), collection.to_owned()),
-            1 => {
-                artifact::main(
-                    self.contexts,
-                    Some(context.to_owned()),
-                    collection.to_owned(),
-                );
-                mongo::main(self.collections, Some(collection.to_owned()));
-            }
-            _ => {
-                println!(
-                    "\n{}",
-                    "Nothing to execute. The context is not supported"
-                        .red()
-                        .bold()
-                )
-            }
-        }
-    }
+/// The progress of the GitHub data collection, the `data_pipeline.progress` output record.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Progress {
+    pub collection: Collection,
+    pub progress: i64,
+    pub total: i64,
+}
 
-    /// The data pipeline program for the provided search_term.
-    fn execute(&mut self, search_term_arg: Option<String>, context: String, collection: String) {
-        let is_some = search_term_arg.is_some();
-        let search_term_arg_input = if is_some {
-            match search_term_arg.unwrap().trim().parse::<String>() {
-                Ok(value) => value,
-                Err(_) => String::new(),
-            }
-        } else {
-            String::new()
-        };
+/// The outcome of a data pipeline run.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// The path of the encrypted artifact if created, or the decrypted artifact if restored.
+    pub artifact: String,
+    /// The number of imported documents.
+    pub documents: usize,
+}
 
-        let mut search_term_input = String::new();
+/// The `data_pipeline.artifact` output record.
+#[derive(Serialize)]
+struct ArtifactRecord<'a> {
+    /// One of `create`, `restore`.
+    context: &'static str,
+    collection: Collection,
+    /// The path of the encrypted artifact if created, or the decrypted artifact if restored.
+    path: &'a str,
+}
 
-        if search_term_arg_input.trim().is_empty() && search_term_input.trim().is_empty() {
-            println!(
-                "\n{}",
-                "Please input a search term (GitHub user):".yellow().bold()
-            );
+/// The `data_pipeline.import` output record.
+#[derive(Serialize)]
+struct ImportRecord {
+    collection: Collection,
+    /// The number of imported documents.
+    documents: usize,
+}
 
-            io::stdin()
-                .read_line(&mut search_term_input)
-                .expect("Failed to read line");
-        } else if search_term_input.trim().is_empty() {
-            search_term_input = search_term_arg_input;
+/// Runs the data pipeline context for the collection with the options, without prompting or printing.
+/// The search term (GitHub user) is only used by the collect context,
+/// and the progress of the GitHub data collection is reported to `progress`.
+pub fn run(
+    context: Context,
+    collection: Collection,
+    search_term: &str,
+    options: &Options,
+    progress: impl Fn(&Progress),
+) -> Result<Report> {
+    let credentials = &options.credentials;
+
+    if context == Context::Collect {
+        let token = required(&credentials.github_token, "GITHUB_TOKEN")?;
+        collect_github_data(collection, search_term, token, options, progress)?;
+    }
+
+    let artifact = artifact::execute(
+        context.artifact_operation(),
+        collection,
+        required(&credentials.gpg_passphrase, "GPG_PASSPHRASE")?,
+        &options.config,
+    )?;
+
+    let db = mongo::connect(
+        required(
+            &credentials.mongodb_connection_string,
+            "MONGODB_CONNECTION_STRING",
+        )?,
+        required(&credentials.mongodb_database, "MONGODB_DATABASE")?,
+    )?;
+    let documents = mongo::import(&db, collection, &options.config)?;
+
+    Ok(Report {
+        artifact,
+        documents,
+    })
+}
+
+/// The credential, or the missing variable error.
+fn required<'a>(credential: &'a Option<String>, key: &str) -> Result<&'a str> {
+    credential.as_deref().ok_or_else(|| {
+        EnvironmentError::MissingVariable {
+            key: key.to_owned(),
+            source: VarError::NotPresent,
         }
+        .into()
+    })
+}
 
-        let col = collection.as_str();
-
-        match col {
-            "repos" => self.execute_repos_collector(search_term_input.clone()),
-            "workflows" => self.execute_workflows_collector(),
-            _ => panic!(
-                "\n{}: {:?}",
-                "Nothing to execute. The collection is not supported"
-                    .red()
-                    .bold(),
-                col
-            ),
+/// Collects the GitHub data of the collection for the search term.
+fn collect_github_data(
+    collection: Collection,
+    search_term: &str,
+    token: &str,
+    options: &Options,
+    progress: impl Fn(&Progress),
+) -> Result<()> {
+    let github = github::DataPipelineGitHub::new(token, &options.config);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(DataPipelineError::Runtime)?;
+
+    match collection {
+        Collection::Repos => {
+            runtime.block_on(github.collect_repos(search_term, progress))?;
         }
+        Collection::Workflows => {
+            runtime.block_on(github.collect_workflows(progress))?;
+        }
+    }
+
+    Ok(())
+}
 
-        artifact::main(self.contexts, Some(context), collection.to_owned());
-        mongo::main(self.collections, Some(collection));
+/// The entry point of the collect context.
+pub fn collect(args: CollectArguments) {
+    DataPipeline::new(Context::Collect, args.collection, args.user);
+}
+
+/// The entry point of the restore context.
+pub fn restore(args: RestoreArguments) {
+    DataPipeline::new(Context::Restore, args.collection, None);
+}
+
+struct DataPipeline;
+
+impl DataPipeline {
+    /// Program constructor.
+    fn new(
+        context: Context,
+        collection: Option<Collection>,
+        search_term: Option<String>,
+    ) -> DataPipeline {
+        let mut program = DataPipeline;
+        program.init(context, collection, search_term);
+        program
     }
 
-    /// Collects repository metadata.
-    fn execute_repos_collector(&self, search_term_input: String) {
-        let mut page = 0;
-
-        loop {
-            let search_term = search_term_input.as_str().trim().to_string();
-
-            println!("\n{}: {}", "Your search term".cyan(), search_term);
-
-            let query_string =
-                "  in:name in:description in:readme user:".to_string() + search_term.as_str();
-            let q = query_string.as_str();
-            let per_page = 5;
-            page += 1;
-
-            let mut fetch_result = github::ReposFetchResult {
-                items: Vec::<RepoSearchResultItem>::new(),
-                total: 0,
-                retry: false,
-            };
-
-            let runtime = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-
-            runtime.block_on(async {
-                let result = self
-                    .github
-                    .repos_request(q, SearchReposSort::Noop, Order::Asc, per_page, page)
-                    .await;
-                fetch_result = match result {
-                    Ok(data) => {
-                        if data.retry {
-                            page -= 1;
-                        } else {
-                            let cwd = env::current_dir().unwrap();
-                            println!("The current directory is {}", cwd.display());
-                            let base_path = cwd.display().to_string()
-                                + self.github.configuration.repos_output.as_str();
-                            let create_dir_result = fs::create_dir_all(&base_path);
-                            if let Ok(_tmp) = create_dir_result {
-                                let path =
-                                    base_path + "/github-repos-" + &page.to_string() + ".json";
-                                let file = File::create(path).unwrap();
-                                let _result = serde_json::to_writer_pretty(file, &data.items);
-                            }
-                        }
-
-                        data
-                    }
-                    Err(error) => {
-                        println!("\n{}: {:?}", "There was an error".red(), error);
-                        let items = Vec::<RepoSearchResultItem>::new();
-                        let total: i64 = 0;
-                        github::ReposFetchResult {
-                            items,
-                            total,
-                            retry: false,
-                        }
-                    }
-                };
-            });
-
-            let progress = page * per_page;
-            println!(
-                "\n{}: {:?}/{:?}",
-                "Progress/Total".green().bold(),
-                progress,
-                fetch_result.total
-            );
+    /// Initializes the program.
+    fn init(
+        &mut self,
+        context: Context,
+        collection: Option<Collection>,
+        search_term: Option<String>,
+    ) {
+        human_println!("\n{}", "DataPipeline initialized.".blue().bold());
+
+        let collection = configuration::choose_collection(collection);
+
+        let search_term = match context {
+            Context::Collect => self.choose_search_term(search_term),
+            Context::Restore => String::new(),
+        };
 
-            if fetch_result.total > progress {
-                continue;
-            } else {
-                println!("\n{}", "Download complete".green().bold());
-                break;
-            }
+        if let Err(error) = self.execute(context, collection, &search_term) {
+            error::report(&error);
         }
     }
 
-    /// Collects repository workflow metadata.
-    fn execute_workflows_collector(&self) {
-        let records = self.collect_documents();
-        let mut record_index = 0;
-        let records_len = records.len();
-
-        while record_index < records_len {
-            let record = &records[record_index];
-            let mut fetch_result = github::WorkflowRunsFetchResult {
-                items: Vec::<WorkflowRun>::new(),
-                total: 0,
-                retry: false,
-            };
-
-            let runtime = tokio::runtime::Builder::new_current_thread()
-                .enable_all()
-                .build()
-                .unwrap();
-
-            let owner = &record.owner.clone().unwrap().login;
-            println!("owner {:?}", owner);
-            let repo = &record.name;
-            println!("repo {:?}", repo);
-            let branch = &record.default_branch;
-            println!("branch {:?}", branch);
-
-            runtime.block_on(async {
-                let result = self
-                    .github
-                    .workflow_runs_request(owner, repo, branch, "", 100, 1)
-                    .await;
-                fetch_result = match result {
-                    Ok(data) => {
-                        if data.retry {
-                            record_index -= 1;
-                        } else {
-                            let cwd = env::current_dir().unwrap();
-                            println!("The current directory is {}", cwd.display());
-                            let base_path = cwd.display().to_string()
-                                + self.github.configuration.workflows_output.as_str();
-                            let create_dir_result = fs::create_dir_all(&base_path);
-                            if let Ok(_tmp) = create_dir_result {
-                                if !data.items.is_empty() {
-                                    let path = base_path + "/" + &record.name + ".json";
-                                    let file = File::create(path).unwrap();
-                                    let _result = serde_json::to_writer_pretty(file, &data.items);
-                                }
-                            }
-                            record_index += 1;
-                        }
-                        data
-                    }
-                    Err(error) => {
-                        println!("\n{}: {:?}", "There was an error".red(), error);
-                        let items = Vec::<WorkflowRun>::new();
-                        let total: i64 = 0;
-                        github::WorkflowRunsFetchResult {
-                            items,
-                            total,
-                            retry: false,
-                        }
-                    }
-                };
-            });
-
-            println!(
+    /// Runs the data pipeline with the options of the configuration, and prints the progress and the outcome.
+    fn execute(&self, context: Context, collection: Collection, search_term: &str) -> Result<()> {
+        let options = options(&config::get().data_pipeline)?;
+
+        let report = run(context, collection, search_term, &options, |progress| {
+            human_println!(
                 "\n{}: {:?}/{:?}",
                 "Progress/Total".green().bold(),
-                record_index,
-                records_len
+                progress.progress,
+                progress.total
             );
-        }
+            output::emit("data_pipeline.progress", progress);
+        })?;
+
+        human_println!("\n{}: {}", "Artifact".green().bold(), report.artifact);
+        output::emit(
+            "data_pipeline.artifact",
+            &ArtifactRecord {
+                context: context.artifact_operation().name(),
+                collection,
+                path: &report.artifact,
+            },
+        );
+        human_println!(
+            "{}: {:?}",
+            "Imported documents".green().bold(),
+            report.documents
+        );
+        output::emit(
+            "data_pipeline.import",
+            &ImportRecord {
+                collection,
+                documents: report.documents,
+            },
+        );
+
+        Ok(())
     }
 
-    /// Collects repository records for the repository workflow collector.
-    fn collect_documents(&self) -> Vec<Repository> {
-        let cwd = env::current_dir().unwrap();
-        println!(
-            "\n{}:\n{:?}",
-            "The current directory is".cyan().bold(),
-            cwd.display()
-        );
-        let json_data_dir = self.github.configuration.repos_output.as_str();
-        let base_path = cwd.display().to_string() + json_data_dir;
-        println!("\n{}:\n{:?}", "Base path".cyan().bold(), base_path);
-        let dir_content_result = fs::read_dir(&base_path);
+    /// Prompts the search term (GitHub user) if it is not provided.
+    fn choose_search_term(&mut self, search_term_arg: Option<String>) -> String {
+        let search_term_arg_input = search_term_arg.unwrap_or_default();
 
-        let Ok(dir_content) = dir_content_result else {
-            panic!("\n{} {:?}", "Can't read directory".red().bold(), base_path);
-        };
+        if search_term_arg_input.trim().is_empty() {
+            human_println!(
+                "\n{}",
+                "Please input a search term (GitHub user):".yellow().bold()
+            );
 
-        let mut docs: Vec<Repository> = vec![];
-
-        let dir_entries = dir_content.enumerate();
-        for (_i, dir_entries_result) in dir_entries {
-            let Ok(dir_entry) = dir_entries_result else {
-                panic!("\n{}: {:?}", "Can't get dir entry", dir_entries_result);
-            };
-            println!("\n{}: {:?}", "Dir entry".green().bold(), dir_entry);
-
-            let file_content_result = fs::read_to_string(dir_entry.path());
-            let Ok(file_content) = file_content_result else {
-                panic!("\n{}: {:?}", "Can't get file content", file_content_result);
-            };
-
-            let parse_result = serde_json::from_str::<Vec<Repository>>(&file_content);
-            if let Ok(mut json) = parse_result {
-                docs.append(&mut json);
-            } else {
-                println!("Error serializing JSON file: {:?}", dir_entry.path());
-            }
+            prompt::read_line("A search term (--user)")
+        } else {
+            search_term_arg_input
         }
-        docs
     }
 }
+
+#[cfg(test)]
+mod tests;
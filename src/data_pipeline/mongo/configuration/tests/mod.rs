@@ -1,24 +1,15 @@
 mod mongo_db_configuration {
-    use crate::data_pipeline::mongo::{configuration::MongoDbConfiguration, COLLECTIONS};
+    use crate::config::DataPipelineConfig;
+    use crate::data_pipeline::{mongo::configuration::fs_config, Collection};
 
     #[test]
-    fn collections() {
-        let program = MongoDbConfiguration::new(COLLECTIONS);
-        let collections = program.collections;
-        for (i, record) in collections.iter().enumerate() {
-            let index = program.choose_collection(Some(record.to_string()));
-            assert_eq!(i, index);
-        }
-    }
+    fn fs_config_of_collections() {
+        for collection in Collection::ALL {
+            let config = fs_config(collection, &DataPipelineConfig::default()).unwrap();
 
-    #[test]
-    fn fs_config() {
-        let program = MongoDbConfiguration::new(COLLECTIONS);
-        let collection = String::from("test");
-        let config = program.fs_config(collection.clone());
-
-        let json_base_path = String::from("/.data/output/github/");
-        let partial_json_data_dir = json_base_path + &collection + "/";
-        assert!(config.json_data_dir.contains(&partial_json_data_dir));
+            let json_base_path = String::from("/.data/output/github/");
+            let partial_json_data_dir = json_base_path + collection.name() + "/";
+            assert!(config.json_data_dir.contains(&partial_json_data_dir));
+        }
     }
 }
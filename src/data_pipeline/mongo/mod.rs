@@ -1,68 +1,69 @@
 //! MongoDB module for the data pipeline.
 
-use colored::Colorize;
+use mongodb::sync::{Client, Database};
+use std::path::PathBuf;
+use thiserror::Error;
+
+use super::{error::FsError, Collection};
+use crate::config::DataPipelineConfig;
+
+pub use self::repos_collection::MongoDbReposCollection;
+pub use self::workflows_collection::MongoDbWorkflowsCollection;
 
 mod configuration;
 mod repos_collection;
 mod workflows_collection;
 
-/// The entry point of the program.
-pub fn main(collections: Collections, collection_arg: Option<String>) {
-    DataPipelineMongoDb::new(collections, collection_arg);
+/// MongoDB import failure.
+#[derive(Debug, Error)]
+pub enum MongoDbError {
+    #[error("can't connect to MongoDB")]
+    Connect(#[source] mongodb::error::Error),
+    #[error("can't list the collections of the database")]
+    ListCollections(#[source] mongodb::error::Error),
+    #[error("can't convert the document to BSON")]
+    Bson(#[from] mongodb::bson::ser::Error),
+    #[error("the data directory {data_dir:?} does not match the collection {collection:?}")]
+    DataDir {
+        data_dir: PathBuf,
+        collection: String,
+    },
+    #[error(transparent)]
+    Fs(#[from] FsError),
 }
 
-/// Supported collections.
-pub type Collections<'a> = [&'a str; 2];
-/// Supported collections.
-pub const COLLECTIONS: Collections = ["repos", "workflows"];
+/// Custom result type for MongoDB operations.
+type Result<T> = std::result::Result<T, MongoDbError>;
 
-struct DataPipelineMongoDb<'a> {
-    collections: Collections<'a>,
-}
+/// Connects to the MongoDB instance and returns the database reference.
+pub fn connect(connection_url: &str, db_name: &str) -> Result<Database> {
+    let client = Client::with_uri_str(connection_url.trim()).map_err(MongoDbError::Connect)?;
 
-impl<'a> DataPipelineMongoDb<'a> {
-    /// Program constructor.
-    fn new(collections: Collections, collection_arg: Option<String>) -> DataPipelineMongoDb {
-        let mut program = DataPipelineMongoDb { collections };
-        program.init(collection_arg);
-        program
-    }
+    let db = client.database(db_name.trim());
 
-    /// Initializes the program.
-    fn init(&mut self, collection_arg: Option<String>) {
-        println!("\n{}", "DataPipelineMongoDb initialized.".blue().bold());
+    for col in db
+        .list_collection_names(None)
+        .map_err(MongoDbError::Connect)?
+    {
+        log::debug!("collection {:?}", col);
+    }
 
-        println!(
-            "\n{} {:?}",
-            "Selected collection".blue().bold(),
-            collection_arg
-        );
+    Ok(db)
+}
 
-        let config = configuration::main(self.collections);
+/// Imports the collected JSON documents of the GitHub output directory into the collection.
+/// Returns the number of imported documents.
+pub fn import(db: &Database, collection: Collection, config: &DataPipelineConfig) -> Result<usize> {
+    log::info!("importing the collection {:?}", collection.name());
 
-        let collection_index = config.choose_collection(collection_arg);
+    let fs_config = configuration::fs_config(collection, config)?;
 
-        match collection_index {
-            0 => {
-                let collection = self.collections[collection_index];
-                let db = config.connect();
-                let fs_config = config.fs_config(collection.to_owned());
-                repos_collection::main(db, collection, &fs_config.json_data_dir);
-            }
-            1 => {
-                let collection = self.collections[collection_index];
-                let db = config.connect();
-                let fs_config = config.fs_config(collection.to_owned());
-                workflows_collection::main(db, collection, &fs_config.json_data_dir);
-            }
-            _ => {
-                println!(
-                    "\n{}",
-                    "Nothing to execute. The collection is not supported"
-                        .red()
-                        .bold()
-                )
-            }
+    match collection {
+        Collection::Repos => {
+            MongoDbReposCollection::new(db.clone()).import(&fs_config.json_data_dir)
+        }
+        Collection::Workflows => {
+            MongoDbWorkflowsCollection::new(db.clone()).import(&fs_config.json_data_dir)
         }
     }
 }
@@ -2,194 +2,149 @@
 
 use std::fs;
 
-use colored::Colorize;
 use mongodb::sync::Database;
 use mongodb::{bson::doc, options::FindOneAndUpdateOptions};
 use octorust::types::WorkflowRun;
 
-/// The entry point of the program.
-pub fn main(db: Database, collection_input: &str, json_data_dir: &str) {
-    MongoDbWorkflowsCollection::new(db, collection_input, json_data_dir);
-}
+use super::{MongoDbError, Result};
+use crate::data_pipeline::error::FsError;
 
-struct MongoDbWorkflowsCollection {
+/// Imports the collected `WorkflowRun` documents into the `workflows` collection.
+pub struct MongoDbWorkflowsCollection {
     db: Database,
 }
 
 impl MongoDbWorkflowsCollection {
-    /// Program constructor.
-    fn new(
-        db: Database,
-        collection_input: &str,
-        json_data_dir: &str,
-    ) -> MongoDbWorkflowsCollection {
-        let mut program = MongoDbWorkflowsCollection { db };
-        program.init(collection_input, json_data_dir);
-        program
+    /// Importer constructor.
+    pub fn new(db: Database) -> MongoDbWorkflowsCollection {
+        MongoDbWorkflowsCollection { db }
     }
 
-    /// Initializes the program.
-    fn init(&mut self, collection_input: &str, json_data_dir: &str) {
-        println!(
-            "\n{}",
-            "MongoDbWorkflowsCollection initialized.".blue().bold()
-        );
+    /// Imports the JSON documents from the directory.
+    /// Returns the number of imported documents.
+    pub fn import(&self, json_data_dir: &str) -> Result<usize> {
+        log::info!("importing the workflows collection");
 
         let collection = "workflows";
 
-        if collection_input.ne(collection) {
-            panic!(
-                "\n{}\n{:?} != {:?}\n",
-                "Something went wrong. Input does not match expectation."
-                    .bold()
-                    .red(),
-                collection_input,
-                collection
-            );
-        }
-
-        println!("{}: {:?}", "JSON data dir".cyan(), json_data_dir);
+        log::debug!("JSON data dir {:?}", json_data_dir);
 
         if !json_data_dir.contains(collection) {
-            panic!(
-                "\n{}\nData dir: {:?}\nCollection: {:?}",
-                "JSON data dir does not contain collection name".red(),
-                json_data_dir,
-                collection
-            );
+            return Err(MongoDbError::DataDir {
+                data_dir: json_data_dir.into(),
+                collection: collection.to_owned(),
+            });
         }
 
-        self.execute(collection, json_data_dir);
+        self.execute(collection, json_data_dir)
     }
 
-    fn execute(&self, collection: &str, json_data_dir: &str) {
-        let mut exists = false;
-        match self.db.list_collection_names(None) {
-            Ok(value) => {
-                for col in value.iter() {
-                    if collection.eq(col) {
-                        exists = true;
-                    }
-                }
-            }
-            Err(err) => {
-                panic!("\nUnable to list collection names\n {:?}", err);
-            }
-        };
+    /// Creates or updates the collection.
+    fn execute(&self, collection: &str, json_data_dir: &str) -> Result<usize> {
+        let exists = self
+            .db
+            .list_collection_names(None)
+            .map_err(MongoDbError::ListCollections)?
+            .iter()
+            .any(|col| collection.eq(col));
 
         if exists {
-            self.update_collection(collection, json_data_dir);
+            self.update_collection(collection, json_data_dir)
         } else {
-            self.create_collection(collection, json_data_dir);
+            self.create_collection(collection, json_data_dir)
         }
     }
 
     /// Collects documents for further processing.
-    fn collect_documents(&self, json_data_dir: &str) -> Vec<Vec<WorkflowRun>> {
-        let read_dir_result = fs::read_dir(json_data_dir);
-
-        let Ok(dir_content) = read_dir_result else {
-            panic!(
-                "\n{} {:?}",
-                "Can't read directory".red().bold(),
-                json_data_dir
-            );
-        };
-
+    fn collect_documents(&self, json_data_dir: &str) -> Result<Vec<Vec<WorkflowRun>>> {
         let mut docs: Vec<Vec<WorkflowRun>> = vec![];
 
-        for dir_entries_result in dir_content {
-            let Ok(dir_entry) = dir_entries_result else {
-                panic!("\n{}: {:?}", "Can't get dir entry", dir_entries_result);
-            };
-            println!("\n{}: {:?}", "Dir entry".green().bold(), dir_entry);
+        let read_dir = fs::read_dir(json_data_dir)
+            .map_err(|error| FsError::new("read the directory", json_data_dir, error))?;
+        for dir_entry in read_dir {
+            let dir_entry = dir_entry
+                .map_err(|error| FsError::new("read the directory", json_data_dir, error))?;
+            log::debug!("dir entry {:?}", dir_entry);
 
-            let file_content_result = fs::read_to_string(dir_entry.path());
-            let Ok(file_content) = file_content_result else {
-                panic!("\n{}: {:?}", "Can't get file content", file_content_result);
-            };
+            let file_content = fs::read_to_string(dir_entry.path())
+                .map_err(|error| FsError::new("read the file", dir_entry.path(), error))?;
 
             let parse_result = serde_json::from_str::<Vec<WorkflowRun>>(&file_content);
             if let Ok(json) = parse_result {
                 docs.push(json);
             } else {
-                println!("Error serializing JSON file: {:?}", dir_entry.path());
+                log::warn!("can't deserialize the JSON file {:?}", dir_entry.path());
             }
         }
-        docs
+        Ok(docs)
     }
 
     /// Creates a collection and inserts data.
-    fn create_collection(&self, collection: &str, json_data_dir: &str) {
-        println!("\n{} {:?}", "Creating collection".cyan().bold(), collection);
+    fn create_collection(&self, collection: &str, json_data_dir: &str) -> Result<usize> {
+        log::info!("creating collection {:?}", collection);
 
-        let docs: Vec<Vec<WorkflowRun>> = self.collect_documents(json_data_dir);
+        let docs: Vec<Vec<WorkflowRun>> = self.collect_documents(json_data_dir)?;
 
         let collection_ref = self.db.collection::<WorkflowRun>(collection);
 
         match collection_ref.drop(None) {
             Ok(_) => {
-                println!("\n{}: {:?}", "Dropped".bold().green(), collection);
+                log::info!("dropped: {:?}", collection);
             }
             Err(err) => {
-                println!(
-                    "\n{}: {:?}\n{:?}",
-                    "Can't drop".bold().red(),
-                    collection,
-                    err
-                );
+                log::warn!("can't drop: {:?} {:?}", collection, err);
             }
         };
 
+        let mut imported = 0;
+
         for batch in docs {
             match collection_ref.insert_many(batch, None) {
-                Ok(_) => {
-                    println!("\n{}: {:?}", "Inserted in".green(), collection);
+                Ok(result) => {
+                    imported += result.inserted_ids.len();
+                    log::info!("inserted in: {:?}", collection);
                 }
                 Err(err) => {
-                    println!(
-                        "\n{}: {:?}\n{:?}",
-                        "Can't insert in".bold().red(),
-                        collection,
-                        err
-                    );
+                    log::warn!("can't insert in: {:?} {:?}", collection, err);
                 }
             };
         }
+
+        Ok(imported)
     }
 
     /// Updates documents in the collection.
-    fn update_collection(&self, collection: &str, json_data_dir: &str) {
-        println!("\n{} {:?}", "Updating collection".cyan().bold(), collection);
+    fn update_collection(&self, collection: &str, json_data_dir: &str) -> Result<usize> {
+        log::info!("updating collection {:?}", collection);
 
-        let docs: Vec<Vec<WorkflowRun>> = self.collect_documents(json_data_dir);
+        let docs: Vec<Vec<WorkflowRun>> = self.collect_documents(json_data_dir)?;
 
         let collection_ref = self.db.collection::<WorkflowRun>(collection);
 
+        let mut imported = 0;
+
         for batch in docs {
             for record in batch.iter().cloned() {
                 let url = &record.url;
                 let filter = doc! { "url": url };
-                let record_bson = mongodb::bson::to_bson(&record).unwrap();
+                let record_bson = mongodb::bson::to_bson(&record)?;
                 if let mongodb::bson::Bson::Document(document) = record_bson {
                     let mut options = FindOneAndUpdateOptions::default();
                     options.upsert = Some(true);
                     let update = doc! { "$set": document };
                     match collection_ref.find_one_and_update(filter, update, options) {
                         Ok(_) => {
-                            println!("{}: {:?}\n{:?}", "Updated".bold().green(), collection, url);
+                            imported += 1;
+                            log::info!("updated: {:?} {:?}", collection, url);
                         }
                         Err(err) => {
-                            println!(
-                                "\n{}: {:?}\n{:?}",
-                                "Can't update".bold().red(),
-                                collection,
-                                err
-                            );
+                            log::warn!("can't update: {:?} {:?}", collection, err);
                         }
                     }
                 }
             }
         }
+
+        Ok(imported)
     }
 }
@@ -0,0 +1,40 @@
+mod data_pipeline {
+    use std::collections::HashMap;
+
+    use crate::data_pipeline::{
+        self, environment, Collection, Context, Credentials, DataPipelineError, EnvironmentError,
+        Options,
+    };
+
+    #[test]
+    fn credentials() {
+        let env = HashMap::from([(String::from("GPG_PASSPHRASE"), String::from("passphrase"))]);
+        let credentials = Credentials::new(&env);
+        assert_eq!(credentials.gpg_passphrase.as_deref(), Some("passphrase"));
+        assert!(environment::var(&env, "RUST_WORKSPACE_MISSING_VARIABLE").is_err());
+    }
+
+    #[test]
+    fn run_without_credentials() {
+        let options = Options::default();
+        let result = data_pipeline::run(Context::Restore, Collection::Repos, "", &options, |_| {});
+        assert!(matches!(
+            result,
+            Err(DataPipelineError::Environment(EnvironmentError::MissingVariable { ref key, .. }))
+                if key == "GPG_PASSPHRASE"
+        ));
+
+        let result = data_pipeline::run(
+            Context::Collect,
+            Collection::Repos,
+            "user",
+            &options,
+            |_| panic!("no progress without the GitHub token"),
+        );
+        assert!(matches!(
+            result,
+            Err(DataPipelineError::Environment(EnvironmentError::MissingVariable { ref key, .. }))
+                if key == "GITHUB_TOKEN"
+        ));
+    }
+}
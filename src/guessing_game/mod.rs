@@ -1,137 +1,711 @@
 //! Guessing game module.
+//!
+//! The secret number is drawn from a range, the configured one or the one of a difficulty preset,
+//! and stays hidden unless `--debug-reveal` is passed. The game is lost when the attempts run out,
+//! and the precision hints of the wrong guesses scale with the size of the range.
+//!
+//! The finished games are recorded in the scores file, see the `scores` submodule, which feeds
+//! the `--stats` and `--leaderboard` views. In the `--reverse` mode the computer guesses
+//! the number of the player, see the `reverse` submodule.
 
+use clap::{Args, ValueEnum};
 use colored::Colorize;
 use rand::{thread_rng, Rng};
-use std::{cmp::Ordering, env::args, io};
+use serde::{Deserialize, Serialize};
+use std::{
+    cmp::Ordering,
+    env,
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    config::{self, GuessingGameConfig},
+    console, output, prompt,
+};
+
+use reverse::{Answer, Search};
+use scores::{Game, Outcome, Standing, Summary};
+
+pub mod reverse;
+pub mod scores;
+
+/// The exit code used when the scores file can't be read.
+pub const EXIT_CODE: i32 = 11;
+
+/// The widest bar of the attempt distribution.
+const MAX_BAR: u32 = 40;
 
 /// The entry point of the program.
-pub fn main() {
-    GuessingGame::new();
+pub fn main(args: InuputArguments) {
+    GuessingGame::new(args);
 }
 
 /// Input arguments of the program.
-struct InuputArguments {
-    guess: Option<String>,
+#[derive(Args, Default)]
+pub struct InuputArguments {
+    /// The first guess; the game prompts for further guesses.
+    #[arg(long, allow_negative_numbers = true)]
+    guess: Option<i32>,
+    /// The preset of the range and the number of attempts [default: the `guessing_game` configuration].
+    #[arg(long, value_enum)]
+    difficulty: Option<Difficulty>,
+    /// The minimum secret number, overriding the difficulty [default: `guessing_game.min`].
+    #[arg(long, allow_negative_numbers = true)]
+    min: Option<i32>,
+    /// The maximum secret number, overriding the difficulty [default: `guessing_game.max`].
+    #[arg(long, allow_negative_numbers = true)]
+    max: Option<i32>,
+    /// The number of guesses before the game is lost, overriding the difficulty [default: `guessing_game.attempts`].
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    attempts: Option<u32>,
+    /// Prints the secret number before the first guess, for debugging.
+    #[arg(long)]
+    debug_reveal: bool,
+    /// The name of the player recorded with the score, or the player of the statistics [default: the user name].
+    #[arg(long)]
+    player: Option<String>,
+    /// Prints the statistics of the recorded games, of the player and the difficulty if given, instead of playing.
+    #[arg(long, conflicts_with = "leaderboard")]
+    stats: bool,
+    /// Prints the best players of the recorded games, of the difficulty if given, instead of playing.
+    #[arg(long)]
+    leaderboard: bool,
+    /// The computer guesses the number the player thinks of, in the range, instead; the player answers
+    /// higher, lower or correct.
+    #[arg(long, conflicts_with_all = ["guess", "debug_reveal", "stats", "leaderboard"])]
+    reverse: bool,
+}
+
+/// A preset of the range of the secret number and the number of attempts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    /// From 1 to 20, with 6 attempts.
+    Easy,
+    /// From 1 to 100, with 7 attempts.
+    Normal,
+    /// From 1 to 1000, with 10 attempts.
+    Hard,
+}
+
+impl Difficulty {
+    /// The name of the preset, `custom` for custom settings.
+    pub fn label(difficulty: Option<Difficulty>) -> &'static str {
+        match difficulty {
+            Some(Difficulty::Easy) => "easy",
+            Some(Difficulty::Normal) => "normal",
+            Some(Difficulty::Hard) => "hard",
+            None => "custom",
+        }
+    }
+
+    /// The settings of the preset.
+    pub fn settings(self) -> Settings {
+        let (max, attempts) = match self {
+            Difficulty::Easy => (20, 6),
+            Difficulty::Normal => (100, 7),
+            Difficulty::Hard => (1000, 10),
+        };
+        Settings {
+            min: 1,
+            max,
+            attempts,
+        }
+    }
+}
+
+/// The settings of a game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settings {
+    /// The minimum secret number.
+    pub min: i32,
+    /// The maximum secret number.
+    pub max: i32,
+    /// The number of guesses before the game is lost.
+    pub attempts: u32,
+}
+
+impl Settings {
+    /// The settings of the difficulty preset, or of the configuration, overridden by
+    /// `--min`, `--max` and `--attempts`; an error if the range is empty.
+    fn new(args: &InuputArguments, config: &GuessingGameConfig) -> Result<Settings, String> {
+        let defaults = match args.difficulty {
+            Some(difficulty) => difficulty.settings(),
+            None => Settings {
+                min: config.min,
+                max: config.max,
+                attempts: config.attempts,
+            },
+        };
+        let settings = Settings {
+            min: args.min.unwrap_or(defaults.min),
+            max: args.max.unwrap_or(defaults.max),
+            attempts: args.attempts.unwrap_or(defaults.attempts),
+        };
+        if settings.min > settings.max {
+            return Err(format!(
+                "the minimum {} is greater than the maximum {}",
+                settings.min, settings.max
+            ));
+        }
+        Ok(settings)
+    }
+
+    /// The preset with these settings, `None` for custom settings.
+    pub fn difficulty(&self) -> Option<Difficulty> {
+        Difficulty::value_variants()
+            .iter()
+            .copied()
+            .find(|difficulty| difficulty.settings() == *self)
+    }
+}
+
+/// The distances to the secret number below which a wrong guess is in the closest,
+/// the closer and the far precision ranges; it's too far otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Thresholds {
+    pub closest: i64,
+    pub closer: i64,
+    pub far: i64,
+}
+
+impl Thresholds {
+    /// The thresholds of the range, 2%, 5% and 10% of its size, e.g. 2, 5 and 10 from 1 to 100;
+    /// at least 2, 3 and 4, so that each precision is possible in a small range.
+    pub fn new(min: i32, max: i32) -> Thresholds {
+        let size = max as i64 - min as i64 + 1;
+        let closest = (size / 50).max(2);
+        let closer = (size / 20).max(closest + 1);
+        let far = (size / 10).max(closer + 1);
+        Thresholds {
+            closest,
+            closer,
+            far,
+        }
+    }
+
+    /// The precision of a wrong guess at the distance from the secret number:
+    /// one of `closest`, `closer`, `far`, `too_far`.
+    pub fn precision(&self, distance: i64) -> &'static str {
+        if distance < self.closest {
+            "closest"
+        } else if distance < self.closer {
+            "closer"
+        } else if distance < self.far {
+            "far"
+        } else {
+            "too_far"
+        }
+    }
+}
+
+/// The `guessing_game.guess` output record.
+#[derive(Serialize)]
+struct GuessRecord {
+    guess: i32,
+    /// One of `too_small`, `too_big`, `correct`.
+    outcome: &'static str,
+    /// One of `closest`, `closer`, `far`, `too_far`; `null` if the guess is correct.
+    precision: Option<&'static str>,
+    /// The number of the guess, from 1.
+    attempt: u32,
+    attempts_left: u32,
+}
+
+/// The `guessing_game.result` output record.
+#[derive(Serialize)]
+struct ResultRecord {
+    /// One of `won`, `lost`.
+    outcome: &'static str,
+    secret: i32,
+    /// The number of guesses.
+    attempts: u32,
+    duration_ms: u64,
+}
+
+/// The `guessing_game.reverse` output record.
+#[derive(Serialize)]
+struct ReverseRecord {
+    /// One of `guessed`, `inconsistent`.
+    outcome: &'static str,
+    /// The number of the player, `null` if the answers are inconsistent.
+    number: Option<i32>,
+    /// The number of guesses of the computer.
+    guesses: u32,
+    /// The number of guesses of the binary search in the worst case.
+    optimum: u32,
+}
+
+/// The `guessing_game.stats` output record.
+#[derive(Serialize)]
+struct StatsRecord<'a> {
+    /// One of `all`, `easy`, `normal`, `hard`, `custom`.
+    difficulty: &'a str,
+    /// The player, `null` for all the players.
+    player: Option<&'a str>,
+    games: u32,
+    won: u32,
+    win_rate: f64,
+    average_attempts: Option<f64>,
+    /// The numbers of the won games by the number of attempts, from 1 attempt.
+    distribution: &'a [u32],
+}
+
+/// The `guessing_game.standing` output record.
+#[derive(Serialize)]
+struct StandingRecord<'a> {
+    rank: usize,
+    player: &'a str,
+    games: u32,
+    won: u32,
+    win_rate: f64,
+    average_attempts: Option<f64>,
+    best_duration_ms: Option<u64>,
 }
 
 struct GuessingGame;
 
 impl GuessingGame {
     /// Program constructor.
-    fn new() -> GuessingGame {
+    fn new(args: InuputArguments) -> GuessingGame {
         let mut program = GuessingGame;
-        program.init();
+        program.init(args);
         program
     }
 
     /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "Guessing game initialized.".blue().bold());
+    fn init(&mut self, args: InuputArguments) {
+        human_println!("\n{}", "Guessing game initialized.".blue().bold());
 
-        let secret_number: i32 = self.generate_secret();
+        if args.stats || args.leaderboard {
+            let scores_file = &config::get().guessing_game.scores_file;
+            let games: Vec<Game> = match scores::read(scores_file) {
+                Ok(games) => games,
+                Err(error) => {
+                    human_eprintln!(
+                        "\n{}: can't read the scores file {:?}: {}.",
+                        "Error".red().bold(),
+                        scores_file,
+                        error
+                    );
+                    console::exit(EXIT_CODE);
+                }
+            };
+            let games: Vec<Game> = games
+                .into_iter()
+                .filter(|game| args.difficulty.is_none() || game.difficulty == args.difficulty)
+                .collect();
+            match args.stats {
+                true => self.print_stats(&games, args.player.as_deref()),
+                false => self.print_leaderboard(&games),
+            }
+            return;
+        }
 
-        let args = self.args();
+        let settings = match Settings::new(&args, &config::get().guessing_game) {
+            Ok(settings) => settings,
+            Err(message) => {
+                human_eprintln!("\n{}: {}.", "Configuration error".red().bold(), message);
+                console::exit(config::EXIT_CODE);
+            }
+        };
 
-        self.start_guessing(secret_number, args.guess);
-    }
+        if args.reverse {
+            self.start_reverse(&settings);
+            return;
+        }
 
-    /// Parses arguments passed to the program.
-    fn args(&mut self) -> InuputArguments {
-        let arguments: Vec<String> = args().collect();
+        let secret_number: i32 = self.generate_secret(&settings, args.debug_reveal);
 
-        println!("\n{}:\n{:?}", "Arguments".cyan().bold(), arguments);
+        let player = args.player.clone().unwrap_or_else(user_name);
+        let game = self.start_guessing(secret_number, &settings, args.guess, player);
 
-        InuputArguments {
-            guess: arguments.get(2).cloned(),
+        // The scripted sessions, e.g. the tests, don't record their games.
+        if !console::is_scripted() {
+            let scores_file = &config::get().guessing_game.scores_file;
+            if let Err(error) = scores::append(scores_file, &game) {
+                log::warn!("can't save the score to {:?}: {}", scores_file, error);
+            }
         }
     }
 
-    /// Generates a secret number.
-    fn generate_secret(&mut self) -> i32 {
-        let range_min = 1;
-        let range_max = 101;
-
-        println!(
-            "\n{} [{}-{}]",
+    /// Generates a secret number, printed only if `reveal` is set.
+    fn generate_secret(&mut self, settings: &Settings, reveal: bool) -> i32 {
+        human_println!(
+            "\n{} [{}-{}], {} {}",
             "Guess the number between from range".cyan(),
-            range_min,
-            range_max
+            settings.min,
+            settings.max,
+            "attempts:".cyan(),
+            settings.attempts
         );
 
         let mut range = thread_rng();
 
-        let secret_number: i32 = range.gen_range(range_min..range_max);
+        let secret_number: i32 = range.gen_range(settings.min..=settings.max);
 
-        println!("{}: {}", "The secret number is".cyan(), secret_number);
+        if reveal {
+            human_println!("{}: {}", "The secret number is".cyan(), secret_number);
+        }
 
         secret_number
     }
 
-    /// The main logic of the guessing game.
-    fn start_guessing(&mut self, secret_number: i32, guess_arg: Option<String>) {
-        let is_some = guess_arg.is_some();
-        let mut guess_arg_input = if is_some {
-            match guess_arg.unwrap().trim().parse::<i32>() {
-                Ok(value) => value.to_string(),
-                Err(_) => String::new(),
-            }
-        } else {
-            String::new()
-        };
+    /// The main logic of the guessing game; returns the finished game of the player.
+    fn start_guessing(
+        &mut self,
+        secret_number: i32,
+        settings: &Settings,
+        guess_arg: Option<i32>,
+        player: String,
+    ) -> Game {
+        let started = Instant::now();
+        let mut guess_arg_input = guess_arg.map(|value| value.to_string()).unwrap_or_default();
+        let thresholds = Thresholds::new(settings.min, settings.max);
+        let mut attempt = 0;
 
         loop {
-            let mut guess_input = String::new();
-
-            if guess_arg_input.is_empty() {
-                println!("\n{}", "Please input your guess:".yellow().bold());
+            let guess_input = if guess_arg_input.is_empty() {
+                human_println!("\n{}", "Please input your guess:".yellow().bold());
 
-                io::stdin()
-                    .read_line(&mut guess_input)
-                    .expect("Failed to read line");
+                prompt::read_line("A guess (--guess)")
             } else {
-                guess_input = guess_arg_input.to_string();
-            }
+                guess_arg_input.to_string()
+            };
 
             let guess = match guess_input.trim().parse::<i32>() {
                 Ok(num) => num,
                 Err(_) => continue,
             };
 
-            println!("\n{}: {}", "You guessed".cyan(), guess);
+            attempt += 1;
+            human_println!("\n{}: {}", "You guessed".cyan(), guess);
 
-            match guess.cmp(&secret_number) {
+            let outcome = match guess.cmp(&secret_number) {
                 Ordering::Less => {
-                    println!("{}", "Too small!".red());
-                    self.precision(guess, secret_number);
-                    guess_arg_input = String::new();
+                    human_println!("{}", "Too small!".red());
+                    "too_small"
                 }
                 Ordering::Greater => {
-                    println!("{}", "Too big!".red());
-                    self.precision(guess, secret_number);
-                    guess_arg_input = String::new();
+                    human_println!("{}", "Too big!".red());
+                    "too_big"
                 }
                 Ordering::Equal => {
-                    println!("{}", "You win!".green().bold());
-                    break;
+                    human_println!("{}", "You win!".green().bold());
+                    "correct"
+                }
+            };
+
+            let precision = if outcome == "correct" {
+                None
+            } else {
+                Some(self.precision(guess, secret_number, &thresholds))
+            };
+            let attempts_left = settings.attempts - attempt;
+
+            output::emit(
+                "guessing_game.guess",
+                &GuessRecord {
+                    guess,
+                    outcome,
+                    precision,
+                    attempt,
+                    attempts_left,
+                },
+            );
+
+            let outcome = match (precision, attempts_left) {
+                (None, _) => Outcome::Won,
+                (Some(_), 0) => {
+                    human_println!(
+                        "\n{} {}: {}",
+                        "You lose!".red().bold(),
+                        "The secret number was".cyan(),
+                        secret_number
+                    );
+                    Outcome::Lost
+                }
+                (Some(_), attempts_left) => {
+                    human_println!("{}: {}", "Attempts left".cyan(), attempts_left);
+                    guess_arg_input = String::new();
+                    continue;
+                }
+            };
+            let duration_ms = started.elapsed().as_millis() as u64;
+            output::emit(
+                "guessing_game.result",
+                &ResultRecord {
+                    outcome: match outcome {
+                        Outcome::Won => "won",
+                        Outcome::Lost => "lost",
+                    },
+                    secret: secret_number,
+                    attempts: attempt,
+                    duration_ms,
+                },
+            );
+            return Game {
+                player,
+                difficulty: settings.difficulty(),
+                min: settings.min,
+                max: settings.max,
+                attempts: attempt,
+                max_attempts: settings.attempts,
+                duration_ms,
+                outcome,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |duration| duration.as_millis() as u64),
+            };
+        }
+    }
+
+    /// The reverse mode: the computer guesses the number of the player by binary search,
+    /// until the answer is correct or the answers are inconsistent.
+    fn start_reverse(&mut self, settings: &Settings) {
+        human_println!(
+            "\n{} [{}-{}], {}",
+            "Think of a number from range".cyan(),
+            settings.min,
+            settings.max,
+            "and answer my guesses with higher, lower or correct.".cyan()
+        );
+
+        let optimum = reverse::optimum(settings.min, settings.max);
+        let mut search = Search::new(settings.min, settings.max);
+        let mut guesses = 0;
+
+        let number = loop {
+            let Some(guess) = search.guess() else {
+                human_println!(
+                    "\n{} No number from {} to {} is higher than {} and lower than {}.",
+                    "Your answers are inconsistent!".red().bold(),
+                    settings.min,
+                    settings.max,
+                    search.low - 1,
+                    search.high + 1
+                );
+                break None;
+            };
+            guesses += 1;
+            human_println!("\n{}: {}", "My guess".cyan(), guess);
+
+            let answer = loop {
+                human_println!(
+                    "{}",
+                    "Is your number higher, lower, or correct? [h/l/c]"
+                        .yellow()
+                        .bold()
+                );
+                if let Some(answer) = Answer::parse(&prompt::read_line("An answer")) {
+                    break answer;
                 }
+            };
+            if answer == Answer::Correct {
+                human_println!(
+                    "\n{} {} in {} guesses, the optimum for the range is {} guesses at most.",
+                    "I guessed your number".green().bold(),
+                    guess,
+                    guesses,
+                    optimum
+                );
+                break Some(guess);
             }
+            search.answer(guess, answer);
+        };
+
+        output::emit(
+            "guessing_game.reverse",
+            &ReverseRecord {
+                outcome: match number {
+                    Some(_) => "guessed",
+                    None => "inconsistent",
+                },
+                number,
+                guesses,
+                optimum,
+            },
+        );
+    }
+
+    /// Prints how far or close the user guess is, and returns the precision.
+    fn precision(
+        &mut self,
+        guess: i32,
+        secret_number: i32,
+        thresholds: &Thresholds,
+    ) -> &'static str {
+        let precision = thresholds.precision((secret_number as i64 - guess as i64).abs());
+        match precision {
+            "closest" => human_println!("{}", "The guess is in the closest range.".green()),
+            "closer" => human_println!("{}", "The guess is closer.".bright_green()),
+            "far" => human_println!("{}", "The guess is far.".bright_red()),
+            _ => human_println!("{}", "The guess is too far.".red()),
         }
+        precision
     }
 
-    /// Prints how far or close the user guess is.
-    fn precision(&mut self, guess: i32, secret_number: i32) {
-        let far_threshold = 10;
-        let closer_threshold = far_threshold / 2;
-        let closest_threshold = closer_threshold / 2;
-        let absolute_difference = (secret_number - guess).abs();
-        if absolute_difference < closest_threshold {
-            println!("{}", "The guess is in the closest range.".green())
-        } else if absolute_difference < closer_threshold {
-            println!("{}", "The guess is closer.".bright_green())
-        } else if absolute_difference < far_threshold {
-            println!("{}", "The guess is far.".bright_red())
-        } else {
-            println!("{}", "The guess is too far.".red())
+    /// Prints the statistics of the games of the player, or of all the players:
+    /// the win rate and the average attempts by difficulty, and the distribution of the attempts.
+    fn print_stats(&mut self, games: &[Game], player: Option<&str>) {
+        let games: Vec<&Game> = games
+            .iter()
+            .filter(|game| player.is_none_or(|player| game.player == player))
+            .collect();
+        let players = player.map_or(String::from("all players"), |player| {
+            format!("player {}", player)
+        });
+        human_println!("\n{} ({})", "Statistics".cyan().bold(), players);
+        if games.is_empty() {
+            human_println!("No games recorded yet.");
+            return;
+        }
+
+        let mut rows = vec![("all", Summary::new(games.iter().copied()))];
+        for difficulty in Difficulty::value_variants()
+            .iter()
+            .copied()
+            .map(Some)
+            .chain([None])
+        {
+            let summary = Summary::new(
+                games
+                    .iter()
+                    .copied()
+                    .filter(|game| game.difficulty == difficulty),
+            );
+            if summary.games > 0 {
+                rows.push((Difficulty::label(difficulty), summary));
+            }
+        }
+
+        human_println!(
+            "\n{:<10} {:>6} {:>6} {:>9} {:>17}",
+            "Difficulty",
+            "Games",
+            "Won",
+            "Win rate",
+            "Average attempts"
+        );
+        for (difficulty, summary) in &rows {
+            human_println!(
+                "{:<10} {:>6} {:>6} {:>8.1}% {:>17}",
+                difficulty,
+                summary.games,
+                summary.won,
+                summary.win_rate() * 100.0,
+                summary
+                    .average_attempts()
+                    .map_or(String::from("-"), |average| format!("{:.2}", average))
+            );
+            output::emit(
+                "guessing_game.stats",
+                &StatsRecord {
+                    difficulty,
+                    player,
+                    games: summary.games,
+                    won: summary.won,
+                    win_rate: summary.win_rate(),
+                    average_attempts: summary.average_attempts(),
+                    distribution: &summary.distribution,
+                },
+            );
+        }
+
+        let all = &rows[0].1;
+        let largest = all
+            .distribution
+            .iter()
+            .copied()
+            .chain([all.lost()])
+            .max()
+            .unwrap_or_default();
+        human_println!("\n{}", "Attempts per game:".cyan());
+        for (index, games) in all.distribution.iter().enumerate() {
+            human_println!("{:>4} | {} {}", index + 1, bar(*games, largest), games);
+        }
+        human_println!(
+            "{:>4} | {} {}",
+            "lost",
+            bar(all.lost(), largest),
+            all.lost()
+        );
+    }
+
+    /// Prints the best players of the games.
+    fn print_leaderboard(&mut self, games: &[Game]) {
+        human_println!("\n{}", "Leaderboard".cyan().bold());
+        let standings: Vec<Standing> = scores::leaderboard(games);
+        if standings.is_empty() {
+            human_println!("No games recorded yet.");
+            return;
+        }
+
+        let width = standings
+            .iter()
+            .map(|standing| standing.player.chars().count())
+            .chain(["Player".len()])
+            .max()
+            .unwrap_or_default();
+        human_println!(
+            "\n{:>4}  {:<width$} {:>6} {:>6} {:>9} {:>17} {:>10}",
+            "Rank",
+            "Player",
+            "Games",
+            "Won",
+            "Win rate",
+            "Average attempts",
+            "Best time"
+        );
+        for (index, standing) in standings.iter().enumerate() {
+            let summary = &standing.summary;
+            human_println!(
+                "{:>4}  {:<width$} {:>6} {:>6} {:>8.1}% {:>17} {:>10}",
+                index + 1,
+                standing.player,
+                summary.games,
+                summary.won,
+                summary.win_rate() * 100.0,
+                summary
+                    .average_attempts()
+                    .map_or(String::from("-"), |average| format!("{:.2}", average)),
+                summary
+                    .best_duration_ms
+                    .map_or(String::from("-"), |duration| format!(
+                        "{:.1}s",
+                        duration as f64 / 1000.0
+                    ))
+            );
+            output::emit(
+                "guessing_game.standing",
+                &StandingRecord {
+                    rank: index + 1,
+                    player: &standing.player,
+                    games: summary.games,
+                    won: summary.won,
+                    win_rate: summary.win_rate(),
+                    average_attempts: summary.average_attempts(),
+                    best_duration_ms: summary.best_duration_ms,
+                },
+            );
         }
     }
 }
+
+/// The bar of the count, `MAX_BAR` wide for the largest count.
+fn bar(count: u32, largest: u32) -> String {
+    match largest {
+        0 => String::new(),
+        largest => "#".repeat((count * MAX_BAR).div_ceil(largest) as usize),
+    }
+}
+
+/// The name of the user, from the `USER` or the `USERNAME` environment variable, `anonymous` otherwise.
+fn user_name() -> String {
+    env::var("USER")
+        .or_else(|_| env::var("USERNAME"))
+        .unwrap_or_else(|_| String::from("anonymous"))
+}
+
+#[cfg(test)]
+mod tests;
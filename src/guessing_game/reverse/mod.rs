@@ -0,0 +1,74 @@
+//! Reverse submodule.
+//!
+//! In the reverse mode the player thinks of a number of the range, and the computer guesses it
+//! by binary search over the numbers consistent with the answers, `higher`, `lower` or `correct`.
+//! The answers are inconsistent, i.e. the player cheats, if no number of the range satisfies them.
+
+/// An answer of the player to a guess of the computer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Answer {
+    /// The number is higher than the guess.
+    Higher,
+    /// The number is lower than the guess.
+    Lower,
+    Correct,
+}
+
+impl Answer {
+    /// Parses the answer, e.g. `higher`, `h` or `+`; `None` if it's invalid.
+    pub fn parse(input: &str) -> Option<Answer> {
+        match input.trim().to_lowercase().as_str() {
+            "higher" | "h" | "+" | ">" => Some(Answer::Higher),
+            "lower" | "l" | "-" | "<" => Some(Answer::Lower),
+            "correct" | "c" | "=" => Some(Answer::Correct),
+            _ => None,
+        }
+    }
+}
+
+/// The binary search of the number, over the numbers from `low` to `high` consistent with the answers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Search {
+    pub low: i64,
+    pub high: i64,
+}
+
+impl Search {
+    /// The search of a number from `min` to `max`.
+    pub fn new(min: i32, max: i32) -> Search {
+        Search {
+            low: min as i64,
+            high: max as i64,
+        }
+    }
+
+    /// The next guess, the middle of the consistent numbers; `None` if the answers are inconsistent.
+    pub fn guess(&self) -> Option<i32> {
+        match self.low <= self.high {
+            true => Some((self.low + (self.high - self.low) / 2) as i32),
+            false => None,
+        }
+    }
+
+    /// Narrows the consistent numbers with the answer to the guess.
+    pub fn answer(&mut self, guess: i32, answer: Answer) {
+        match answer {
+            Answer::Higher => self.low = guess as i64 + 1,
+            Answer::Lower => self.high = guess as i64 - 1,
+            Answer::Correct => {
+                self.low = guess as i64;
+                self.high = guess as i64;
+            }
+        }
+    }
+}
+
+/// The number of guesses of the binary search in the worst case, the optimum for the range
+/// from `min` to `max`, i.e. the number of bits of its size.
+pub fn optimum(min: i32, max: i32) -> u32 {
+    let size = (max as i64 - min as i64 + 1) as u64;
+    u64::BITS - size.leading_zeros()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,43 @@
+mod reverse {
+    use crate::guessing_game::reverse::{self, Answer, Search};
+
+    #[test]
+    fn search() {
+        assert_eq!(Answer::parse(" Higher\n"), Some(Answer::Higher));
+        assert_eq!(Answer::parse("<"), Some(Answer::Lower));
+        assert_eq!(Answer::parse("yes"), None);
+
+        let secret = 73;
+        let mut search = Search::new(1, 100);
+        let mut guesses = 0;
+        while let Some(guess) = search.guess() {
+            guesses += 1;
+            if guess == secret {
+                break;
+            }
+            let answer = match guess < secret {
+                true => Answer::Higher,
+                false => Answer::Lower,
+            };
+            search.answer(guess, answer);
+        }
+        assert!(guesses <= reverse::optimum(1, 100));
+
+        let mut search = Search::new(1, 10);
+        search.answer(5, Answer::Higher);
+        search.answer(6, Answer::Lower);
+        assert_eq!(search.guess(), None);
+        let mut search = Search::new(i32::MIN, i32::MAX);
+        search.answer(i32::MAX, Answer::Higher);
+        assert_eq!(search.guess(), None);
+    }
+
+    #[test]
+    fn optimum() {
+        assert_eq!(reverse::optimum(1, 1), 1);
+        assert_eq!(reverse::optimum(1, 10), 4);
+        assert_eq!(reverse::optimum(1, 100), 7);
+        assert_eq!(reverse::optimum(1, 1000), 10);
+        assert_eq!(reverse::optimum(i32::MIN, i32::MAX), 33);
+    }
+}
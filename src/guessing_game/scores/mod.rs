@@ -0,0 +1,182 @@
+//! Scores submodule.
+//!
+//! Every finished game is appended to the scores file, `guessing_game.scores_file`, as a JSON line,
+//! and the games are summarized by the `--stats` and `--leaderboard` views: the win rate,
+//! the average attempts of the won games, the distribution of the attempts, and the ranking of the players.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use super::Difficulty;
+
+/// The largest number of players of the leaderboard.
+pub const LEADERBOARD_SIZE: usize = 10;
+
+/// The outcome of a finished game.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Won,
+    Lost,
+}
+
+/// A finished game, a line of the scores file.
+#[derive(Clone, Debug, PartialEq, Deserialize, Serialize)]
+pub struct Game {
+    pub player: String,
+    /// The preset matching the settings of the game, `None` for custom settings.
+    pub difficulty: Option<Difficulty>,
+    pub min: i32,
+    pub max: i32,
+    /// The number of guesses.
+    pub attempts: u32,
+    /// The number of guesses allowed.
+    pub max_attempts: u32,
+    pub duration_ms: u64,
+    pub outcome: Outcome,
+    /// The end of the game, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+}
+
+/// Appends the game to the scores file, creating the file and its directory if needed.
+pub fn append(path: &Path, game: &Game) -> io::Result<()> {
+    if let Some(directory) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(directory)?;
+    }
+    let line = serde_json::to_string(game)?;
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// The games of the scores file, none if the file doesn't exist; the invalid lines are skipped.
+pub fn read(path: &Path) -> io::Result<Vec<Game>> {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error),
+    };
+    let games = content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .filter_map(|(index, line)| match serde_json::from_str::<Game>(line) {
+            Ok(game) => Some(game),
+            Err(_) => {
+                log::warn!(
+                    "can't deserialize the game on the line {} of {:?}",
+                    index + 1,
+                    path
+                );
+                None
+            }
+        })
+        .collect();
+    Ok(games)
+}
+
+/// The summary of a set of games.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Summary {
+    pub games: u32,
+    pub won: u32,
+    /// The numbers of the won games by the number of attempts, from 1 attempt.
+    pub distribution: Vec<u32>,
+    /// The shortest won game.
+    pub best_duration_ms: Option<u64>,
+}
+
+impl Summary {
+    /// The summary of the games.
+    pub fn new<'a>(games: impl IntoIterator<Item = &'a Game>) -> Summary {
+        let mut summary = Summary::default();
+        for game in games {
+            summary.games += 1;
+            if game.outcome == Outcome::Lost {
+                continue;
+            }
+            summary.won += 1;
+            let index = game.attempts.max(1) as usize - 1;
+            if summary.distribution.len() <= index {
+                summary.distribution.resize(index + 1, 0);
+            }
+            summary.distribution[index] += 1;
+            summary.best_duration_ms = Some(
+                summary
+                    .best_duration_ms
+                    .map_or(game.duration_ms, |best| best.min(game.duration_ms)),
+            );
+        }
+        summary
+    }
+
+    pub fn lost(&self) -> u32 {
+        self.games - self.won
+    }
+
+    /// The share of the won games, from 0 to 1; zero without games.
+    pub fn win_rate(&self) -> f64 {
+        match self.games {
+            0 => 0.0,
+            games => self.won as f64 / games as f64,
+        }
+    }
+
+    /// The average number of attempts of the won games, `None` without won games.
+    pub fn average_attempts(&self) -> Option<f64> {
+        let attempts: u32 = self
+            .distribution
+            .iter()
+            .enumerate()
+            .map(|(index, games)| (index as u32 + 1) * games)
+            .sum();
+        match self.won {
+            0 => None,
+            won => Some(attempts as f64 / won as f64),
+        }
+    }
+}
+
+/// A player of the leaderboard.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Standing {
+    pub player: String,
+    pub summary: Summary,
+}
+
+/// The players ranked by the number of won games, then by the win rate, then by the average attempts;
+/// at most `LEADERBOARD_SIZE` of them.
+pub fn leaderboard(games: &[Game]) -> Vec<Standing> {
+    let mut players: Vec<&str> = games.iter().map(|game| game.player.as_str()).collect();
+    players.sort_unstable();
+    players.dedup();
+    let mut standings: Vec<Standing> = players
+        .into_iter()
+        .map(|player| Standing {
+            player: player.to_owned(),
+            summary: Summary::new(games.iter().filter(|game| game.player == player)),
+        })
+        .collect();
+    standings.sort_by(|a, b| {
+        let (a, b) = (&a.summary, &b.summary);
+        b.won
+            .cmp(&a.won)
+            .then(b.win_rate().total_cmp(&a.win_rate()))
+            .then(
+                a.average_attempts()
+                    .unwrap_or(f64::INFINITY)
+                    .total_cmp(&b.average_attempts().unwrap_or(f64::INFINITY)),
+            )
+    });
+    standings.truncate(LEADERBOARD_SIZE);
+    standings
+}
+
+#[cfg(test)]
+mod tests;
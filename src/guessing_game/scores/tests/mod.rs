@@ -0,0 +1,67 @@
+mod scores {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use crate::guessing_game::scores::{self, Game, Outcome, Summary};
+    use crate::guessing_game::Difficulty;
+
+    fn game(player: &str, attempts: u32, outcome: Outcome) -> Game {
+        Game {
+            player: player.to_owned(),
+            difficulty: Some(Difficulty::Normal),
+            min: 1,
+            max: 100,
+            attempts,
+            max_attempts: 7,
+            duration_ms: attempts as u64 * 1000,
+            outcome,
+            timestamp: 0,
+        }
+    }
+
+    #[test]
+    fn append_and_read() {
+        let path: PathBuf = std::env::temp_dir().join("rust-workspace-scores.jsonl");
+        let _ = fs::remove_file(&path);
+        assert_eq!(scores::read(&path).unwrap(), vec![]);
+
+        let games = [
+            game("alice", 3, Outcome::Won),
+            game("bob", 7, Outcome::Lost),
+        ];
+        for game in &games {
+            scores::append(&path, game).unwrap();
+        }
+        let mut content = fs::read_to_string(&path).unwrap();
+        assert!(content.contains("\"difficulty\":\"normal\",\"min\":1"));
+        content.push_str("not a game\n");
+        fs::write(&path, content).unwrap();
+        assert_eq!(scores::read(&path).unwrap(), games);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn summary_and_leaderboard() {
+        let games = [
+            game("alice", 3, Outcome::Won),
+            game("alice", 5, Outcome::Won),
+            game("alice", 7, Outcome::Lost),
+            game("bob", 2, Outcome::Won),
+            game("carol", 4, Outcome::Won),
+            game("carol", 4, Outcome::Won),
+        ];
+        let summary = Summary::new(&games);
+        assert_eq!(summary.games, 6);
+        assert_eq!(summary.lost(), 1);
+        assert_eq!(summary.distribution, vec![0, 1, 1, 2, 1]);
+        assert_eq!(summary.average_attempts(), Some(3.6));
+        assert_eq!(summary.best_duration_ms, Some(2000));
+        assert_eq!(Summary::new(&games[2..3]).average_attempts(), None);
+
+        let players: Vec<String> = scores::leaderboard(&games)
+            .into_iter()
+            .map(|standing| standing.player)
+            .collect();
+        assert_eq!(players, ["carol", "alice", "bob"]);
+    }
+}
@@ -0,0 +1,61 @@
+mod guessing_game {
+    use crate::config::GuessingGameConfig;
+    use crate::guessing_game::{Difficulty, InuputArguments, Settings, Thresholds};
+
+    #[test]
+    fn settings() {
+        let config = GuessingGameConfig::default();
+        let settings = |args: InuputArguments| Settings::new(&args, &config);
+        assert_eq!(
+            settings(InuputArguments::default()),
+            Ok(Settings {
+                min: 1,
+                max: 100,
+                attempts: 7
+            })
+        );
+        assert_eq!(
+            settings(InuputArguments {
+                difficulty: Some(Difficulty::Hard),
+                attempts: Some(3),
+                ..Default::default()
+            }),
+            Ok(Settings {
+                min: 1,
+                max: 1000,
+                attempts: 3
+            })
+        );
+        assert!(settings(InuputArguments {
+            difficulty: Some(Difficulty::Easy),
+            min: Some(50),
+            ..Default::default()
+        })
+        .is_err());
+    }
+
+    #[test]
+    fn thresholds() {
+        let thresholds = Thresholds::new(1, 100);
+        assert_eq!(
+            thresholds,
+            Thresholds {
+                closest: 2,
+                closer: 5,
+                far: 10
+            }
+        );
+        assert_eq!(thresholds.precision(1), "closest");
+        assert_eq!(thresholds.precision(9), "far");
+        assert_eq!(thresholds.precision(10), "too_far");
+        assert_eq!(Thresholds::new(1, 1000).precision(30), "closer");
+        assert_eq!(
+            Thresholds::new(1, 20),
+            Thresholds {
+                closest: 2,
+                closer: 3,
+                far: 4
+            }
+        );
+    }
+}
@@ -0,0 +1,77 @@
+//! Rust workspace library.
+//!
+//! The programs of the workspace are exposed as modules, so that they can be used
+//! without the interactive CLI, e.g. the GitHub collectors, the MongoDB importers,
+//! the artifact packer, and the model trainers of the data pipeline.
+//!
+//! The `main` function of each module is the entry point of the interactive program,
+//! and `programs` returns the registry of the CLI programs.
+
+use program::{Entry, Group, Registry};
+
+// The output module is declared first, so that its macros are available in the other modules.
+#[macro_use]
+pub mod output;
+
+pub mod calculator;
+pub mod config;
+pub mod console;
+pub mod data_pipeline;
+pub mod guessing_game;
+pub mod linfa_train;
+pub mod logging;
+pub mod open_weather;
+pub mod program;
+pub mod prompt;
+pub mod redact;
+pub mod server;
+pub mod system_information;
+
+/// The programs of the workspace, in the order of the interactive menu.
+pub fn programs() -> Registry {
+    Registry::new("program")
+        .register(Entry::new(
+            "guessing-game",
+            "Guess the secret number",
+            guessing_game::main,
+        ))
+        .register(Entry::new(
+            "weather",
+            "Current weather by city name using OpenWeather API",
+            open_weather::main,
+        ))
+        .register(Group::new(
+            "system-information",
+            "Print information about the system",
+            system_information::programs(),
+        ))
+        .register(Entry::new(
+            "calculator",
+            "Evaluate mathematical expressions",
+            calculator::main,
+        ))
+        .register(Group::new(
+            "linfa-train",
+            "Train machine learning models on the diabetes dataset",
+            linfa_train::programs(),
+        ))
+        .register(Group::new(
+            "data-pipeline",
+            "Collect GitHub data, pack artifacts and import them into MongoDB",
+            data_pipeline::programs(),
+        ))
+        .register(Group::new(
+            "config",
+            "Inspect the configuration of the programs",
+            Registry::new("command").register(Entry::new(
+                "show",
+                "Print the effective configuration and where each value came from",
+                config::show,
+            )),
+        ))
+        .register(Entry::new(
+            "serve",
+            "Serve the programs as a JSON HTTP API",
+            server::main,
+        ))
+}
@@ -0,0 +1,112 @@
+//! Dataset submodule for the model trainers.
+
+use csv::Reader;
+use linfa::Dataset;
+use ndarray::{Array, Array1, Array2, Ix1};
+use std::{fs::File, path::Path};
+
+use super::Result;
+
+/// Reads a dataset from a CSV file, where the last column contains the targets.
+/// The `target` closure converts a target value to the target type.
+pub fn read<T>(path: &Path, target: impl Fn(f32) -> T) -> Result<Dataset<f32, T, Ix1>> {
+    let mut reader = Reader::from_path(path)?;
+    let headers = headers(&mut reader)?;
+    let data = data(&mut reader)?;
+    let target_index = headers
+        .len()
+        .checked_sub(1)
+        .ok_or("The dataset has no columns")?;
+    let features = headers[0..target_index].to_vec();
+    let records = records(&data, target_index)?;
+    let targets = targets(&data, target_index, target);
+    Ok(Dataset::new(records, targets).with_feature_names(features))
+}
+
+/// The feature names of a CSV dataset, i.e. the headers without the targets column.
+pub fn features(path: &Path) -> Result<Vec<String>> {
+    let mut reader = Reader::from_path(path)?;
+    let mut headers: Vec<String> = reader.headers()?.iter().map(|r| r.to_owned()).collect();
+    headers.pop().ok_or("The dataset has no columns")?;
+    Ok(headers)
+}
+
+/// Converts rows of feature values to a records matrix, checking the number of features of each row.
+pub fn matrix(rows: &[Vec<f32>], nfeatures: usize) -> Result<Array2<f32>> {
+    if let Some((index, row)) = rows
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != nfeatures)
+    {
+        return Err(format!(
+            "The record {} has {} features, expected {}",
+            index,
+            row.len(),
+            nfeatures
+        )
+        .into());
+    }
+    let values = rows.iter().flatten().copied().collect::<Vec<f32>>();
+    Ok(Array::from(values).into_shape((rows.len(), nfeatures))?)
+}
+
+/// The share of the predictions equal to the targets.
+pub fn accuracy<T: PartialEq>(predictions: &Array1<T>, targets: &Array1<T>) -> f32 {
+    if targets.is_empty() {
+        return 0.0;
+    }
+    let correct = predictions
+        .iter()
+        .zip(targets.iter())
+        .filter(|(prediction, target)| prediction == target)
+        .count();
+    correct as f32 / targets.len() as f32
+}
+
+/// The dataset headers.
+fn headers(reader: &mut Reader<File>) -> Result<Vec<String>> {
+    let result: Vec<String> = reader.headers()?.iter().map(|r| r.to_owned()).collect();
+    log::debug!("headers collected {:?}", result);
+    Ok(result)
+}
+
+/// The dataset data.
+fn data(reader: &mut Reader<File>) -> Result<Vec<Vec<f32>>> {
+    let mut result: Vec<Vec<f32>> = vec![];
+    for record in reader.records() {
+        let row = record?
+            .iter()
+            .map(|field| field.parse::<f32>())
+            .collect::<std::result::Result<Vec<f32>, _>>()?;
+        result.push(row);
+    }
+    log::debug!("data collected, length {}", result.len());
+    Ok(result)
+}
+
+/// The dataset records.
+fn records(data: &[Vec<f32>], target_index: usize) -> Result<Array2<f32>> {
+    let mut records: Vec<f32> = vec![];
+    for record in data.iter() {
+        records.extend_from_slice(&record[0..target_index]);
+    }
+
+    let result = Array::from(records).into_shape((data.len(), target_index))?;
+    let record_shape = result.shape();
+    log::debug!(
+        "records collected, shape {} x {}",
+        record_shape[0],
+        record_shape[1]
+    );
+    Ok(result)
+}
+
+/// The dataset targets.
+fn targets<T>(data: &[Vec<f32>], target_index: usize, target: impl Fn(f32) -> T) -> Array1<T> {
+    let targets = data
+        .iter()
+        .map(|r| target(r[target_index]))
+        .collect::<Vec<T>>();
+    log::debug!("targets collected, length {}", targets.len());
+    Array::from(targets)
+}
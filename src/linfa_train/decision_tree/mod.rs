@@ -1,180 +1,178 @@
 //! Decision tree module.
 
 use ciborium::{cbor, value};
+use clap::Args;
 use colored::Colorize;
-use csv::Reader;
 use linfa::prelude::*;
 use linfa::Dataset;
 use linfa_trees::DecisionTree;
-use ndarray::{Array, Array1, Array2};
-use std::io::Read;
-use std::path::Path;
-use std::{env::args, fs, fs::File};
+use ndarray::Ix1;
+use std::fs;
+use std::path::PathBuf;
+
+use super::{dataset, MetricsRecord, Result};
+use crate::{config, output};
 
 /// The entry point of the program.
-pub fn main() {
-    LinfaTrainDecisionTree::new();
+pub fn main(args: InuputArguments) {
+    LinfaTrainDecisionTree::new(args);
 }
 
 /// Input arguments of the program.
-struct InuputArguments {
-    max_depth: usize,
+#[derive(Args, Default)]
+pub struct InuputArguments {
+    /// The maximum depth of the tree [default: `linfa_train.max_depth`].
+    #[arg(long)]
+    max_depth: Option<usize>,
 }
 
-struct LinfaTrainDecisionTree;
+/// The decision tree model trained on the diabetes dataset.
+pub type DecisionTreeModel = DecisionTree<f32, usize>;
 
+/// Decision tree trainer.
+///
 /// Source: https:///github.com/DataPsycho/data-pipelines-in-rust/blob/main/diabetes_ml_pipeline/Cargo.toml
-impl LinfaTrainDecisionTree {
-    /// Program constructor.
-    fn new() -> LinfaTrainDecisionTree {
-        let mut program = LinfaTrainDecisionTree;
-        program.init();
-        program
+pub struct DecisionTreeTrainer {
+    dataset_path: PathBuf,
+    model_path: PathBuf,
+    max_depth: usize,
+}
+
+impl Default for DecisionTreeTrainer {
+    /// The trainer with the `linfa_train` configuration.
+    fn default() -> Self {
+        let config = &config::get().linfa_train;
+        DecisionTreeTrainer {
+            dataset_path: config.dataset_path.clone(),
+            model_path: config.decision_tree_model_path.clone(),
+            max_depth: config.max_depth,
+        }
     }
+}
 
-    /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "Linfa train initialized.".blue().bold());
+impl DecisionTreeTrainer {
+    /// Trainer constructor with the `linfa_train` configuration.
+    pub fn new() -> DecisionTreeTrainer {
+        DecisionTreeTrainer::default()
+    }
 
-        let args = self.args();
+    /// Sets the path of the CSV dataset, where the last column contains the targets.
+    pub fn dataset_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dataset_path = path.into();
+        self
+    }
 
-        self.train(args.max_depth);
-        self.load_model();
+    /// Sets the path of the serialized model.
+    pub fn model_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.model_path = path.into();
+        self
     }
 
-    /// Parses arguments passed to the program.
-    fn args(&mut self) -> InuputArguments {
-        let arguments: Vec<String> = args().collect();
+    /// Sets the maximum depth of the tree.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
 
-        println!("\n{}:\n{:?}", "Arguments".cyan().bold(), arguments);
+    /// The dataset.
+    /// Data source: https:///github.com/plotly/datasets/blob/master/diabetes.csv
+    pub fn dataset(&self) -> Result<Dataset<f32, usize, Ix1>> {
+        dataset::read(&self.dataset_path, |target| target as usize)
+    }
 
-        let max_depth = arguments
-            .get(2)
-            .cloned()
-            .unwrap_or_default()
-            .trim()
-            .parse::<usize>()
-            .unwrap_or(500);
+    /// Trains the model and saves it to the model path.
+    pub fn train(&self) -> Result<DecisionTreeModel> {
+        let dataset = self.dataset()?;
+        let model = DecisionTree::params()
+            .max_depth(Some(self.max_depth))
+            .fit(&dataset)?;
+        let value = cbor!(model)?;
+        let mut vec_model = Vec::new();
+        ciborium::ser::into_writer(&value, &mut vec_model)?;
+        fs::write(&self.model_path, vec_model)?;
+        Ok(model)
+    }
 
-        InuputArguments { max_depth }
+    /// The accuracy of the model on the dataset, from 0 to 1.
+    pub fn accuracy(&self, model: &DecisionTreeModel) -> Result<f32> {
+        let dataset = self.dataset()?;
+        let predictions = model.predict(dataset.records());
+        Ok(dataset::accuracy(&predictions, dataset.targets()))
     }
 
-    /// The dataset headers.
-    fn headers(&mut self, reader: &mut Reader<File>) -> Vec<String> {
-        let result = reader
-            .headers()
-            .unwrap()
-            .iter()
-            .map(|r| r.to_owned())
-            .collect();
-        println!("\n{} {:?}", "Header collected, result:".yellow(), result);
-        result
+    /// Predicts the targets of the records, each record being the feature values in the dataset order.
+    pub fn predict(&self, model: &DecisionTreeModel, records: &[Vec<f32>]) -> Result<Vec<usize>> {
+        let features = dataset::features(&self.dataset_path)?;
+        let records = dataset::matrix(records, features.len())?;
+        Ok(model.predict(&records).to_vec())
     }
 
-    /// The dataset data.
-    fn data(&mut self, reader: &mut Reader<File>) -> Vec<Vec<f32>> {
-        let result = reader
-            .records()
-            .map(|r| {
-                r.unwrap()
-                    .iter()
-                    .map(|field| field.parse::<f32>().unwrap())
-                    .collect::<Vec<f32>>()
-            })
-            .collect::<Vec<Vec<f32>>>();
-        println!(
-            "\n{} {:?}",
-            "Data collected, length:".yellow(),
-            result.len()
-        );
-        result
+    /// Loads the model from the model path.
+    pub fn load_model(&self) -> Result<DecisionTreeModel> {
+        let data = fs::read(&self.model_path)?;
+        let value = ciborium::de::from_reader::<value::Value, _>(&data[..])?;
+        let model: DecisionTreeModel = value.deserialized()?;
+        Ok(model)
     }
+}
 
-    /// The dataset records.
-    fn records(&mut self, data: &[Vec<f32>], target_index: usize) -> Array2<f32> {
-        let mut records: Vec<f32> = vec![];
-        for record in data.iter() {
-            records.extend_from_slice(&record[0..target_index]);
-        }
+struct LinfaTrainDecisionTree;
 
-        let result = Array::from(records)
-            .into_shape((data.len(), target_index))
-            .unwrap();
-        let record_shape = result.shape();
-        println!(
-            "\n{} {:?} x {:?}",
-            "Records collected, shape:".yellow(),
-            record_shape[0],
-            record_shape[1]
-        );
-        result
+impl LinfaTrainDecisionTree {
+    /// Program constructor.
+    fn new(args: InuputArguments) -> LinfaTrainDecisionTree {
+        let mut program = LinfaTrainDecisionTree;
+        program.init(args);
+        program
     }
 
-    /// The dataset targets.
-    fn targets(&mut self, data: &[Vec<f32>], target_index: usize) -> Array1<usize> {
-        let targets = data
-            .iter()
-            .map(|r| r[target_index] as usize)
-            .collect::<Vec<usize>>();
-        println!(
-            "\n{} {:?}",
-            "Target collected, length:".yellow(),
-            targets.len()
-        );
-        Array::from(targets)
-    }
+    /// Initializes the program.
+    fn init(&mut self, args: InuputArguments) {
+        human_println!("\n{}", "Linfa train initialized.".blue().bold());
 
-    /// The dataset.
-    /// Data source: https:///github.com/plotly/datasets/blob/master/diabetes.csv
-    fn dataset(&mut self) -> Dataset<f32, usize, ndarray::Dim<[usize; 1]>> {
-        let file_path = ".data/input/diabetes.csv";
-        let mut reader = Reader::from_path(file_path).unwrap();
-        let headers = self.headers(&mut reader);
-        let data = self.data(&mut reader);
-        let target_index = headers.len() - 1;
-        let features = headers[0..target_index].to_vec();
-        let records = self.records(&data, target_index);
-        let targets = self.targets(&data, target_index);
-        Dataset::new(records, targets).with_feature_names(features)
+        let mut trainer = DecisionTreeTrainer::new();
+        if let Some(max_depth) = args.max_depth {
+            trainer = trainer.max_depth(max_depth);
+        }
+
+        self.train(&trainer);
+        self.load_model(&trainer);
     }
 
     /// Trains the model.
-    fn train(&mut self, max_depth: usize) {
-        println!("\n{}", "Training the model...".yellow().bold());
-        let dataset = self.dataset();
-        let model = DecisionTree::params()
-            .max_depth(Some(max_depth))
-            .fit(&dataset)
-            .expect("Can not train the model");
-        let value = cbor!(model).unwrap();
-        let mut vec_model = Vec::new();
-        ciborium::ser::into_writer(&value, &mut vec_model).unwrap();
-        // debug: start
-        let prediction = model.predict(&dataset.records);
-        println!("{:?}", prediction);
-        // debug: end
-        let output = Path::new(".data")
-            .join("output")
-            .join("diabetes_model.decision_tree.cbor");
-        fs::write(output.clone(), vec_model).unwrap();
-        println!("\n{} {:?}", "Model saved, path:".yellow(), output.as_path());
+    fn train(&mut self, trainer: &DecisionTreeTrainer) {
+        human_println!("\n{}", "Training the model...".yellow().bold());
+        let model = trainer.train().expect("Can not train the model");
+        let dataset = trainer.dataset().expect("Can not read the dataset");
+        log::debug!("predictions {:?}", model.predict(&dataset.records));
+        human_println!(
+            "\n{} {:?}",
+            "Model saved, path:".yellow(),
+            trainer.model_path
+        );
+        let accuracy = trainer
+            .accuracy(&model)
+            .expect("Can not evaluate the model");
+        human_println!("{} {:?}", "Model accuracy:".yellow(), accuracy);
+        output::emit(
+            "linfa_train.metrics",
+            &MetricsRecord {
+                model: "decision_tree",
+                model_path: &trainer.model_path,
+                records: dataset.nsamples(),
+                accuracy,
+            },
+        );
     }
 
     /// Loads the model.
-    fn load_model(&mut self) {
-        println!("\n{}", "Testing the model...".yellow().bold());
-        let dataset = self.dataset();
-        let mut data: Vec<u8> = Vec::new();
-        let path = Path::new(".data")
-            .join("output")
-            .join("diabetes_model.decision_tree.cbor");
-        let mut file = File::open(path).unwrap();
-        file.read_to_end(&mut data).unwrap();
-        let value = ciborium::de::from_reader::<value::Value, _>(&data[..]).unwrap();
-        let model: DecisionTree<f32, usize> = value.deserialized().unwrap();
-        println!("\n{} {:?}", "Model loaded:".yellow(), model);
+    fn load_model(&mut self, trainer: &DecisionTreeTrainer) {
+        human_println!("\n{}", "Testing the model...".yellow().bold());
+        let dataset = trainer.dataset().expect("Can not read the dataset");
+        let model = trainer.load_model().expect("Can not load the model");
+        human_println!("\n{} {:?}", "Model loaded:".yellow(), model);
         let prediction = model.predict(dataset.records);
-        println!(
+        human_println!(
             "\n{} {:?}",
             "Prediction test with the model success:".green().bold(),
             prediction
@@ -1,246 +1,164 @@
 //! Logistic regression module.
 
 use ciborium::{cbor, value};
+use clap::Args;
 use colored::Colorize;
-use csv::Reader;
 use linfa::prelude::*;
 use linfa::Dataset;
 use linfa_logistic::FittedLogisticRegression;
 use linfa_logistic::LogisticRegression;
-use ndarray::Axis;
-use ndarray::{Array, Array1, Array2};
+use ndarray::{Axis, Ix1};
 use plotters::prelude::*;
-use std::io::Read;
-use std::path::Path;
-use std::{env::args, fs, fs::File};
+use std::fs;
+use std::path::PathBuf;
+
+use super::{dataset, MetricsRecord, PlotRecord, Result};
+use crate::{config, output};
 
 /// The entry point of the program.
-pub fn main() {
-    LinfaTrainLogisticRegression::new();
+pub fn main(args: InuputArguments) {
+    LinfaTrainLogisticRegression::new(args);
 }
 
 /// Input arguments of the program.
-struct InuputArguments {
-    max_iterations: u64,
+#[derive(Args, Default)]
+pub struct InuputArguments {
+    /// The maximum number of iterations of the solver [default: `linfa_train.max_iterations`].
+    #[arg(long)]
+    max_iterations: Option<u64>,
 }
 
-struct LinfaTrainLogisticRegression;
+/// The logistic regression model trained on the diabetes dataset.
+pub type LogisticRegressionModel = FittedLogisticRegression<f32, i32>;
 
+/// Logistic regression trainer.
+///
 /// Source: https:///github.com/DataPsycho/data-pipelines-in-rust/blob/main/diabetes_ml_pipeline/Cargo.toml
-impl LinfaTrainLogisticRegression {
-    /// Program constructor.
-    fn new() -> LinfaTrainLogisticRegression {
-        let mut program = LinfaTrainLogisticRegression;
-        program.init();
-        program
-    }
-
-    /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "Linfa train initialized.".blue().bold());
-
-        let args = self.args();
+pub struct LogisticRegressionTrainer {
+    dataset_path: PathBuf,
+    model_path: PathBuf,
+    plots_path: PathBuf,
+    max_iterations: u64,
+}
 
-        self.train(args.max_iterations);
-        self.load_model();
-        self.generate_plots();
+impl Default for LogisticRegressionTrainer {
+    /// The trainer with the `linfa_train` configuration.
+    fn default() -> Self {
+        let config = &config::get().linfa_train;
+        LogisticRegressionTrainer {
+            dataset_path: config.dataset_path.clone(),
+            model_path: config.logistic_regression_model_path.clone(),
+            plots_path: config.plots_path.clone(),
+            max_iterations: config.max_iterations,
+        }
     }
+}
 
-    /// Parses arguments passed to the program.
-    fn args(&mut self) -> InuputArguments {
-        let arguments: Vec<String> = args().collect();
-
-        println!("\n{}:\n{:?}", "Arguments".cyan().bold(), arguments);
-
-        let max_iterations = arguments
-            .get(2)
-            .cloned()
-            .unwrap_or_default()
-            .trim()
-            .parse::<u64>()
-            .unwrap_or(500);
-
-        InuputArguments { max_iterations }
+impl LogisticRegressionTrainer {
+    /// Trainer constructor with the `linfa_train` configuration.
+    pub fn new() -> LogisticRegressionTrainer {
+        LogisticRegressionTrainer::default()
     }
 
-    /// The dataset headers.
-    fn headers(&mut self, reader: &mut Reader<File>) -> Vec<String> {
-        let result = reader
-            .headers()
-            .unwrap()
-            .iter()
-            .map(|r| r.to_owned())
-            .collect();
-        println!("\n{} {:?}", "Header collected, result:".yellow(), result);
-        result
+    /// Sets the path of the CSV dataset, where the last column contains the targets.
+    pub fn dataset_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.dataset_path = path.into();
+        self
     }
 
-    /// The dataset data.
-    fn data(&mut self, reader: &mut Reader<File>) -> Vec<Vec<f32>> {
-        let result = reader
-            .records()
-            .map(|r| {
-                r.unwrap()
-                    .iter()
-                    .map(|field| field.parse::<f32>().unwrap())
-                    .collect::<Vec<f32>>()
-            })
-            .collect::<Vec<Vec<f32>>>();
-        println!(
-            "\n{} {:?}",
-            "Data collected, length:".yellow(),
-            result.len()
-        );
-        result
+    /// Sets the path of the serialized model.
+    pub fn model_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.model_path = path.into();
+        self
     }
 
-    /// The dataset records.
-    fn records(&mut self, data: &[Vec<f32>], target_index: usize) -> Array2<f32> {
-        let mut records: Vec<f32> = vec![];
-        for record in data.iter() {
-            records.extend_from_slice(&record[0..target_index]);
-        }
-
-        let result = Array::from(records)
-            .into_shape((data.len(), target_index))
-            .unwrap();
-        let record_shape = result.shape();
-        println!(
-            "\n{} {:?} x {:?}",
-            "Records collected, shape:".yellow(),
-            record_shape[0],
-            record_shape[1]
-        );
-        result
+    /// Sets the directory of the generated plots.
+    pub fn plots_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.plots_path = path.into();
+        self
     }
 
-    /// The dataset targets.
-    fn targets(&mut self, data: &[Vec<f32>], target_index: usize) -> Array1<i32> {
-        let targets = data
-            .iter()
-            .map(|r| r[target_index] as i32)
-            .collect::<Vec<i32>>();
-        println!(
-            "\n{} {:?}",
-            "Target collected, length:".yellow(),
-            targets.len()
-        );
-        Array::from(targets)
+    /// Sets the maximum number of iterations of the solver.
+    pub fn max_iterations(mut self, max_iterations: u64) -> Self {
+        self.max_iterations = max_iterations;
+        self
     }
 
     /// The dataset.
     /// Data source: https:///github.com/plotly/datasets/blob/master/diabetes.csv
-    fn dataset(&mut self) -> Dataset<f32, i32, ndarray::Dim<[usize; 1]>> {
-        let file_path = ".data/input/diabetes.csv";
-        let mut reader = Reader::from_path(file_path).unwrap();
-        let headers = self.headers(&mut reader);
-        let data = self.data(&mut reader);
-        let target_index = headers.len() - 1;
-        let features = headers[0..target_index].to_vec();
-        let records = self.records(&data, target_index);
-        let targets = self.targets(&data, target_index);
-        Dataset::new(records, targets).with_feature_names(features)
+    pub fn dataset(&self) -> Result<Dataset<f32, i32, Ix1>> {
+        dataset::read(&self.dataset_path, |target| target as i32)
     }
 
-    /// Trains the model.
-    fn train(&mut self, max_iterations: u64) {
-        println!("\n{}", "Training the model...".yellow().bold());
-        let dataset = self.dataset();
+    /// Trains the model and saves it to the model path.
+    pub fn train(&self) -> Result<LogisticRegressionModel> {
+        let dataset = self.dataset()?;
         let model = LogisticRegression::default()
-            .max_iterations(max_iterations)
+            .max_iterations(self.max_iterations)
             .gradient_tolerance(0.0001)
-            .fit(&dataset)
-            .expect("Can not train the model");
-        let value = cbor!(model).unwrap();
+            .fit(&dataset)?;
+        let value = cbor!(model)?;
         let mut vec_model = Vec::new();
-        ciborium::ser::into_writer(&value, &mut vec_model).unwrap();
-        // debug: start
-        let prediction = model.predict(&dataset.records);
-        println!("{:?}", prediction);
-        // debug: end
-        let output = Path::new(".data")
-            .join("output")
-            .join("diabetes_model.logistic_regression.cbor");
-        fs::write(output.clone(), vec_model).unwrap();
-        println!("\n{} {:?}", "Model saved, path:".yellow(), output.as_path());
+        ciborium::ser::into_writer(&value, &mut vec_model)?;
+        fs::write(&self.model_path, vec_model)?;
+        Ok(model)
     }
 
-    /// Loads the model.
-    fn load_model(&mut self) {
-        println!("\n{}", "Testing the model...".yellow().bold());
-        let dataset = self.dataset();
-        let mut data: Vec<u8> = Vec::new();
-        let path = Path::new(".data")
-            .join("output")
-            .join("diabetes_model.logistic_regression.cbor");
-        let mut file = File::open(path).unwrap();
-        file.read_to_end(&mut data).unwrap();
-        let value = ciborium::de::from_reader::<value::Value, _>(&data[..]).unwrap();
-        let model: FittedLogisticRegression<f32, i32> = value.deserialized().unwrap();
-        println!("\n{} {:?}", "Model loaded:".yellow(), model);
-        let prediction = model.predict(dataset.records);
-        println!(
-            "\n{} {:?}",
-            "Prediction test with the model success:".green().bold(),
-            prediction
-        );
+    /// The accuracy of the model on the dataset, from 0 to 1.
+    pub fn accuracy(&self, model: &LogisticRegressionModel) -> Result<f32> {
+        let dataset = self.dataset()?;
+        let predictions = model.predict(dataset.records());
+        Ok(dataset::accuracy(&predictions, dataset.targets()))
     }
 
-    /// Generates plots (2D Cartesian coordinate system).
-    fn generate_plots(&mut self) {
-        let dataset = self.dataset();
+    /// Predicts the targets of the records, each record being the feature values in the dataset order.
+    pub fn predict(
+        &self,
+        model: &LogisticRegressionModel,
+        records: &[Vec<f32>],
+    ) -> Result<Vec<i32>> {
+        let features = dataset::features(&self.dataset_path)?;
+        let records = dataset::matrix(records, features.len())?;
+        Ok(model.predict(&records).to_vec())
+    }
+
+    /// Loads the model from the model path.
+    pub fn load_model(&self) -> Result<LogisticRegressionModel> {
+        let data = fs::read(&self.model_path)?;
+        let value = ciborium::de::from_reader::<value::Value, _>(&data[..])?;
+        let model: LogisticRegressionModel = value.deserialized()?;
+        Ok(model)
+    }
+
+    /// Generates plots (2D Cartesian coordinate system) of each feature by the last feature.
+    /// Returns the paths of the generated plots.
+    pub fn generate_plots(&self) -> Result<Vec<PathBuf>> {
+        let dataset = self.dataset()?;
         let records = dataset.records().to_owned();
         let length = records.index_axis(Axis(0), 0).len();
         let x_axis_column_index = length - 1;
-        for index in 0..x_axis_column_index {
-            let y_axis_column_index = index;
-            match self.plot(dataset.clone(), x_axis_column_index, y_axis_column_index) {
-                Ok(()) => {
-                    println!("Generated a plot {:?}", index);
-                }
-                Err(error) => {
-                    panic!("Plot error\n{:?}", error.source());
-                }
-            }
+        let mut paths = vec![];
+        for y_axis_column_index in 0..x_axis_column_index {
+            let path = self
+                .plot(&dataset, x_axis_column_index, y_axis_column_index)
+                .map_err(|error| error.to_string())?;
+            paths.push(path);
         }
+        Ok(paths)
     }
 
     /// Generates a plot (2D Cartesian coordinate system).
     fn plot(
-        &mut self,
-        dataset: Dataset<f32, i32, ndarray::Dim<[usize; 1]>>,
+        &self,
+        dataset: &Dataset<f32, i32, Ix1>,
         x_axis_column_index: usize,
         y_axis_column_index: usize,
-    ) -> Result<(), Box<dyn std::error::Error>> {
+    ) -> std::result::Result<PathBuf, Box<dyn std::error::Error>> {
         let records = dataset.records().to_owned();
-        let x_axis = records.column(x_axis_column_index);
-        let x_values = x_axis.to_owned().to_vec();
-        let x_range = x_values
-            .clone()
-            .into_iter()
-            .reduce(f32::min)
-            .unwrap()
-            .to_owned()
-            ..x_values
-                .clone()
-                .into_iter()
-                .reduce(f32::max)
-                .unwrap()
-                .to_owned();
-        let y_axis = records.column(y_axis_column_index);
-        let y_values = y_axis.to_owned().to_vec();
-        let y_range = y_values
-            .clone()
-            .into_iter()
-            .reduce(f32::min)
-            .unwrap()
-            .to_owned()
-            ..y_values
-                .clone()
-                .into_iter()
-                .reduce(f32::max)
-                .unwrap()
-                .to_owned();
+        let x_values = records.column(x_axis_column_index).to_vec();
+        let x_range = min(&x_values)..max(&x_values);
+        let y_values = records.column(y_axis_column_index).to_vec();
+        let y_range = min(&y_values)..max(&y_values);
 
         let features = dataset.feature_names();
         let x_feature_default = String::from("x");
@@ -255,9 +173,7 @@ impl LinfaTrainLogisticRegression {
 
         let file_name =
             y_feature.to_owned().to_lowercase() + "-by-" + x_feature.to_lowercase().as_str();
-        let plot_path = Path::new(".data")
-            .join("plots")
-            .join(file_name + "_diabetes_model.png");
+        let plot_path = self.plots_path.join(file_name + "_diabetes_model.png");
         let root = BitMapBackend::new(&plot_path, (1600, 1200)).into_drawing_area();
         root.fill(&WHITE)?;
 
@@ -268,8 +184,7 @@ impl LinfaTrainLogisticRegression {
             .y_label_area_size(30)
             .build_cartesian_2d(x_range, y_range)?;
 
-        let binding = x_values.to_owned().to_vec();
-        let plot_data = binding.iter().enumerate().map(|(index, value)| {
+        let plot_data = x_values.iter().enumerate().map(|(index, value)| {
             (
                 value.to_owned(),
                 y_values.get(index).unwrap_or(&0.0).to_owned(),
@@ -284,6 +199,99 @@ impl LinfaTrainLogisticRegression {
 
         root.present()?;
 
-        Ok(())
+        drop(chart);
+        drop(root);
+
+        Ok(plot_path)
+    }
+}
+
+/// The minimum of the values.
+fn min(values: &[f32]) -> f32 {
+    values.iter().copied().reduce(f32::min).unwrap_or_default()
+}
+
+/// The maximum of the values.
+fn max(values: &[f32]) -> f32 {
+    values.iter().copied().reduce(f32::max).unwrap_or_default()
+}
+
+struct LinfaTrainLogisticRegression;
+
+impl LinfaTrainLogisticRegression {
+    /// Program constructor.
+    fn new(args: InuputArguments) -> LinfaTrainLogisticRegression {
+        let mut program = LinfaTrainLogisticRegression;
+        program.init(args);
+        program
+    }
+
+    /// Initializes the program.
+    fn init(&mut self, args: InuputArguments) {
+        human_println!("\n{}", "Linfa train initialized.".blue().bold());
+
+        let mut trainer = LogisticRegressionTrainer::new();
+        if let Some(max_iterations) = args.max_iterations {
+            trainer = trainer.max_iterations(max_iterations);
+        }
+
+        self.train(&trainer);
+        self.load_model(&trainer);
+        self.generate_plots(&trainer);
+    }
+
+    /// Trains the model.
+    fn train(&mut self, trainer: &LogisticRegressionTrainer) {
+        human_println!("\n{}", "Training the model...".yellow().bold());
+        let model = trainer.train().expect("Can not train the model");
+        let dataset = trainer.dataset().expect("Can not read the dataset");
+        log::debug!("predictions {:?}", model.predict(&dataset.records));
+        human_println!(
+            "\n{} {:?}",
+            "Model saved, path:".yellow(),
+            trainer.model_path
+        );
+        let accuracy = trainer
+            .accuracy(&model)
+            .expect("Can not evaluate the model");
+        human_println!("{} {:?}", "Model accuracy:".yellow(), accuracy);
+        output::emit(
+            "linfa_train.metrics",
+            &MetricsRecord {
+                model: "logistic_regression",
+                model_path: &trainer.model_path,
+                records: dataset.nsamples(),
+                accuracy,
+            },
+        );
+    }
+
+    /// Loads the model.
+    fn load_model(&mut self, trainer: &LogisticRegressionTrainer) {
+        human_println!("\n{}", "Testing the model...".yellow().bold());
+        let dataset = trainer.dataset().expect("Can not read the dataset");
+        let model = trainer.load_model().expect("Can not load the model");
+        human_println!("\n{} {:?}", "Model loaded:".yellow(), model);
+        let prediction = model.predict(dataset.records);
+        human_println!(
+            "\n{} {:?}",
+            "Prediction test with the model success:".green().bold(),
+            prediction
+        );
+    }
+
+    /// Generates plots (2D Cartesian coordinate system).
+    fn generate_plots(&mut self, trainer: &LogisticRegressionTrainer) {
+        match trainer.generate_plots() {
+            Ok(paths) => {
+                for (index, path) in paths.iter().enumerate() {
+                    human_println!("Generated a plot {:?}: {:?}", index, path);
+                    output::emit("linfa_train.plot", &PlotRecord { path });
+                }
+            }
+            Err(error) => {
+                panic!("Plot error\n{:?}", error);
+            }
+        }
     }
 }
@@ -0,0 +1,220 @@
+//! Logging module.
+//!
+//! Diagnostics are logged with the `log` macros, e.g. `log::debug!`, and printed to the standard error
+//! as `LEVEL target: message`. Optionally, the records are appended to a JSON lines log file.
+//! Every record is redacted, see the `redact` module.
+//!
+//! The level is `warn` by default; `-v` raises it to `info`, `-vv` to `debug`, `-vvv` to `trace`,
+//! and `-q` lowers it to `error`, `-qq` turns logging off.
+//! The `RUST_LOG` environment variable overrides the level, globally or by module,
+//! e.g. `RUST_LOG=info,rust_workspace::data_pipeline=debug`.
+
+use clap::Args;
+use colored::Colorize;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde::Serialize;
+use std::{
+    env,
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::PathBuf,
+    str::FromStr,
+    sync::{Mutex, OnceLock},
+    time::{SystemTime, UNIX_EPOCH},
+};
+use thiserror::Error;
+
+use crate::redact;
+
+/// The environment variable with the log filter.
+pub const FILTER_ENV: &str = "RUST_LOG";
+
+/// Arguments of the logging.
+#[derive(Args, Debug, Default)]
+pub struct LogArguments {
+    /// Logs more diagnostics; can be repeated, e.g. `-vv`.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+    /// Logs fewer diagnostics; can be repeated, e.g. `-qq`.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub quiet: u8,
+    /// Appends the log records as JSON lines to the file.
+    #[arg(long, global = true, value_name = "FILE")]
+    pub log_file: Option<PathBuf>,
+}
+
+/// Logging initialization failure.
+#[derive(Debug, Error)]
+pub enum LogError {
+    #[error("invalid log filter {0:?}, expected e.g. `info,rust_workspace::data_pipeline=debug`")]
+    InvalidFilter(String),
+    #[error("can't open the log file {path:?}")]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// The log levels by module path prefix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Filter {
+    /// The level of the modules without a directive.
+    pub level: LevelFilter,
+    /// The levels of the modules and their submodules.
+    pub directives: Vec<(String, LevelFilter)>,
+}
+
+impl Filter {
+    /// The filter with the level of the verbosity arguments.
+    pub fn new(verbose: u8, quiet: u8) -> Filter {
+        let levels = LevelFilter::iter().collect::<Vec<LevelFilter>>();
+        let index = (LevelFilter::Warn as i32 + verbose as i32 - quiet as i32)
+            .clamp(0, levels.len() as i32 - 1);
+        Filter {
+            level: levels[index as usize],
+            directives: vec![],
+        }
+    }
+
+    /// Applies the comma-separated directives, `level` or `module=level`.
+    pub fn parse(mut self, spec: &str) -> Result<Filter, LogError> {
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            let invalid = || LogError::InvalidFilter(spec.to_owned());
+            match directive.split_once('=') {
+                Some((module, level)) => {
+                    let level = LevelFilter::from_str(level.trim()).map_err(|_| invalid())?;
+                    self.directives.push((module.trim().to_owned(), level));
+                }
+                None => self.level = LevelFilter::from_str(directive).map_err(|_| invalid())?,
+            }
+        }
+        // The most specific directive is matched first.
+        self.directives
+            .sort_by_key(|(module, _)| std::cmp::Reverse(module.len()));
+        Ok(self)
+    }
+
+    /// The level of the module.
+    pub fn level(&self, target: &str) -> LevelFilter {
+        self.directives
+            .iter()
+            .find(|(module, _)| target == module || target.starts_with(&(module.to_owned() + "::")))
+            .map_or(self.level, |(_, level)| *level)
+    }
+
+    /// The highest level of the filter.
+    pub fn max_level(&self) -> LevelFilter {
+        self.directives
+            .iter()
+            .map(|(_, level)| *level)
+            .fold(self.level, Ord::max)
+    }
+}
+
+/// A log record of the log file.
+#[derive(Serialize)]
+struct FileRecord<'a> {
+    /// Milliseconds since the Unix epoch.
+    timestamp: u128,
+    level: &'a str,
+    target: &'a str,
+    message: &'a str,
+}
+
+/// The logger of the process.
+struct Logger {
+    filter: Filter,
+    file: Option<Mutex<File>>,
+}
+
+/// The logger, set once by `init`.
+static LOGGER: OnceLock<Logger> = OnceLock::new();
+
+/// Configures the logging from the arguments and the `RUST_LOG` environment variable.
+pub fn init(args: &LogArguments) -> Result<(), LogError> {
+    let mut filter = Filter::new(args.verbose, args.quiet);
+    if let Ok(spec) = env::var(FILTER_ENV) {
+        filter = filter.parse(&spec)?;
+    }
+
+    let file = match &args.log_file {
+        Some(path) => Some(Mutex::new(
+            OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .map_err(|source| LogError::Open {
+                    path: path.clone(),
+                    source,
+                })?,
+        )),
+        None => None,
+    };
+
+    let max_level = filter.max_level();
+    let logger = LOGGER.get_or_init(|| Logger { filter, file });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+    Ok(())
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.filter.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let message = record.args().to_string();
+        let message = redact::redact(&message);
+
+        human_eprintln!(
+            "{} {}: {}",
+            colored_level(record.level()),
+            record.target().dimmed(),
+            message
+        );
+
+        if let Some(file) = &self.file {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_millis());
+            let line = serde_json::to_string(&FileRecord {
+                timestamp,
+                level: record.level().as_str(),
+                target: record.target(),
+                message: &message,
+            })
+            .unwrap_or_default();
+            let mut file = file.lock().unwrap_or_else(|error| error.into_inner());
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file {
+            let mut file = file.lock().unwrap_or_else(|error| error.into_inner());
+            let _ = file.flush();
+        }
+    }
+}
+
+/// The colored name of the level.
+fn colored_level(level: Level) -> colored::ColoredString {
+    let name = format!("{:<5}", level.as_str());
+    match level {
+        Level::Error => name.red().bold(),
+        Level::Warn => name.yellow().bold(),
+        Level::Info => name.green(),
+        Level::Debug => name.blue(),
+        Level::Trace => name.dimmed(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
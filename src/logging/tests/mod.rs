@@ -0,0 +1,46 @@
+mod logging {
+    use log::LevelFilter;
+
+    use crate::logging::Filter;
+
+    #[test]
+    fn verbosity() {
+        assert_eq!(Filter::new(0, 0).level, LevelFilter::Warn);
+        assert_eq!(Filter::new(1, 0).level, LevelFilter::Info);
+        assert_eq!(Filter::new(5, 0).level, LevelFilter::Trace);
+        assert_eq!(Filter::new(0, 1).level, LevelFilter::Error);
+        assert_eq!(Filter::new(0, 5).level, LevelFilter::Off);
+    }
+
+    #[test]
+    fn directives() {
+        let filter = Filter::new(0, 0)
+            .parse(
+                "info,rust_workspace::data_pipeline=debug,rust_workspace::data_pipeline::mongo=off",
+            )
+            .unwrap();
+        assert_eq!(
+            filter.level("rust_workspace::calculator"),
+            LevelFilter::Info
+        );
+        assert_eq!(
+            filter.level("rust_workspace::data_pipeline::github"),
+            LevelFilter::Debug
+        );
+        assert_eq!(
+            filter.level("rust_workspace::data_pipeline::mongo"),
+            LevelFilter::Off
+        );
+        assert_eq!(
+            filter.level("rust_workspace::data_pipeline_extra"),
+            LevelFilter::Info
+        );
+        assert_eq!(filter.max_level(), LevelFilter::Debug);
+    }
+
+    #[test]
+    fn invalid_directive() {
+        assert!(Filter::new(0, 0).parse("loud").is_err());
+        assert!(Filter::new(0, 0).parse("rust_workspace=loud").is_err());
+    }
+}
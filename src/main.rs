@@ -1,116 +1,61 @@
+use clap::{CommandFactory, FromArgMatches, Parser};
 use colored::Colorize;
-use std::{
-    cmp::Ordering,
-    env::{args, Args},
-    io,
+use rust_workspace::{
+    config::{self, ConfigArguments},
+    console, human_eprintln,
+    logging::{self, LogArguments},
+    output::{self, OutputFormat},
+    programs, prompt, redact,
 };
-
-mod calculator;
-mod data_pipeline;
-mod guessing_game;
-mod linfa_train;
-mod open_weather;
-mod system_information;
-
-type Programs<'a> = [&'a str; 6];
+use std::env;
+
+/// Rust workspace programs.
+///
+/// Run without a subcommand to select a program interactively.
+#[derive(Parser)]
+#[command(name = "rust-workspace", version)]
+struct Cli {
+    /// Never prompt for input; fail if a required input is missing or invalid.
+    /// Enabled automatically if the standard input is not a terminal.
+    #[arg(long, global = true)]
+    non_interactive: bool,
+
+    /// The output format; the JSON formats print machine-readable records to the standard output.
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+
+    #[command(flatten)]
+    config: ConfigArguments,
+
+    #[command(flatten)]
+    log: LogArguments,
+}
 
 /// The entry point of the CLI.
 fn main() {
-    let mut args: Args = args();
-
-    println!("\n{}:\n{:?}", "Arguments".cyan().bold(), args);
+    let programs = programs();
 
-    let programs: Programs = [
-        "Guessing game",
-        "Open Weather",
-        "System information",
-        "Calculator",
-        "Linfa train",
-        "Data pipeline",
-    ];
+    let matches = programs.command(Cli::command()).get_matches();
 
-    let program_arg = args.nth(1);
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|error| error.exit());
 
-    let program_index = choose_program(programs, program_arg);
+    output::init(cli.output);
 
-    match program_index {
-        0 => guessing_game::main(),
-        1 => open_weather::main(),
-        2 => system_information::main(),
-        3 => calculator::main(),
-        4 => linfa_train::main(),
-        5 => data_pipeline::main(),
-        _ => guessing_game::main(),
+    if let Err(error) = logging::init(&cli.log) {
+        human_eprintln!("\n{}: {}", "Logging error".red().bold(), error);
+        console::exit(config::EXIT_CODE);
     }
-}
-
-/// Prompts input from the user, processes it, and returns the index of the selected program.
-fn choose_program(programs: Programs, program_arg: Option<String>) -> usize {
-    let is_some = program_arg.is_some();
-    let mut program_arg_input = if is_some {
-        match program_arg.unwrap().trim().parse::<i32>() {
-            Ok(value) => value.to_string(),
-            Err(_) => String::new(),
-        }
-    } else {
-        String::new()
-    };
-
-    loop {
-        let mut program_input = String::new();
-
-        if program_arg_input.is_empty() {
-            print_instructions(programs);
 
-            io::stdin()
-                .read_line(&mut program_input)
-                .expect("Failed to read line");
-        } else {
-            program_input = program_arg_input.to_string();
-        }
-
-        let program_index = match program_input.trim().parse::<usize>() {
-            Ok(num) => num,
-            Err(_) => continue,
-        };
-
-        match program_index.cmp(&programs.len()) {
-            Ordering::Less => {
-                return select_program(programs, program_index);
-            }
-            Ordering::Greater => program_arg_input = reset_input_arg(),
-            Ordering::Equal => program_arg_input = reset_input_arg(),
-        }
+    if let Err(error) = config::init(&cli.config) {
+        human_eprintln!("\n{}: {}", "Configuration error".red().bold(), error);
+        console::exit(config::EXIT_CODE);
     }
-}
 
-/// Prints the program selection instructions.
-fn print_instructions(programs: Programs) {
-    println!("\n{}", "Available programs:".yellow().bold());
+    log::debug!("arguments: {:?}", redact::args(env::args()));
 
-    let max_i = programs.len() - 1;
-    let mut i = 0;
-    while i <= max_i {
-        println!("{}: {}", i, programs[i]);
-        i += 1;
-    }
+    prompt::init(cli.non_interactive);
 
-    println!(
-        "\n{}, [0-{}]:",
-        "Please select a program".yellow().bold(),
-        max_i
-    );
-}
-
-/// Resets the input argument to start over if the program does not exist.
-fn reset_input_arg() -> String {
-    println!("\n{}", "The subprogram does not exist.".red());
-    String::new()
-}
+    programs.dispatch(&matches);
 
-/// Prints selected program and returns the program index.
-fn select_program(programs: Programs, program_index: usize) -> usize {
-    let program = programs[program_index];
-    println!("You selected: {}", program);
-    program_index
+    output::finish();
 }
@@ -1,85 +1,126 @@
 //! Open weather module.
 
+use clap::Args;
 use colored::Colorize;
 use http_body_util::{BodyExt, Empty};
-use hyper::{body::Bytes, Request, Uri};
+use hyper::{body::Bytes, HeaderMap, Request, StatusCode, Uri};
 use hyper_util::rt::TokioIo;
-use std::{
-    env::args,
-    io::{self, Write},
-};
+use serde::Serialize;
 use tokio::net::TcpStream;
 
+use crate::{config, output, prompt, redact};
+
 type Result<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
+/// The environment variable with the OpenWeather API key of the server.
+pub const API_KEY_VAR: &str = "OPENWEATHER_API_KEY";
+
 /// The entry point of the program.
-pub fn main() {
-    OpenWeather::new();
+pub fn main(args: InuputArguments) {
+    OpenWeather::new(args);
+}
+
+/// The OpenWeather API response.
+pub struct WeatherResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: String,
+}
+
+/// Builds the current weather request URI with the configured API endpoint.
+/// The parameters are percent-encoded, so the city can't add parameters or change the path.
+pub fn weather_uri(city: &str, api_key: &str) -> Result<String> {
+    let query = serde_urlencoded::to_string([("q", city), ("appid", api_key)])?;
+    Ok(config::get().weather.api_url.clone() + "?" + &query)
+}
+
+/// Requests the current weather data for the city.
+pub async fn weather_request(city: &str, api_key: &str) -> Result<WeatherResponse> {
+    let uri = weather_uri(city, api_key)?.parse::<Uri>()?;
+    log::debug!("request {}", uri);
+
+    let host = uri.host().ok_or("uri has no host")?;
+    let port = uri.port_u16().unwrap_or(80);
+    let addr = format!("{}:{}", host, port);
+    let stream = TcpStream::connect(addr).await?;
+    let io = TokioIo::new(stream);
+
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(io).await?;
+    tokio::task::spawn(async move {
+        if let Err(err) = conn.await {
+            log::error!("connection failed: {:?}", err);
+        }
+    });
+
+    let authority = uri.authority().ok_or("uri has no authority")?.clone();
+
+    let path = uri.path_and_query().map_or("/", |path| path.as_str());
+    let req = Request::builder()
+        .uri(path)
+        .header(hyper::header::HOST, authority.as_str())
+        .body(Empty::<Bytes>::new())?;
+
+    let res = sender.send_request(req).await?;
+
+    let status = res.status();
+    let headers = res.headers().clone();
+    let body = res.into_body().collect().await?.to_bytes();
+
+    Ok(WeatherResponse {
+        status,
+        headers,
+        body: String::from_utf8_lossy(&body).to_string(),
+    })
 }
 
 /// Input arguments of the program.
-struct InuputArguments {
+#[derive(Args, Default)]
+pub struct InuputArguments {
+    /// The city name, e.g. London.
+    #[arg(long)]
     city: Option<String>,
+    /// The OpenWeather API key (to get one for free, sign up here -> https://openweathermap.org/home/sign_up).
+    #[arg(long)]
     api_key: Option<String>,
 }
 
+/// The `weather.observation` output record.
+#[derive(Serialize)]
+struct ObservationRecord<'a> {
+    city: &'a str,
+    /// The HTTP status code of the OpenWeather API response.
+    status: u16,
+    /// The OpenWeather API response body, or `null` if it is not valid JSON.
+    data: Option<serde_json::Value>,
+}
+
 struct OpenWeather;
 
 impl OpenWeather {
     /// Program constructor.
-    fn new() -> OpenWeather {
+    fn new(args: InuputArguments) -> OpenWeather {
         let mut program = OpenWeather;
-        program.init();
+        program.init(args);
         program
     }
 
     /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "Open weather initialized.".blue().bold());
-
-        let args = self.args();
+    fn init(&mut self, args: InuputArguments) {
+        human_println!("\n{}", "Open weather initialized.".blue().bold());
 
         self.weather(args.city, args.api_key);
     }
 
-    /// Parses arguments passed to the program.
-    fn args(&mut self) -> InuputArguments {
-        let arguments: Vec<String> = args().collect();
-
-        println!("\n{}:\n{:?}", "Arguments".cyan().bold(), arguments);
-
-        InuputArguments {
-            city: arguments.get(2).cloned(),
-            api_key: arguments.get(3).cloned(),
-        }
-    }
-
     /// Processes the input arguments and send a request to get weather data.
     fn weather(&mut self, city_arg: Option<String>, api_key_arg: Option<String>) {
-        println!(
+        human_println!(
             "\n{}",
             "Current weather by city name using OpenWeather API.".cyan()
         );
 
-        let city_is_some = city_arg.is_some();
-        let mut city_arg_input = if city_is_some {
-            match city_arg.unwrap().trim().parse::<String>() {
-                Ok(value) => value,
-                Err(_) => String::new(),
-            }
-        } else {
-            String::new()
-        };
-
-        let api_key_is_some = api_key_arg.is_some();
-        let mut api_key_arg_input = if api_key_is_some {
-            match api_key_arg.unwrap().trim().parse::<String>() {
-                Ok(value) => value,
-                Err(_) => String::new(),
-            }
-        } else {
-            String::new()
-        };
+        let mut city_arg_input = city_arg.unwrap_or_default();
+
+        let mut api_key_arg_input = api_key_arg.unwrap_or_default();
 
         let mut city_input = String::new();
 
@@ -87,11 +128,9 @@ impl OpenWeather {
 
         loop {
             if city_arg_input.trim().is_empty() && city_input.trim().is_empty() {
-                println!("\n{}", "Please input a city:".yellow().bold());
+                human_println!("\n{}", "Please input a city:".yellow().bold());
 
-                io::stdin()
-                    .read_line(&mut city_input)
-                    .expect("Failed to read line");
+                city_input = prompt::read_line("A city (--city)");
             } else if city_input.trim().is_empty() {
                 city_input = city_arg_input.to_string();
             }
@@ -99,11 +138,9 @@ impl OpenWeather {
             let mut city = "";
 
             if api_key_arg_input.trim().is_empty() && api_key_input.trim().is_empty() {
-                println!("\n{}", "Please input an API key (to get one for free, sign up here -> https:///openweathermap.org/home/sign_up):".yellow());
+                human_println!("\n{}", "Please input an API key (to get one for free, sign up here -> https:///openweathermap.org/home/sign_up):".yellow());
 
-                io::stdin()
-                    .read_line(&mut api_key_input)
-                    .expect("Failed to read line");
+                api_key_input = prompt::read_line("An API key (--api-key)");
             } else if api_key_input.trim().is_empty() {
                 api_key_input = api_key_arg_input.to_string();
             }
@@ -128,67 +165,36 @@ impl OpenWeather {
                     .build()
                     .unwrap();
 
+                redact::register(api_key);
+
                 runtime.block_on(async {
-                    let result = self.weather_request(city, api_key).await;
+                    let result = weather_request(city, api_key).await;
                     match result {
-                        Ok(data) => data,
-                        Err(error) => println!("\n{}: {:?}", "There was an error".red(), error),
+                        Ok(response) => {
+                            human_println!("Response: {}", response.status);
+                            log::debug!("headers {:?}", response.headers);
+                            human_println!("{}", response.body);
+                            human_println!("\n\n{}", "Done!".green().bold());
+
+                            output::emit(
+                                "weather.observation",
+                                &ObservationRecord {
+                                    city,
+                                    status: response.status.as_u16(),
+                                    data: serde_json::from_str(&response.body).ok(),
+                                },
+                            );
+                        }
+                        Err(error) => {
+                            human_println!("\n{}: {:?}", "There was an error".red(), error)
+                        }
                     };
                 });
                 break;
             }
         }
     }
-
-    /// Weather data request logic.
-    async fn weather_request(&mut self, city: &str, api_key: &str) -> Result<()> {
-        let mut uri_with_params = String::from("http://api.openweathermap.org/data/2.5/weather");
-        uri_with_params.push_str("?q=");
-        uri_with_params.push_str(city);
-        uri_with_params.push_str("&appid=");
-        uri_with_params.push_str(api_key);
-
-        println!("\nUri, {}", uri_with_params);
-
-        let uri = uri_with_params.as_str().parse::<Uri>()?;
-
-        let host = uri.host().expect("uri has no host");
-        let port = uri.port_u16().unwrap_or(80);
-        let addr = format!("{}:{}", host, port);
-        let stream = TcpStream::connect(addr).await?;
-        let io = TokioIo::new(stream);
-
-        let (mut sender, conn) = hyper::client::conn::http1::handshake(io).await?;
-        tokio::task::spawn(async move {
-            if let Err(err) = conn.await {
-                println!("Connection failed: {:?}", err);
-            }
-        });
-
-        let authority = uri.authority().unwrap().clone();
-
-        let path = uri.path();
-        let req = Request::builder()
-            .uri(path)
-            .header(hyper::header::HOST, authority.as_str())
-            .body(Empty::<Bytes>::new())?;
-
-        let mut res = sender.send_request(req).await?;
-
-        println!("Response: {}", res.status());
-        println!("Headers: {:#?}\n", res.headers());
-
-        // Stream the body, writing each chunk to stdout as we get it
-        // (instead of buffering and printing at the end).
-        while let Some(next) = res.frame().await {
-            let frame = next?;
-            if let Some(chunk) = frame.data_ref() {
-                io::stdout().write_all(chunk)?;
-            }
-        }
-
-        println!("\n\n{}", "Done!".green().bold());
-
-        Ok(())
-    }
 }
+
+#[cfg(test)]
+mod tests;
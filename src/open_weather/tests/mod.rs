@@ -0,0 +1,16 @@
+mod open_weather {
+    use hyper::Uri;
+
+    use crate::open_weather;
+
+    #[test]
+    fn weather_uri() {
+        let uri = open_weather::weather_uri("London", "key").unwrap();
+        assert!(uri.ends_with("/weather?q=London&appid=key"));
+
+        let uri = open_weather::weather_uri("New York&appid=other/../#", "key").unwrap();
+        assert!(uri.ends_with("?q=New+York%26appid%3Dother%2F..%2F%23&appid=key"));
+        let uri = uri.parse::<Uri>().unwrap();
+        assert_eq!(uri.path(), "/data/2.5/weather");
+    }
+}
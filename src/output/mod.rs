@@ -0,0 +1,159 @@
+//! Output module.
+//!
+//! Programs print human-readable text with the `human_println!` macro, and machine-readable records
+//! with the `emit` function. The text output mode prints only the human-readable text.
+//! The JSON output modes print the records to the standard output, and redirect
+//! the human-readable text to the standard error.
+//!
+//! Each record is a JSON object with the `type` field, e.g. `calculator.result`,
+//! and the fields of the record type documented in the README.
+//! In a console session, the records are captured in the transcript, see the `console` module.
+
+use clap::ValueEnum;
+use serde::Serialize;
+use std::{
+    io::{self, Write},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Mutex,
+    },
+};
+
+use crate::console;
+
+/// The output format of the programs.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// A JSON array of records, printed when the program finishes.
+    Json,
+    /// Newline-delimited JSON records, printed as soon as they are emitted.
+    Ndjson,
+}
+
+/// The output format, stored as the index of the `OutputFormat` variant.
+static FORMAT: AtomicU8 = AtomicU8::new(OutputFormat::Text as u8);
+
+/// The records buffered in the `json` output mode.
+static RECORDS: Mutex<Vec<serde_json::Value>> = Mutex::new(vec![]);
+
+/// Prints the human-readable text to the standard output in the text output mode,
+/// and to the standard error otherwise, see the `console` module.
+#[macro_export]
+macro_rules! human_println {
+    ($($arg:tt)*) => {
+        $crate::console::print(format_args!($($arg)*))
+    };
+}
+
+/// Prints the error text to the standard error, see the `console` module.
+#[macro_export]
+macro_rules! human_eprintln {
+    ($($arg:tt)*) => {
+        $crate::console::eprint(format_args!($($arg)*))
+    };
+}
+
+/// A record with its type.
+#[derive(Serialize)]
+struct Record<'a, T: Serialize> {
+    #[serde(rename = "type")]
+    record_type: &'a str,
+    #[serde(flatten)]
+    data: &'a T,
+}
+
+/// Configures the output format.
+pub fn init(format: OutputFormat) {
+    FORMAT.store(format as u8, Ordering::Relaxed);
+}
+
+/// The output format of the console session, or of the process.
+pub fn format() -> OutputFormat {
+    if let Some(format) = console::format() {
+        return format;
+    }
+    match FORMAT.load(Ordering::Relaxed) {
+        value if value == OutputFormat::Json as u8 => OutputFormat::Json,
+        value if value == OutputFormat::Ndjson as u8 => OutputFormat::Ndjson,
+        _ => OutputFormat::Text,
+    }
+}
+
+/// Whether the output format is the human-readable text.
+pub fn is_text() -> bool {
+    format() == OutputFormat::Text
+}
+
+/// Emits the record of the type in the JSON output modes, or captures it in the console session.
+/// The data should serialize to a JSON object.
+pub fn emit<T: Serialize>(record_type: &str, data: &T) {
+    let format = format();
+    if format == OutputFormat::Text {
+        return;
+    }
+    let Some(record) = console::record(record(record_type, data)) else {
+        return;
+    };
+    match format {
+        OutputFormat::Text => {}
+        OutputFormat::Json => records().push(record),
+        OutputFormat::Ndjson => {
+            let mut stdout = io::stdout().lock();
+            let _ = write_line(&mut stdout, &record);
+            let _ = stdout.flush();
+        }
+    }
+}
+
+/// Prints the records buffered in the `json` output mode.
+/// Should be called before the process exits.
+pub fn finish() {
+    if format() != OutputFormat::Json {
+        return;
+    }
+
+    let records = std::mem::take(&mut *records());
+    let _ = write_document(&mut io::stdout().lock(), &records);
+}
+
+/// Writes the records as printed in the output format: a JSON array, a record per line, or nothing.
+pub fn write_records(
+    out: &mut impl Write,
+    format: OutputFormat,
+    records: &[serde_json::Value],
+) -> io::Result<()> {
+    match format {
+        OutputFormat::Text => Ok(()),
+        OutputFormat::Json => write_document(out, records),
+        OutputFormat::Ndjson => records
+            .iter()
+            .try_for_each(|record| write_line(out, record)),
+    }
+}
+
+/// The record of the type: a JSON object with the `type` field and the fields of the data.
+fn record<T: Serialize>(record_type: &str, data: &T) -> serde_json::Value {
+    serde_json::to_value(Record { record_type, data }).expect("Can not serialize the record")
+}
+
+/// Writes the record as a line of the `ndjson` output mode.
+fn write_line(out: &mut impl Write, record: &serde_json::Value) -> io::Result<()> {
+    writeln!(out, "{}", record)
+}
+
+/// Writes the records as the JSON array of the `json` output mode.
+fn write_document(out: &mut impl Write, records: &[serde_json::Value]) -> io::Result<()> {
+    let document = serde_json::to_string_pretty(records).expect("Can not serialize the records");
+    writeln!(out, "{}", document)
+}
+
+/// The buffered records.
+fn records() -> std::sync::MutexGuard<'static, Vec<serde_json::Value>> {
+    RECORDS.lock().unwrap_or_else(|error| error.into_inner())
+}
+
+#[cfg(test)]
+mod tests;
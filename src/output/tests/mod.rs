@@ -0,0 +1,144 @@
+mod output {
+    use serde_json::{json, Value};
+
+    use crate::calculator::{
+        self,
+        number::{Mode, Number},
+        ErrorRecord, ResultRecord, ValueFields,
+    };
+    use crate::data_pipeline::{Collection, Progress};
+    use crate::output::{emit, format, record, records, write_document, write_line, OutputFormat};
+
+    /// The sorted field names of the record.
+    fn fields(record: &Value) -> Vec<&str> {
+        let mut fields: Vec<&str> = record
+            .as_object()
+            .expect("The record is a JSON object")
+            .keys()
+            .map(String::as_str)
+            .collect();
+        fields.sort_unstable();
+        fields
+    }
+
+    /// The `calculator.result` record of the expression.
+    fn result_record(expression: &str, variable: Option<&str>) -> Value {
+        let value = calculator::evaluate(expression).unwrap();
+        record(
+            "calculator.result",
+            &ResultRecord {
+                expression,
+                value: ValueFields::new(&value, Mode::Float, variable),
+            },
+        )
+    }
+
+    #[test]
+    fn text_framing() {
+        assert_eq!(format(), OutputFormat::Text);
+        emit("calculator.plot", &json!({"path": "plot.svg"}));
+        assert!(records().is_empty());
+    }
+
+    #[test]
+    fn ndjson_framing() {
+        let records = [
+            record("calculator.plot", &json!({"path": "plot.svg"})),
+            record("config.value", &json!({"key": "a\nb", "value": 1})),
+        ];
+        let mut out = Vec::new();
+        for record in &records {
+            write_line(&mut out, record).unwrap();
+        }
+
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.ends_with('\n'));
+        let lines: Vec<Value> = out
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines, records);
+    }
+
+    #[test]
+    fn json_framing() {
+        let records = [
+            record("calculator.plot", &json!({"path": "plot.svg"})),
+            record("calculator.plot", &json!({"path": "other.svg"})),
+        ];
+        let mut out = Vec::new();
+        write_document(&mut out, &records).unwrap();
+        let document: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(document, Value::Array(records.to_vec()));
+
+        let mut out = Vec::new();
+        write_document(&mut out, &[]).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap().trim(), "[]");
+    }
+
+    #[test]
+    fn calculator_record_shapes() {
+        let record = result_record("2+3", None);
+        assert_eq!(record["type"], "calculator.result");
+        assert_eq!(fields(&record), ["expression", "result", "type"]);
+        assert_eq!(record["result"], 5.0);
+
+        let record = result_record("3+4i", Some("z"));
+        assert_eq!(
+            fields(&record),
+            ["expression", "imaginary", "result", "type", "variable"]
+        );
+
+        let record = result_record("[1,2;3,4]", None);
+        assert_eq!(fields(&record), ["expression", "matrix", "result", "type"]);
+        assert_eq!(record["result"], Value::Null);
+
+        let record = result_record("3 km in mi", None);
+        assert_eq!(fields(&record), ["expression", "result", "type", "unit"]);
+
+        let value = Number::Float(2f64.sqrt());
+        let record = crate::output::record(
+            "calculator.result",
+            &ResultRecord {
+                expression: "sqrt(2)",
+                value: ValueFields::new(&value, Mode::Decimal(20), None),
+            },
+        );
+        assert_eq!(
+            fields(&record),
+            ["approximate", "expression", "result", "type"]
+        );
+        assert_eq!(record["approximate"], true);
+
+        let error = calculator::evaluate("1+").unwrap_err();
+        let record = crate::output::record("calculator.error", &ErrorRecord::new("1+", &error));
+        assert_eq!(record["type"], "calculator.error");
+        assert_eq!(fields(&record), ["error", "expression", "type"]);
+        assert_eq!(record["error"], error.describe());
+
+        let error = calculator::evaluate("pj").unwrap_err();
+        let record = crate::output::record("calculator.error", &ErrorRecord::new("pj", &error));
+        assert_eq!(fields(&record), ["error", "expression", "hint", "type"]);
+    }
+
+    #[test]
+    fn data_pipeline_record_shapes() {
+        let record = record(
+            "data_pipeline.progress",
+            &Progress {
+                collection: Collection::Workflows,
+                progress: 1,
+                total: 2,
+            },
+        );
+        assert_eq!(
+            record,
+            json!({
+                "type": "data_pipeline.progress",
+                "collection": "workflows",
+                "progress": 1,
+                "total": 2,
+            })
+        );
+    }
+}
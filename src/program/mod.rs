@@ -0,0 +1,237 @@
+//! Program module.
+//!
+//! A program is a named entry point with a description, an argument schema, and a `run` function.
+//! Programs are collected in a registry, which drives the interactive menu, the help output,
+//! and the dispatch of subcommands at every level of nesting.
+
+use clap::{ArgMatches, Args, Command};
+use colored::Colorize;
+use std::marker::PhantomData;
+
+use crate::prompt;
+
+/// A program that can be registered in a `Registry`.
+pub trait Program {
+    /// The subcommand name of the program.
+    fn name(&self) -> &'static str;
+
+    /// The description of the program, used in the menu and in the help output.
+    fn description(&self) -> &'static str;
+
+    /// Adds the arguments of the program to the command.
+    fn args(&self, command: Command) -> Command {
+        command
+    }
+
+    /// Runs the program with the parsed arguments.
+    fn run(&self, matches: &ArgMatches);
+}
+
+/// A program with clap arguments and a `main` function.
+pub struct Entry<A> {
+    name: &'static str,
+    description: &'static str,
+    main: fn(A),
+    arguments: PhantomData<A>,
+}
+
+impl<A> Entry<A> {
+    /// Entry constructor.
+    pub fn new(name: &'static str, description: &'static str, main: fn(A)) -> Entry<A> {
+        Entry {
+            name,
+            description,
+            main,
+            arguments: PhantomData,
+        }
+    }
+}
+
+impl<A: Args> Program for Entry<A> {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn args(&self, command: Command) -> Command {
+        A::augment_args(command)
+    }
+
+    fn run(&self, matches: &ArgMatches) {
+        let args = A::from_arg_matches(matches).unwrap_or_else(|error| error.exit());
+        (self.main)(args);
+    }
+}
+
+/// A program with nested programs.
+pub struct Group {
+    name: &'static str,
+    description: &'static str,
+    registry: Registry,
+}
+
+impl Group {
+    /// Group constructor.
+    pub fn new(name: &'static str, description: &'static str, registry: Registry) -> Group {
+        Group {
+            name,
+            description,
+            registry,
+        }
+    }
+}
+
+impl Program for Group {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn description(&self) -> &'static str {
+        self.description
+    }
+
+    fn args(&self, command: Command) -> Command {
+        self.registry.command(command)
+    }
+
+    fn run(&self, matches: &ArgMatches) {
+        self.registry.dispatch(matches);
+    }
+}
+
+/// Registered programs, in the order of the interactive menu.
+pub struct Registry {
+    kind: &'static str,
+    programs: Vec<Box<dyn Program>>,
+}
+
+impl Registry {
+    /// Registry constructor.
+    /// The kind is the name of the registered items used in the menu, e.g. `program`.
+    pub fn new(kind: &'static str) -> Registry {
+        Registry {
+            kind,
+            programs: vec![],
+        }
+    }
+
+    /// Registers the program.
+    pub fn register(mut self, program: impl Program + 'static) -> Self {
+        self.programs.push(Box::new(program));
+        self
+    }
+
+    /// The registered programs.
+    pub fn programs(&self) -> impl Iterator<Item = &dyn Program> {
+        self.programs.iter().map(|program| program.as_ref())
+    }
+
+    /// The registered program with the name.
+    pub fn get(&self, name: &str) -> Option<&dyn Program> {
+        self.programs().find(|program| program.name() == name)
+    }
+
+    /// Adds the registered programs to the command as subcommands.
+    pub fn command(&self, command: Command) -> Command {
+        command.subcommands(self.programs().map(subcommand))
+    }
+
+    /// Runs the program selected by the subcommand, or the program selected by the user
+    /// with the default arguments if there is no subcommand.
+    pub fn dispatch(&self, matches: &ArgMatches) {
+        match matches
+            .subcommand()
+            .and_then(|(name, matches)| Some((self.get(name)?, matches)))
+        {
+            Some((program, matches)) => program.run(matches),
+            None => {
+                let program = self.choose(None);
+                let matches = subcommand(program)
+                    .try_get_matches_from([program.name()])
+                    .unwrap_or_else(|error| error.exit());
+                program.run(&matches);
+            }
+        }
+    }
+
+    /// Prompts input from the user, processes it, and returns the selected program.
+    /// The input can be either an index or a program name.
+    pub fn choose(&self, program_arg: Option<String>) -> &dyn Program {
+        let options: Vec<(&str, &str)> = self
+            .programs()
+            .map(|program| (program.name(), program.description()))
+            .collect();
+        self.programs[choose(self.kind, "subcommand", &options, program_arg)].as_ref()
+    }
+}
+
+/// Prompts the user to choose one of the options, named and described, unless the argument is one,
+/// and returns the index of the chosen option.
+/// The input can be either an index or an option name; the kind names the options in the menu,
+/// and the argument names the command-line argument that chooses the option without the menu.
+pub fn choose(
+    kind: &str,
+    argument: &str,
+    options: &[(&str, &str)],
+    option_arg: Option<String>,
+) -> usize {
+    let mut option_arg_input = option_arg.unwrap_or_default();
+
+    loop {
+        let option_input = if option_arg_input.is_empty() {
+            print_instructions(kind, options);
+
+            prompt::read_line(&format!("A {} ({})", kind, argument))
+        } else {
+            option_arg_input.to_string()
+        };
+
+        match option_index(options, &option_input) {
+            Some(index) => {
+                human_println!("You selected: {}", options[index].0);
+                return index;
+            }
+            None => {
+                human_println!("\n{}", format!("The {} does not exist.", kind).red());
+                option_arg_input = String::new();
+            }
+        }
+    }
+}
+
+/// Prints the option selection instructions.
+fn print_instructions(kind: &str, options: &[(&str, &str)]) {
+    human_println!("\n{}", format!("Available {}s:", kind).yellow().bold());
+
+    for (i, (name, description)) in options.iter().enumerate() {
+        human_println!("{}: {} - {}", i, name, description);
+    }
+
+    human_println!(
+        "\n{}, [0-{}]:",
+        format!("Please select a {}", kind).yellow().bold(),
+        options.len() - 1
+    );
+}
+
+/// Resolves the option index from the user input, which can be either an index or an option name.
+fn option_index(options: &[(&str, &str)], input: &str) -> Option<usize> {
+    let input = input.trim();
+    match input.parse::<usize>() {
+        Ok(index) if index < options.len() => Some(index),
+        Ok(_) => None,
+        Err(_) => options.iter().position(|(name, _)| *name == input),
+    }
+}
+
+/// The subcommand of the program.
+fn subcommand(program: &dyn Program) -> Command {
+    // The description is set after the arguments, as the documentation of the argument structs
+    // would replace it.
+    program
+        .args(Command::new(program.name()))
+        .about(program.description())
+}
@@ -0,0 +1,65 @@
+//! Prompt module.
+//!
+//! All programs read user input through this module, so that the non-interactive mode
+//! turns every prompt into an error instead of blocking on the standard input.
+
+use colored::Colorize;
+use std::{
+    io::{self, IsTerminal},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::console;
+
+/// The exit code used when an input is required but can't be prompted.
+pub const EXIT_CODE: i32 = 2;
+
+/// Whether the programs are allowed to prompt input from the user.
+static INTERACTIVE: AtomicBool = AtomicBool::new(true);
+
+/// Configures the prompt mode.
+/// The mode is non-interactive if requested explicitly or if the standard input is not a terminal.
+pub fn init(non_interactive: bool) {
+    let interactive = !non_interactive && io::stdin().is_terminal();
+    INTERACTIVE.store(interactive, Ordering::Relaxed);
+}
+
+/// Whether the programs are allowed to prompt input from the user.
+/// A console session overrides the process mode.
+pub fn is_interactive() -> bool {
+    console::interactive().unwrap_or_else(|| INTERACTIVE.load(Ordering::Relaxed))
+}
+
+/// Reads a line from the console input.
+/// Exits the process with an error if the input can't be prompted.
+pub fn read_line(input_name: &str) -> String {
+    read_line_until_eof(input_name)
+        .unwrap_or_else(|| fail(input_name, "the standard input is closed"))
+}
+
+/// Reads a line from the console input; `None` if the input is closed, e.g. with `Ctrl-D`.
+/// Exits the process with an error if the input can't be prompted.
+pub fn read_line_until_eof(input_name: &str) -> Option<String> {
+    if !is_interactive() {
+        fail(input_name, "the program runs in the non-interactive mode");
+    }
+
+    let mut input = String::new();
+    match console::read_line(&mut input) {
+        Ok(0) => None,
+        Ok(_) => Some(input),
+        Err(error) => fail(input_name, &error.to_string()),
+    }
+}
+
+/// Reports the missing input and exits the process.
+fn fail(input_name: &str, reason: &str) -> ! {
+    human_eprintln!(
+        "\n{}: {} is required, but {}.\n{}",
+        "Error".red().bold(),
+        input_name,
+        reason,
+        "Provide a valid value with command line arguments, see --help.".yellow()
+    );
+    console::exit(EXIT_CODE);
+}
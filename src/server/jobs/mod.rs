@@ -0,0 +1,184 @@
+//! Jobs submodule.
+//!
+//! Data pipeline runs take minutes, so the server runs them as background jobs
+//! and exposes their status instead of holding the request open.
+//! The jobs of a collection write the same files and documents, so they run one at a time.
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    panic,
+    sync::{Arc, Mutex, MutexGuard},
+    thread,
+};
+use thiserror::Error;
+
+use crate::data_pipeline::{self, error, Collection, Context, ErrorCategory, Options};
+
+/// The data pipeline job request.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct JobRequest {
+    pub context: Context,
+    pub collection: Collection,
+    /// The GitHub user, required by the collect context.
+    #[serde(default)]
+    pub user: Option<String>,
+}
+
+/// The status of a job.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// The failure of a job, with the category and the exit code the CLI would use.
+#[derive(Clone, Debug, Serialize)]
+pub struct JobError {
+    pub category: &'static str,
+    pub exit_code: i32,
+    pub message: String,
+    /// The cause chain, from the direct cause to the root cause.
+    pub causes: Vec<String>,
+}
+
+/// A data pipeline job.
+#[derive(Clone, Debug, Serialize)]
+pub struct Job {
+    pub id: u64,
+    #[serde(flatten)]
+    pub request: JobRequest,
+    pub status: Status,
+    pub error: Option<JobError>,
+}
+
+/// The submission failure when a job of the collection is queued or running.
+#[derive(Debug, Error)]
+#[error("the job {id} of the collection {:?} is not finished", .collection.name())]
+pub struct Conflict {
+    pub id: u64,
+    pub collection: Collection,
+}
+
+/// The jobs of the server, shared between the request handlers and the job threads.
+#[derive(Clone, Default)]
+pub struct Jobs {
+    jobs: Arc<Mutex<BTreeMap<u64, Job>>>,
+    /// The options of the data pipeline runs, loaded when the server starts.
+    options: Arc<Options>,
+}
+
+impl Jobs {
+    /// Jobs constructor.
+    pub fn new(options: Options) -> Jobs {
+        Jobs {
+            jobs: Arc::default(),
+            options: Arc::new(options),
+        }
+    }
+
+    /// Queues the job and runs it in a background thread,
+    /// unless a job of the collection is queued or running.
+    pub fn submit(&self, request: JobRequest) -> Result<Job, Conflict> {
+        let job = self.insert(request)?;
+        let jobs = self.clone();
+        let id = job.id;
+        thread::spawn(move || jobs.run(id));
+        Ok(job)
+    }
+
+    /// The job with the id.
+    pub fn get(&self, id: u64) -> Option<Job> {
+        self.lock().get(&id).cloned()
+    }
+
+    /// All jobs, in submission order.
+    pub fn list(&self) -> Vec<Job> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Adds a queued job, unless a job of the collection is queued or running.
+    fn insert(&self, request: JobRequest) -> Result<Job, Conflict> {
+        let mut jobs = self.lock();
+        let unfinished = jobs.values().find(|job| {
+            job.request.collection == request.collection
+                && matches!(job.status, Status::Queued | Status::Running)
+        });
+        if let Some(job) = unfinished {
+            return Err(Conflict {
+                id: job.id,
+                collection: request.collection,
+            });
+        }
+        let id = jobs.keys().next_back().map_or(1, |id| id + 1);
+        let job = Job {
+            id,
+            request,
+            status: Status::Queued,
+            error: None,
+        };
+        jobs.insert(id, job.clone());
+        Ok(job)
+    }
+
+    /// Runs the job and records its outcome.
+    fn run(&self, id: u64) {
+        let Some(job) = self.update(id, |job| job.status = Status::Running) else {
+            return;
+        };
+        let request = job.request;
+        let result = panic::catch_unwind(|| {
+            data_pipeline::run(
+                request.context,
+                request.collection,
+                request.user.as_deref().unwrap_or_default(),
+                &self.options,
+                |progress| log::debug!("job {} progress {:?}", id, progress),
+            )
+        });
+        let error = match result {
+            Ok(Ok(_)) => None,
+            Ok(Err(error)) => {
+                let category = error.category();
+                Some(JobError {
+                    category: category.name(),
+                    exit_code: category.exit_code(),
+                    message: error.to_string(),
+                    causes: error::causes(&error),
+                })
+            }
+            Err(_) => Some(JobError {
+                category: ErrorCategory::Internal.name(),
+                exit_code: ErrorCategory::Internal.exit_code(),
+                message: String::from("the job panicked"),
+                causes: vec![],
+            }),
+        };
+        self.update(id, |job| {
+            job.status = match error {
+                None => Status::Succeeded,
+                Some(_) => Status::Failed,
+            };
+            job.error = error;
+        });
+    }
+
+    /// Updates the job and returns the updated job.
+    fn update(&self, id: u64, update: impl FnOnce(&mut Job)) -> Option<Job> {
+        let mut jobs = self.lock();
+        let job = jobs.get_mut(&id)?;
+        update(job);
+        Some(job.clone())
+    }
+
+    /// Locks the jobs; a job thread panicking doesn't make the other jobs unavailable.
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<u64, Job>> {
+        self.jobs.lock().unwrap_or_else(|error| error.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,30 @@
+mod jobs {
+    use crate::data_pipeline::{Collection, Context};
+    use crate::server::jobs::{JobRequest, Jobs, Status};
+
+    fn request(collection: Collection) -> JobRequest {
+        JobRequest {
+            context: Context::Restore,
+            collection,
+            user: None,
+        }
+    }
+
+    #[test]
+    fn one_job_per_collection() {
+        let jobs = Jobs::default();
+        let job = jobs.insert(request(Collection::Repos)).unwrap();
+        assert_eq!(job.status, Status::Queued);
+
+        let conflict = jobs.insert(request(Collection::Repos)).unwrap_err();
+        assert_eq!(conflict.id, job.id);
+        assert!(jobs.insert(request(Collection::Workflows)).is_ok());
+
+        jobs.update(job.id, |job| job.status = Status::Running);
+        assert!(jobs.insert(request(Collection::Repos)).is_err());
+
+        jobs.update(job.id, |job| job.status = Status::Failed);
+        assert_eq!(jobs.insert(request(Collection::Repos)).unwrap().id, 3);
+        assert_eq!(jobs.list().len(), 3);
+    }
+}
@@ -0,0 +1,414 @@
+//! Server module.
+//!
+//! Serves the programs as a JSON HTTP API:
+//!
+//! - `GET /health`
+//! - `POST /calculator` with `{"expression": "1+2"}`
+//! - `GET /system/{information,processes,components,disks,memory}`
+//! - `GET /weather?city=London`, with the API key of the `OPENWEATHER_API_KEY` environment variable
+//! - `POST /linfa-train/{logistic-regression,decision-tree}/predict` with `{"records": [[...]]}`
+//! - `POST /data-pipeline/jobs` with `{"context": "collect", "collection": "repos", "user": "..."}`
+//! - `GET /data-pipeline/jobs` and `GET /data-pipeline/jobs/{id}`
+//!
+//! Errors are returned as `{"error": "..."}` with a 4xx or 5xx status.
+//! Request bodies are limited to `MAX_BODY_SIZE` bytes.
+
+use clap::Args;
+use colored::Colorize;
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::{
+    body::{Body, Bytes},
+    header, Method, Request, Response, StatusCode, Uri,
+};
+use hyper_util::rt::TokioIo;
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+use std::{collections::HashMap, convert::Infallible, env, error::Error, io, sync::Arc};
+use sysinfo::System;
+use thiserror::Error;
+use tokio::{net::TcpListener, task};
+
+use crate::{
+    calculator, config, console,
+    data_pipeline::{self, Context, Credentials, Options},
+    linfa_train::{self, DecisionTreeTrainer, LogisticRegressionTrainer},
+    open_weather, redact, system_information,
+};
+
+pub mod jobs;
+
+use self::jobs::{JobRequest, Jobs};
+
+/// The exit code used when the server can't start.
+pub const EXIT_CODE: i32 = 9;
+
+/// The largest request body, in bytes.
+pub const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// The entry point of the program.
+pub fn main(args: InuputArguments) {
+    let address = args
+        .address
+        .unwrap_or_else(|| config::get().server.address.clone());
+
+    if let Err(error) = serve(&address) {
+        human_eprintln!("\n{}: {}", "Server error".red().bold(), error);
+        console::exit(EXIT_CODE);
+    }
+}
+
+/// Input arguments of the program.
+#[derive(Args, Default)]
+pub struct InuputArguments {
+    /// The socket address to listen on [default: `server.address`].
+    #[arg(long)]
+    address: Option<String>,
+}
+
+/// Server failure.
+#[derive(Debug, Error)]
+pub enum ServerError {
+    #[error("can't start the async runtime")]
+    Runtime(#[source] io::Error),
+    #[error("can't listen on {address}")]
+    Bind {
+        address: String,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// The state of the server, shared between the request handlers.
+#[derive(Clone, Default)]
+pub struct State {
+    pub jobs: Jobs,
+    /// The OpenWeather API key, registered for redaction when the server starts.
+    pub weather_api_key: Option<Arc<str>>,
+}
+
+impl State {
+    /// The state with the options of the data pipeline jobs and the OpenWeather API key
+    /// of the environment.
+    fn load() -> State {
+        let weather_api_key = env::var(open_weather::API_KEY_VAR)
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+        if let Some(key) = &weather_api_key {
+            redact::register(key);
+        }
+        State {
+            jobs: Jobs::new(job_options()),
+            weather_api_key: weather_api_key.map(|key| Arc::from(key.trim())),
+        }
+    }
+}
+
+/// A JSON response.
+#[derive(Debug)]
+pub struct Reply {
+    pub status: StatusCode,
+    pub body: Value,
+}
+
+impl Reply {
+    /// A successful response.
+    fn ok(body: Value) -> Reply {
+        Reply {
+            status: StatusCode::OK,
+            body,
+        }
+    }
+
+    /// An error response.
+    fn error(status: StatusCode, message: impl ToString) -> Reply {
+        Reply {
+            status,
+            body: json!({ "error": message.to_string() }),
+        }
+    }
+}
+
+/// Listens on the address and serves the requests until the process is stopped.
+pub fn serve(address: &str) -> Result<(), ServerError> {
+    let state = State::load();
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()
+        .map_err(ServerError::Runtime)?;
+
+    runtime.block_on(async {
+        let listener = TcpListener::bind(address)
+            .await
+            .map_err(|source| ServerError::Bind {
+                address: address.to_owned(),
+                source,
+            })?;
+
+        human_println!(
+            "\n{} http://{}",
+            "Server listening on".blue().bold(),
+            address
+        );
+
+        loop {
+            let (stream, _) = match listener.accept().await {
+                Ok(connection) => connection,
+                Err(error) => {
+                    log::error!("connection failed: {}", error);
+                    continue;
+                }
+            };
+            let state = state.clone();
+
+            task::spawn(async move {
+                let service = hyper::service::service_fn(move |request| {
+                    let state = state.clone();
+                    async move { Ok::<_, Infallible>(respond(&state, request).await) }
+                });
+                if let Err(error) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    log::error!("connection failed: {}", error);
+                }
+            });
+        }
+    })
+}
+
+/// The options of the data pipeline jobs, with the credentials of the env file.
+/// Without the env file, the jobs use the credentials of the process environment.
+fn job_options() -> Options {
+    let config = &config::get().data_pipeline;
+    data_pipeline::options(config).unwrap_or_else(|error| {
+        log::warn!(
+            "the data pipeline jobs use the process environment: {}",
+            error
+        );
+        Options {
+            credentials: Credentials::new(&HashMap::new()),
+            config: config.clone(),
+        }
+    })
+}
+
+/// Reads the request body, routes the request, and logs it.
+async fn respond<B>(state: &State, request: Request<B>) -> Response<Full<Bytes>>
+where
+    B: Body,
+    B::Error: Into<Box<dyn Error + Send + Sync>>,
+{
+    let (parts, body) = request.into_parts();
+
+    let reply = match Limited::new(body, MAX_BODY_SIZE).collect().await {
+        Ok(body) => route(state, &parts.method, &parts.uri, body.to_bytes()).await,
+        Err(error) if error.is::<LengthLimitError>() => Reply::error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("the request body is larger than {} bytes", MAX_BODY_SIZE),
+        ),
+        Err(error) => Reply::error(StatusCode::BAD_REQUEST, error),
+    };
+
+    log::info!("{} {} {}", parts.method, parts.uri.path(), reply.status);
+
+    Response::builder()
+        .status(reply.status)
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Full::new(Bytes::from(reply.body.to_string())))
+        .unwrap_or_default()
+}
+
+/// Routes the request to the handler of the endpoint.
+pub async fn route(state: &State, method: &Method, uri: &Uri, body: Bytes) -> Reply {
+    let jobs = &state.jobs;
+    let segments: Vec<&str> = uri.path().trim_matches('/').split('/').collect();
+
+    match (method, segments.as_slice()) {
+        (&Method::GET, ["health"]) => Reply::ok(json!({ "status": "ok" })),
+        (&Method::POST, ["calculator"]) => calculate(body).await,
+        (&Method::GET, ["system", section]) => system(section).await,
+        (&Method::GET, ["weather"]) => {
+            weather(
+                uri.query().unwrap_or_default(),
+                state.weather_api_key.as_deref(),
+            )
+            .await
+        }
+        (&Method::POST, ["linfa-train", model, "predict"]) => predict(model, &body).await,
+        (&Method::POST, ["data-pipeline", "jobs"]) => submit_job(jobs, &body),
+        (&Method::GET, ["data-pipeline", "jobs"]) => Reply::ok(json!(jobs.list())),
+        (&Method::GET, ["data-pipeline", "jobs", id]) => {
+            match id.parse().ok().and_then(|id| jobs.get(id)) {
+                Some(job) => Reply::ok(json!(job)),
+                None => Reply::error(StatusCode::NOT_FOUND, format!("no job {}", id)),
+            }
+        }
+        _ => Reply::error(
+            StatusCode::NOT_FOUND,
+            format!("no endpoint {} {}", method, uri.path()),
+        ),
+    }
+}
+
+/// Parses the JSON request body.
+fn parse<T: DeserializeOwned>(body: &[u8]) -> Result<T, Reply> {
+    serde_json::from_slice(body).map_err(|error| {
+        Reply::error(
+            StatusCode::BAD_REQUEST,
+            format!("invalid request body: {}", error),
+        )
+    })
+}
+
+/// The calculator request.
+#[derive(Deserialize)]
+struct CalculatorRequest {
+    expression: String,
+}
+
+/// Evaluates the expression, within the evaluation budget of the calculator;
+/// the response has the fields of the `calculator.result` record.
+async fn calculate(body: Bytes) -> Reply {
+    let request: CalculatorRequest = match parse(&body) {
+        Ok(request) => request,
+        Err(reply) => return reply,
+    };
+
+    let expression = request.expression.clone();
+    let result = task::spawn_blocking(move || calculator::evaluate(&expression)).await;
+
+    match result {
+        Ok(Ok(value)) => Reply::ok(json!(calculator::ResultRecord {
+            expression: &request.expression,
+            value: calculator::ValueFields::new(&value, calculator::number::Mode::Float, None),
+        })),
+        Ok(Err(error)) => Reply::error(StatusCode::UNPROCESSABLE_ENTITY, error.describe()),
+        Err(error) => Reply::error(StatusCode::INTERNAL_SERVER_ERROR, error),
+    }
+}
+
+/// A snapshot of the system section.
+async fn system(section: &str) -> Reply {
+    let section = section.to_owned();
+
+    let snapshot = task::spawn_blocking(move || match section.as_str() {
+        "information" => Some(json!(system_information::information())),
+        "processes" => Some(json!(system_information::processes(&System::new_all()))),
+        "components" => Some(json!(system_information::components())),
+        "disks" => Some(json!(system_information::disks())),
+        "memory" => Some(json!(system_information::memory(&System::new_all()))),
+        _ => None,
+    })
+    .await;
+
+    match snapshot {
+        Ok(Some(snapshot)) => Reply::ok(snapshot),
+        Ok(None) => Reply::error(StatusCode::NOT_FOUND, "unknown system section"),
+        Err(error) => Reply::error(StatusCode::INTERNAL_SERVER_ERROR, error),
+    }
+}
+
+/// The weather query; the API key is not accepted, so that clients can't register secrets.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct WeatherQuery {
+    city: String,
+}
+
+/// The current weather of the city, with the API key of the server.
+async fn weather(query: &str, api_key: Option<&str>) -> Reply {
+    let query: WeatherQuery = match serde_urlencoded::from_str(query) {
+        Ok(query) => query,
+        Err(error) => {
+            return Reply::error(StatusCode::BAD_REQUEST, format!("invalid query: {}", error))
+        }
+    };
+    let Some(api_key) = api_key else {
+        return Reply::error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!(
+                "the weather endpoint requires the {} environment variable",
+                open_weather::API_KEY_VAR
+            ),
+        );
+    };
+
+    match open_weather::weather_request(&query.city, api_key).await {
+        Ok(response) => Reply::ok(json!({
+            "city": query.city,
+            "status": response.status.as_u16(),
+            "data": serde_json::from_str::<Value>(&response.body).ok(),
+        })),
+        Err(error) => Reply::error(StatusCode::BAD_GATEWAY, error),
+    }
+}
+
+/// The prediction request; each record is the feature values in the dataset order.
+#[derive(Deserialize)]
+struct PredictRequest {
+    records: Vec<Vec<f32>>,
+}
+
+/// Predicts the targets of the records with the trained model.
+async fn predict(model: &str, body: &[u8]) -> Reply {
+    let request: PredictRequest = match parse(body) {
+        Ok(request) => request,
+        Err(reply) => return reply,
+    };
+    let model = model.to_owned();
+
+    let predictions = task::spawn_blocking(move || -> linfa_train::Result<_> {
+        match model.as_str() {
+            "logistic-regression" => {
+                let trainer = LogisticRegressionTrainer::new();
+                let model = trainer.load_model()?;
+                Ok(Some(json!(trainer.predict(&model, &request.records)?)))
+            }
+            "decision-tree" => {
+                let trainer = DecisionTreeTrainer::new();
+                let model = trainer.load_model()?;
+                Ok(Some(json!(trainer.predict(&model, &request.records)?)))
+            }
+            _ => Ok(None),
+        }
+    })
+    .await;
+
+    match predictions {
+        Ok(Ok(Some(predictions))) => Reply::ok(json!({ "predictions": predictions })),
+        Ok(Ok(None)) => Reply::error(StatusCode::NOT_FOUND, "unknown model"),
+        Ok(Err(error)) => Reply::error(StatusCode::UNPROCESSABLE_ENTITY, error),
+        Err(error) => Reply::error(StatusCode::INTERNAL_SERVER_ERROR, error),
+    }
+}
+
+/// Submits a data pipeline job.
+fn submit_job(jobs: &Jobs, body: &[u8]) -> Reply {
+    let request: JobRequest = match parse(body) {
+        Ok(request) => request,
+        Err(reply) => return reply,
+    };
+
+    let user_missing = request
+        .user
+        .as_deref()
+        .is_none_or(|user| user.trim().is_empty());
+    if request.context == Context::Collect && user_missing {
+        return Reply::error(
+            StatusCode::BAD_REQUEST,
+            "the collect context requires a user",
+        );
+    }
+
+    match jobs.submit(request) {
+        Ok(job) => Reply {
+            status: StatusCode::ACCEPTED,
+            body: json!(job),
+        },
+        Err(conflict) => Reply::error(StatusCode::CONFLICT, conflict),
+    }
+}
+
+#[cfg(test)]
+mod tests;
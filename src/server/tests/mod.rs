@@ -0,0 +1,159 @@
+mod server {
+    use http_body_util::Full;
+    use hyper::{body::Bytes, Method, Request, StatusCode, Uri};
+    use serde_json::json;
+
+    use crate::server::{respond, route, Reply, State, MAX_BODY_SIZE};
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap()
+    }
+
+    /// Routes the request with a fresh job store and without the weather API key.
+    fn request(method: Method, uri: &str, body: &str) -> Reply {
+        let uri = uri.parse::<Uri>().unwrap();
+        runtime().block_on(route(
+            &State::default(),
+            &method,
+            &uri,
+            Bytes::from(body.to_owned()),
+        ))
+    }
+
+    #[test]
+    fn health() {
+        let reply = request(Method::GET, "/health", "");
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(reply.body, json!({ "status": "ok" }));
+    }
+
+    #[test]
+    fn calculator() {
+        let reply = request(Method::POST, "/calculator", r#"{"expression": "1+2*3"}"#);
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(reply.body["result"], json!(7.0));
+
+        let reply = request(Method::POST, "/calculator", r#"{"expression": "3+4i"}"#);
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(reply.body["result"], json!(3.0));
+        assert_eq!(reply.body["imaginary"], json!(4.0));
+
+        let reply = request(
+            Method::POST,
+            "/calculator",
+            r#"{"expression": "[1,2;3,4]"}"#,
+        );
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(reply.body["result"], json!(null));
+        assert_eq!(reply.body["matrix"], json!([[1.0, 2.0], [3.0, 4.0]]));
+
+        let reply = request(
+            Method::POST,
+            "/calculator",
+            r#"{"expression": "3 km in mi"}"#,
+        );
+        assert_eq!(reply.status, StatusCode::OK);
+        assert_eq!(reply.body["unit"], json!("mi"));
+        let miles = reply.body["result"].as_f64().unwrap();
+        assert!((miles - 1.864).abs() < 0.001);
+
+        let reply = request(Method::POST, "/calculator", r#"{"expression": "1+"}"#);
+        assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+        let reply = request(Method::POST, "/calculator", "1+2");
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+
+        let reply = request(
+            Method::POST,
+            "/calculator",
+            r#"{"expression": "load(\"/etc/passwd\", \"x\")"}"#,
+        );
+        assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(!reply.body["error"].to_string().contains("root"));
+    }
+
+    #[test]
+    fn slow_calculation() {
+        let start = std::time::Instant::now();
+        let reply = request(
+            Method::POST,
+            "/calculator",
+            r#"{"expression": "integrate(sin(1/x) * exp(-x^2), x, 0.0001, 10)"}"#,
+        );
+        assert_eq!(reply.status, StatusCode::UNPROCESSABLE_ENTITY);
+        assert!(reply.body["error"].to_string().contains("budget"));
+        assert!(start.elapsed().as_secs() < 2);
+
+        for expression in [
+            "integrate(exp(-x^2), x, 0, 3)",
+            "integrate(sin(x)^2, x, 0, 10*pi)",
+            "minimum((x-1)^2, x, -5, 5)",
+        ] {
+            let body = json!({ "expression": expression }).to_string();
+            let reply = request(Method::POST, "/calculator", &body);
+            assert_eq!(reply.status, StatusCode::OK, "{}", expression);
+        }
+    }
+
+    #[test]
+    fn body_size() {
+        let body = format!(r#"{{"expression": "1{}"}}"#, "+1".repeat(MAX_BODY_SIZE / 2));
+        let request = Request::post("/calculator")
+            .body(Full::new(Bytes::from(body)))
+            .unwrap();
+        let response = runtime().block_on(respond(&State::default(), request));
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let request = Request::post("/calculator")
+            .body(Full::new(Bytes::from(r#"{"expression": "1+1"}"#)))
+            .unwrap();
+        let response = runtime().block_on(respond(&State::default(), request));
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn weather_api_key() {
+        let reply = request(Method::GET, "/weather?city=London&api_key=calculator", "");
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+
+        let reply = request(Method::GET, "/weather?city=London", "");
+        assert_eq!(reply.status, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(crate::redact::registered("calculator"), "calculator");
+    }
+
+    #[test]
+    fn unknown_endpoint() {
+        assert_eq!(
+            request(Method::GET, "/calculator", "").status,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            request(Method::GET, "/system/unknown", "").status,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            request(Method::GET, "/data-pipeline/jobs/1", "").status,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[test]
+    fn job_validation() {
+        let reply = request(
+            Method::POST,
+            "/data-pipeline/jobs",
+            r#"{"context": "restore", "collection": "unknown"}"#,
+        );
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+
+        let reply = request(
+            Method::POST,
+            "/data-pipeline/jobs",
+            r#"{"context": "collect", "collection": "repos"}"#,
+        );
+        assert_eq!(reply.status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -1,201 +1,285 @@
 //! System information module.
 
+use clap::ArgMatches;
 use colored::Colorize;
-use std::{cmp::Ordering, env::args, io};
+use serde::Serialize;
 use sysinfo::{Components, Disks, System};
 
-type Subprograms<'a> = [&'a str; 6];
+use crate::output;
+use crate::program::{Program, Registry};
 
 /// The entry point of the program.
-pub fn main() {
-    SystemInformation::new();
+pub fn main(subprogram: Subprogram) {
+    SystemInformation::new(subprogram);
 }
 
-struct InuputArguments {
-    subprogram: Option<String>,
+/// The subprograms, in the order of the interactive menu.
+pub fn programs() -> Registry {
+    [
+        Subprogram::System,
+        Subprogram::Processes,
+        Subprogram::Components,
+        Subprogram::Disks,
+        Subprogram::Memory,
+        Subprogram::All,
+    ]
+    .into_iter()
+    .fold(Registry::new("subprogram"), Registry::register)
 }
 
-struct SystemInformation;
+/// Supported subprograms.
+#[derive(Clone, Copy)]
+pub enum Subprogram {
+    System,
+    Processes,
+    Components,
+    Disks,
+    Memory,
+    All,
+}
 
-impl SystemInformation {
-    /// Program constructor.
-    fn new() -> SystemInformation {
-        let mut program = SystemInformation;
-        program.init();
-        program
+impl Program for Subprogram {
+    fn name(&self) -> &'static str {
+        match self {
+            Subprogram::System => "system",
+            Subprogram::Processes => "processes",
+            Subprogram::Components => "components",
+            Subprogram::Disks => "disks",
+            Subprogram::Memory => "memory",
+            Subprogram::All => "all",
+        }
     }
 
-    /// Initializes the program.
-    fn init(&mut self) {
-        println!("\n{}", "System information initialized.".blue().bold());
+    fn description(&self) -> &'static str {
+        match self {
+            Subprogram::System => "System information",
+            Subprogram::Processes => "Process information",
+            Subprogram::Components => "Components temperature",
+            Subprogram::Disks => "Disks information",
+            Subprogram::Memory => "Memory information",
+            Subprogram::All => "All information",
+        }
+    }
 
-        let args = self.args();
+    fn run(&self, _matches: &ArgMatches) {
+        main(*self);
+    }
+}
 
-        let subprogram_index = self.choose_subprogram(args.subprogram);
+/// The `system.information` output record.
+#[derive(Serialize)]
+pub struct InformationRecord {
+    pub name: Option<String>,
+    pub kernel_version: Option<String>,
+    pub os_version: Option<String>,
+    pub host_name: Option<String>,
+}
 
-        let mut system = sysinfo::System::new_all();
-        system.refresh_all();
+/// The `system.process` output record.
+#[derive(Serialize)]
+pub struct ProcessRecord {
+    pub pid: u32,
+    pub name: String,
+    pub status: String,
+}
 
-        match subprogram_index {
-            0 => self.print_system_information(system),
-            1 => self.print_processes(system),
-            2 => self.print_components_temperature(system),
-            3 => self.print_disks_info(system),
-            4 => self.print_memory_information(system),
-            5 => self.print_all_information(system),
-            _ => self.print_all_information(system),
-        };
-    }
+/// The `system.component` output record, temperatures are in degrees Celsius.
+#[derive(Serialize)]
+pub struct ComponentRecord {
+    pub label: String,
+    pub temperature: f32,
+    pub max: f32,
+    pub critical: Option<f32>,
+}
 
-    /// Parses arguments passed to the program.
-    fn args(&mut self) -> InuputArguments {
-        let arguments: Vec<String> = args().collect();
+/// The `system.disk` output record, sizes are in bytes.
+#[derive(Serialize)]
+pub struct DiskRecord {
+    pub name: String,
+    pub mount_point: String,
+    pub file_system: String,
+    pub kind: String,
+    pub total_space: u64,
+    pub available_space: u64,
+    pub removable: bool,
+}
 
-        println!("\n{}:\n{:?}", "Arguments".cyan().bold(), arguments);
+/// The `system.memory` output record, sizes are in bytes.
+#[derive(Serialize)]
+pub struct MemoryRecord {
+    pub total_memory: u64,
+    pub used_memory: u64,
+    pub total_swap: u64,
+    pub used_swap: u64,
+}
 
-        InuputArguments {
-            subprogram: arguments.get(2).cloned(),
-        }
+/// The system information.
+pub fn information() -> InformationRecord {
+    InformationRecord {
+        name: System::name(),
+        kernel_version: System::kernel_version(),
+        os_version: System::os_version(),
+        host_name: System::host_name(),
     }
+}
 
-    /// Prompts input from the user, processes it, and returns the selected subprogram index.
-    fn choose_subprogram(&mut self, subprogram_arg: Option<String>) -> usize {
-        let is_some = subprogram_arg.is_some();
-        let mut subprogram_arg_input = if is_some {
-            match subprogram_arg.unwrap().trim().parse::<i32>() {
-                Ok(value) => value.to_string(),
-                Err(_) => String::new(),
-            }
-        } else {
-            String::new()
-        };
-
-        let subprograms: Subprograms = [
-            "System information",
-            "Process information",
-            "Components temperature",
-            "Disks information",
-            "Memory information",
-            "All information",
-        ];
-
-        loop {
-            let mut subprogram_input = String::new();
-
-            if subprogram_arg_input.is_empty() {
-                self.print_instructions(subprograms);
-
-                io::stdin()
-                    .read_line(&mut subprogram_input)
-                    .expect("Failed to read line");
-            } else {
-                subprogram_input = subprogram_arg_input.to_string();
-            }
-
-            let subprogram_index = match subprogram_input.trim().parse::<usize>() {
-                Ok(num) => num,
-                Err(_) => continue,
-            };
-
-            match subprogram_index.cmp(&subprograms.len()) {
-                Ordering::Less => {
-                    return self.select_subprogram(subprograms, subprogram_index);
-                }
-                Ordering::Greater => subprogram_arg_input = self.reset_input_arg(),
-                Ordering::Equal => subprogram_arg_input = self.reset_input_arg(),
-            }
-        }
-    }
+/// The processes of the system.
+pub fn processes(system: &System) -> Vec<ProcessRecord> {
+    system
+        .processes()
+        .iter()
+        .map(|(pid, process)| ProcessRecord {
+            pid: pid.as_u32(),
+            name: process.name().to_owned(),
+            status: process.status().to_string(),
+        })
+        .collect()
+}
 
-    /// Prints the subprogram selection instructions.
-    fn print_instructions(&mut self, subprograms: Subprograms) {
-        println!("\n{}", "Available subprograms:".yellow().bold());
+/// The components with temperature sensors.
+pub fn components() -> Vec<ComponentRecord> {
+    Components::new_with_refreshed_list()
+        .iter()
+        .map(|component| ComponentRecord {
+            label: component.label().to_owned(),
+            temperature: component.temperature(),
+            max: component.max(),
+            critical: component.critical(),
+        })
+        .collect()
+}
 
-        let max_i = subprograms.len() - 1;
-        let mut i = 0;
-        while i <= max_i {
-            println!("{}: {}", i, subprograms[i]);
-            i += 1;
-        }
+/// The disks of the system.
+pub fn disks() -> Vec<DiskRecord> {
+    Disks::new_with_refreshed_list()
+        .iter()
+        .map(|disk| DiskRecord {
+            name: disk.name().to_string_lossy().to_string(),
+            mount_point: disk.mount_point().display().to_string(),
+            file_system: disk.file_system().to_string_lossy().to_string(),
+            kind: disk.kind().to_string(),
+            total_space: disk.total_space(),
+            available_space: disk.available_space(),
+            removable: disk.is_removable(),
+        })
+        .collect()
+}
 
-        println!(
-            "\n{}, [0-{}]:",
-            "Please select a subprogram".yellow().bold(),
-            max_i
-        );
+/// The memory usage of the system.
+pub fn memory(system: &System) -> MemoryRecord {
+    MemoryRecord {
+        total_memory: system.total_memory(),
+        used_memory: system.used_memory(),
+        total_swap: system.total_swap(),
+        used_swap: system.used_swap(),
     }
+}
 
-    /// Resets the input argument to start over if the program does not exist.
-    fn reset_input_arg(&mut self) -> String {
-        println!("\n{}", "The subprogram does not exist.".red());
-        String::new()
+struct SystemInformation;
+
+impl SystemInformation {
+    /// Program constructor.
+    fn new(subprogram: Subprogram) -> SystemInformation {
+        let mut program = SystemInformation;
+        program.init(subprogram);
+        program
     }
 
-    /// Prints selected subprogram and returns the program index.
-    fn select_subprogram(&mut self, subprograms: Subprograms, subprogram_index: usize) -> usize {
-        let subprogram = subprograms[subprogram_index];
-        println!("You selected: {}", subprogram);
-        subprogram_index
+    /// Initializes the program.
+    fn init(&mut self, subprogram: Subprogram) {
+        human_println!("\n{}", "System information initialized.".blue().bold());
+
+        let mut system = sysinfo::System::new_all();
+        system.refresh_all();
+
+        match subprogram {
+            Subprogram::System => self.print_system_information(system),
+            Subprogram::Processes => self.print_processes(system),
+            Subprogram::Components => self.print_components_temperature(system),
+            Subprogram::Disks => self.print_disks_info(system),
+            Subprogram::Memory => self.print_memory_information(system),
+            Subprogram::All => self.print_all_information(system),
+        };
     }
 
     /// Print information about the system.
     fn print_system_information(&mut self, system: System) -> System {
-        println!("\n{}", "System information:".green());
-        println!("System name:           {:?}", System::name());
-        println!("System kernel version: {:?}", System::kernel_version());
-        println!("System OS version:     {:?}", System::os_version());
-        println!("System host name:      {:?}", System::host_name());
+        let record = information();
+        human_println!("\n{}", "System information:".green());
+        human_println!("System name:           {:?}", record.name);
+        human_println!("System kernel version: {:?}", record.kernel_version);
+        human_println!("System OS version:     {:?}", record.os_version);
+        human_println!("System host name:      {:?}", record.host_name);
+        output::emit("system.information", &record);
         system
     }
 
     /// Print the system processes information.
     fn print_processes(&mut self, system: System) -> System {
-        println!("\n{}", "System processes:".green());
-        for (pid, proc_) in system.processes() {
+        human_println!("\n{}", "System processes:".green());
+        for record in processes(&system) {
             let double_indent = "\t\t";
             let indent = "\t";
-            println!(
+            human_println!(
                 "STATUS {}\t|\tPID {}{}|\tNAME {:?}",
-                proc_.status(),
-                pid,
-                if pid.as_u32().lt(&999) {
+                record.status,
+                record.pid,
+                if record.pid.lt(&999) {
                     double_indent
                 } else {
                     indent
                 },
-                proc_.name(),
+                record.name,
             );
+            output::emit("system.process", &record);
         }
         system
     }
 
     /// Print the temperature of the components.
     fn print_components_temperature(&mut self, system: System) -> System {
-        println!("\n{}", "Components temperature:".green());
-        let components = Components::new_with_refreshed_list();
-        for component in &components {
-            println!("{:?}", component);
+        human_println!("\n{}", "Components temperature:".green());
+        for record in components() {
+            human_println!(
+                "{}: {}°C (max: {}°C, critical: {:?})",
+                record.label,
+                record.temperature,
+                record.max,
+                record.critical
+            );
+            output::emit("system.component", &record);
         }
         system
     }
 
     /// Print the disks information.
     fn print_disks_info(&mut self, system: System) -> System {
-        println!("\n{}", "Disks information:".green());
-        let disks = Disks::new_with_refreshed_list();
-        for disk in &disks {
-            println!("{:?}", disk);
+        human_println!("\n{}", "Disks information:".green());
+        for record in disks() {
+            human_println!(
+                "{} ({}) mounted on {}: {} bytes available of {} bytes, {}{}",
+                record.name,
+                record.file_system,
+                record.mount_point,
+                record.available_space,
+                record.total_space,
+                record.kind,
+                if record.removable { ", removable" } else { "" }
+            );
+            output::emit("system.disk", &record);
         }
         system
     }
 
     /// Print the RAM and SWAP information.
     fn print_memory_information(&mut self, system: System) -> System {
-        println!("\n{}", "Memory information:".green());
-        println!("Total memory: {} KB", system.total_memory());
-        println!("Used memory : {} KB", system.used_memory());
-        println!("Total swap  : {} KB", system.total_swap());
-        println!("Used swap   : {} KB", system.used_swap());
+        let record = memory(&system);
+        human_println!("\n{}", "Memory information:".green());
+        human_println!("Total memory: {} bytes", record.total_memory);
+        human_println!("Used memory : {} bytes", record.used_memory);
+        human_println!("Total swap  : {} bytes", record.total_swap);
+        human_println!("Used swap   : {} bytes", record.used_swap);
+        output::emit("system.memory", &record);
         system
     }
 